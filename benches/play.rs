@@ -0,0 +1,65 @@
+//! Timings for the parts of a game that look words up, using the word lists the web frontend
+//! uses. Run with `cargo bench`, before and after a change, to see what it does to `play()` and
+//! to working out `max_score()`. The last timing compares looking words up in an `AnswerSet`,
+//! which holds the answers and the words found so far, with a `BTreeSet<String>`, which is what
+//! held them before: each `AnswerSet` lookup hashes the word once, where the `BTreeSet` compares
+//! it with a word at every step down the tree.
+
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use spelling_bee::answers::AnswerSet;
+use spelling_bee::SpellingBeeGame;
+
+const WORDS: &str = include_str!("../www/2of12inf.txt");
+const SWEARS: &str = include_str!("../www/swears.txt");
+
+/// How many times each timing is repeated.
+const ROUNDS: u32 = 200;
+
+/// Returns the average time taken to run `f`, over `ROUNDS` runs.
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let game = SpellingBeeGame::new("cglorw", "i", WORDS, SWEARS);
+    let answers: Vec<&str> = game.answers().iter().collect();
+    // Every answer twice, so that half the plays look through the words found so far, and some
+    // words that aren't answers.
+    let plays: Vec<&str> = answers.iter().chain(&answers).copied()
+        .chain(vec!["grog", "cowl", "crowl", "rigor"])
+        .collect();
+
+    let played = time(|| {
+        let mut game = game.clone();
+        for word in &plays {
+            black_box(game.play_at(word, 0.0));
+        }
+    });
+    println!("play() x {:<6} {:>10.1?}", plays.len(), played);
+
+    // The maximum score is worked out again whenever the answers change.
+    let scored = time(|| {
+        let mut game = game.clone();
+        black_box(game.load_swears(""));
+        black_box(game.max_score());
+    });
+    println!("max_score()     {:>10.1?}", scored);
+
+    let words: Vec<&str> = WORDS.lines().map(str::trim).collect();
+    let set: AnswerSet = words.iter().collect();
+    let tree: BTreeSet<String> = words.iter().map(|w| w.to_string()).collect();
+    let in_set = time(|| {
+        black_box(plays.iter().filter(|w| set.contains(w)).count());
+    });
+    let in_tree = time(|| {
+        black_box(plays.iter().filter(|w| tree.contains(**w)).count());
+    });
+    println!("contains() x {:<3} {:>10.1?}, with a BTreeSet {:.1?}", plays.len(), in_set,
+             in_tree);
+}
@@ -0,0 +1,97 @@
+//! This module provides `Accuracy`, a comparison of a game's answers with a puzzle's official answer
+//! list. The words the lexicon accepts that the official list doesn't are false positives, and the
+//! official answers the lexicon misses are false negatives: together they show which words to add
+//! to or remove from the wordlist to get closer to the official puzzles.
+
+use crate::answers::AnswerSet;
+
+/// How a game's answers compare with an official answer list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Accuracy {
+    /// The number of answers in both lists.
+    pub matched: usize,
+
+    /// The game's answers that aren't official answers, in alphabetical order.
+    pub false_positives: Vec<String>,
+
+    /// The official answers that aren't answers in the game, in alphabetical order.
+    pub false_negatives: Vec<String>,
+}
+
+impl Accuracy {
+    /// Compares a game's answers with the official ones.
+    pub fn compare(answers: &AnswerSet, official: &AnswerSet) -> Accuracy {
+        Accuracy {
+            matched: answers.intersection(official).count(),
+            false_positives: answers.difference(official).map(String::from).collect(),
+            false_negatives: official.difference(answers).map(String::from).collect(),
+        }
+    }
+
+    /// Returns the fraction of the game's answers that are official, or 1 if the game has no
+    /// answers.
+    pub fn precision(&self) -> f64 {
+        ratio(self.matched, self.matched + self.false_positives.len())
+    }
+
+    /// Returns the fraction of the official answers that the game accepts, or 1 if there are no
+    /// official answers.
+    pub fn recall(&self) -> f64 {
+        ratio(self.matched, self.matched + self.false_negatives.len())
+    }
+
+    /// Returns `true` if the two answer lists are the same.
+    pub fn is_exact(&self) -> bool {
+        self.false_positives.is_empty() && self.false_negatives.is_empty()
+    }
+
+    /// Summarizes the comparison in plain text, listing at most `examples` words of each kind.
+    pub fn summary(&self, examples: usize) -> String {
+        let mut text = format!("Matched: {}\nPrecision: {:.1}%\nRecall: {:.1}%\n", self.matched,
+                               self.precision() * 100.0, self.recall() * 100.0);
+        for (label, words) in [("False positives", &self.false_positives),
+                               ("False negatives", &self.false_negatives)] {
+            text.push_str(&format!("{}: {}", label, words.len()));
+            if !words.is_empty() && examples > 0 {
+                let shown: Vec<&str> = words.iter().take(examples).map(String::as_str).collect();
+                text.push_str(&format!(" ({}", shown.join(", ")));
+                if words.len() > examples {
+                    text.push_str(", ...");
+                }
+                text.push(')');
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Returns `n / total`, or 1 if `total` is 0.
+fn ratio(n: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        n as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let answers: AnswerSet = ["coil", "cowgirl", "gigollo", "will"].iter().collect();
+        let official: AnswerSet = ["coil", "cowgirl", "logic", "will"].iter().collect();
+        let accuracy = Accuracy::compare(&answers, &official);
+
+        assert_eq!(accuracy.matched, 3);
+        assert_eq!(accuracy.false_positives, vec!["gigollo"]);
+        assert_eq!(accuracy.false_negatives, vec!["logic"]);
+        assert_eq!(accuracy.precision(), 0.75);
+        assert!(!accuracy.is_exact());
+        assert_eq!(accuracy.summary(1),
+                   "Matched: 3\nPrecision: 75.0%\nRecall: 75.0%\nFalse positives: 1 (gigollo)\n\
+                    False negatives: 1 (logic)\n");
+    }
+}
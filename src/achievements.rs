@@ -0,0 +1,215 @@
+//! This module provides `Achievement`, the milestones a player can reach in a game, like finding a
+//! pangram or reaching Queen Bee, and `AchievementLog`, a record of which ones a player has
+//! reached in which puzzles.
+//!
+//! Achievements are earned from a game's state, not from how it got there, so recording the same
+//! game again, or a game with more words found, only adds the achievements that are new.
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::rank::Rank;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::{js_array, letter_count};
+
+/// The fewest letters a word needs for `Achievement::LongWord`.
+pub const LONG_WORD_LENGTH: usize = 10;
+
+/// A milestone a player can reach in a game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Achievement {
+    /// Found a pangram.
+    Pangram,
+
+    /// Found a pangram that uses every letter exactly once.
+    PerfectPangram,
+
+    /// Found a word of at least `LONG_WORD_LENGTH` letters.
+    LongWord,
+
+    /// Found a word starting with each of the puzzle's letters.
+    Bingo,
+
+    /// Reached Genius.
+    Genius,
+
+    /// Found every answer.
+    QueenBee,
+}
+
+impl fmt::Display for Achievement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+impl Achievement {
+    /// Every achievement.
+    pub const ALL: [Achievement; 6] = [
+        Achievement::Pangram, Achievement::PerfectPangram, Achievement::LongWord,
+        Achievement::Bingo, Achievement::Genius, Achievement::QueenBee,
+    ];
+
+    /// Returns the short name used for the achievement in logs and JavaScript.
+    pub fn key(self) -> &'static str {
+        match self {
+            Achievement::Pangram => "pangram",
+            Achievement::PerfectPangram => "perfect_pangram",
+            Achievement::LongWord => "long_word",
+            Achievement::Bingo => "bingo",
+            Achievement::Genius => "genius",
+            Achievement::QueenBee => "queen_bee",
+        }
+    }
+
+    /// Returns the achievement with the given key, if there is one.
+    pub fn from_key(key: &str) -> Option<Achievement> {
+        Achievement::ALL.iter().copied().find(|a| a.key() == key)
+    }
+
+    /// Returns the achievement's name in English.
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::Pangram => "Pangram",
+            Achievement::PerfectPangram => "Perfect Pangram",
+            Achievement::LongWord => "Long Word",
+            Achievement::Bingo => "Bingo",
+            Achievement::Genius => "Genius",
+            Achievement::QueenBee => "Queen Bee",
+        }
+    }
+
+    /// Returns `true` if the game has reached the achievement.
+    pub fn is_reached(self, game: &SpellingBeeGame) -> bool {
+        let found = game.played_words();
+        match self {
+            Achievement::Pangram => found.iter().any(|w| game.is_pangram(w)),
+            Achievement::PerfectPangram => found.iter().any(|w| game.is_perfect_pangram(w)),
+            Achievement::LongWord => found.iter().any(|w| letter_count(w) >= LONG_WORD_LENGTH),
+            Achievement::Bingo => game.has_bingo(),
+            Achievement::Genius => game.rank() >= Rank::Genius,
+            Achievement::QueenBee => game.rank() == Rank::QueenBee,
+        }
+    }
+}
+
+/// Returns the achievements the game has reached, in the order of `Achievement::ALL`.
+pub fn reached(game: &SpellingBeeGame) -> Vec<Achievement> {
+    Achievement::ALL.iter().copied().filter(|a| a.is_reached(game)).collect()
+}
+
+/// The achievements a player has reached, across games.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AchievementLog {
+    /// Each puzzle id and achievement reached in it, in the order they were recorded.
+    entries: Vec<(String, Achievement)>,
+}
+
+#[wasm_bindgen]
+impl AchievementLog {
+    /// Creates an empty log.
+    pub fn new() -> AchievementLog {
+        AchievementLog::default()
+    }
+
+    /// Records the achievements reached in a game of the puzzle with the given id, and returns
+    /// the keys of the ones that hadn't been recorded for it before, as an array of strings.
+    pub fn record(&mut self, puzzle_id: &str, game: &SpellingBeeGame) -> JsValue {
+        js_array(self.record_game(puzzle_id, game).iter().map(|a| a.key()))
+    }
+
+    /// Returns `true` if the achievement with the given key has been reached in any puzzle.
+    pub fn is_unlocked(&self, key: &str) -> bool {
+        self.count(key) > 0
+    }
+
+    /// Returns the number of puzzles the achievement with the given key has been reached in.
+    pub fn count(&self, key: &str) -> usize {
+        self.entries.iter().filter(|(_, a)| a.key() == key).count()
+    }
+
+    /// Returns the keys of the achievements reached in any puzzle, as an array of strings in the
+    /// order of `Achievement::ALL`.
+    pub fn unlocked_keys(&self) -> JsValue {
+        js_array(self.unlocked().iter().map(|a| a.key()))
+    }
+
+    /// Returns the number of entries in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing has been reached yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encodes the log as text, with a puzzle id and achievement key separated by a tab on each
+    /// line.
+    pub fn encode(&self) -> String {
+        self.entries.iter().map(|(id, a)| format!("{}\t{}\n", id, a)).collect()
+    }
+
+    /// Decodes a log written by `encode()`, returning `None` if any line isn't a valid entry.
+    pub fn decode(text: &str) -> Option<AchievementLog> {
+        let entries = text.lines().map(|line| {
+            let (id, key) = line.split_once('\t')?;
+            Some((id.to_string(), Achievement::from_key(key)?))
+        }).collect::<Option<Vec<(String, Achievement)>>>()?;
+        Some(AchievementLog { entries })
+    }
+}
+
+impl AchievementLog {
+    /// Records the achievements reached in a game like `record()`, returning the new ones.
+    pub fn record_game(&mut self, puzzle_id: &str, game: &SpellingBeeGame) -> Vec<Achievement> {
+        let new: Vec<Achievement> = reached(game).into_iter()
+            .filter(|&a| !self.entries.iter().any(|(id, b)| id == puzzle_id && *b == a))
+            .collect();
+        self.entries.extend(new.iter().map(|&a| (puzzle_id.to_string(), a)));
+        new
+    }
+
+    /// Returns the achievements reached in any puzzle, in the order of `Achievement::ALL`.
+    pub fn unlocked(&self) -> Vec<Achievement> {
+        Achievement::ALL.iter()
+            .copied()
+            .filter(|a| self.entries.iter().any(|(_, b)| b == a))
+            .collect()
+    }
+
+    /// Returns the achievements reached in the puzzle with the given id, in the order they were
+    /// recorded.
+    pub fn for_puzzle(&self, puzzle_id: &str) -> Vec<Achievement> {
+        self.entries.iter().filter(|(id, _)| id == puzzle_id).map(|&(_, a)| a).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achievements() {
+        let answers = "will\ncowgirl\ncoil\ngrill\nlogic\nroil\nigloo\nolio\n";
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", answers);
+        let mut log = AchievementLog::new();
+        game.play("cowgirl");
+        assert_eq!(log.record_game("2024-05-06", &game),
+                   [Achievement::Pangram, Achievement::PerfectPangram]);
+        assert_eq!(log.record_game("2024-05-06", &game), []);
+
+        for word in ["will", "grill", "logic", "roil", "igloo", "olio"].iter() {
+            game.play(word);
+        }
+        assert_eq!(log.record_game("2024-05-06", &game), [Achievement::Bingo, Achievement::Genius]);
+        assert!(!Achievement::LongWord.is_reached(&game));
+        assert_eq!(log.count("pangram"), 1);
+        assert_eq!(AchievementLog::decode(&log.encode()), Some(log.clone()));
+        assert_eq!(AchievementLog::decode("2024-05-06\tnothing\n"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! This module provides `PlayerStats`, a record of which kinds of answers a player tends to find
+//! and which they tend to miss, and `adaptive_hints()`, which uses it to point hints at the
+//! player's blind spots: if they usually miss seven-letter words, or words starting with `q`,
+//! hints about those come first.
+
+use std::collections::BTreeMap;
+use crate::hints::Hint;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::{letters, letter_count};
+
+/// How many answers of some kind a player has found, out of how many there were.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Tally {
+    /// The number found.
+    pub found: usize,
+
+    /// The number there were.
+    pub total: usize,
+}
+
+impl Tally {
+    /// Returns the fraction of answers missed, or 0 if there haven't been any.
+    pub fn miss_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.total - self.found.min(self.total)) as f64 / self.total as f64
+        }
+    }
+}
+
+/// A player's history of finding and missing answers, by length and first letter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PlayerStats {
+    /// The tally for each answer length.
+    by_length: BTreeMap<usize, Tally>,
+
+    /// The tally for each first letter.
+    by_first_letter: BTreeMap<String, Tally>,
+}
+
+impl PlayerStats {
+    /// Creates an empty history.
+    pub fn new() -> PlayerStats {
+        PlayerStats::default()
+    }
+
+    /// Adds a game's answers to the history, counting the ones that weren't found as missed. This
+    /// should be called once per game, after the player is done with it.
+    pub fn record(&mut self, game: &SpellingBeeGame) {
+        let found = game.played_words();
+        for word in game.answers() {
+            let was_found = found.contains(word);
+            let count = |tally: &mut Tally| {
+                tally.total += 1;
+                if was_found {
+                    tally.found += 1;
+                }
+            };
+            count(self.by_length.entry(letter_count(word)).or_default());
+            if let Some(first) = letters(word).first() {
+                count(self.by_first_letter.entry(first.to_string()).or_default());
+            }
+        }
+    }
+
+    /// Returns the tally for answers of the given length.
+    pub fn length_tally(&self, length: usize) -> Tally {
+        self.by_length.get(&length).copied().unwrap_or_default()
+    }
+
+    /// Returns the tally for answers starting with the given letter.
+    pub fn first_letter_tally(&self, letter: &str) -> Tally {
+        self.by_first_letter.get(letter).copied().unwrap_or_default()
+    }
+
+    /// Returns how likely the player is to miss the word, judging by how often they've missed
+    /// answers of its length and answers with its first letter.
+    pub fn miss_chance(&self, word: &str) -> f64 {
+        let first = letters(word).first().map_or(Tally::default(),
+                                                 |l| self.first_letter_tally(l));
+        (self.length_tally(letter_count(word)).miss_rate() + first.miss_rate()) / 2.0
+    }
+}
+
+/// Returns hints about every answer that hasn't been found yet, starting with the kinds the
+/// player most often misses. Answers the history rates the same are in the usual easiest-first
+/// order (see `SpellingBeeGame::hints()`).
+pub fn adaptive_hints(game: &SpellingBeeGame, stats: &PlayerStats) -> Vec<Hint> {
+    let mut missing = game.missing_easiest_first();
+    // The sort is stable, so that ties keep their easiest-first order.
+    missing.sort_by(|a, b| stats.miss_chance(b).total_cmp(&stats.miss_chance(a)));
+    missing.into_iter().map(Hint::for_word).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_hints() {
+        let mut past = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\nlogic\n");
+        past.play("will");
+        past.play("logic");
+        let mut stats = PlayerStats::new();
+        stats.record(&past);
+        assert_eq!(stats.length_tally(7), Tally { found: 0, total: 1 });
+        assert_eq!(stats.first_letter_tally("c").miss_rate(), 1.0);
+
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "coil\nwill\nlogic\ncowgirl\n");
+        let hints: Vec<(String, usize)> = adaptive_hints(&game, &stats).iter()
+            .map(|h| (h.start(), h.length()))
+            .collect();
+        assert_eq!(hints[0], ("co".to_string(), 7));
+        assert_eq!(hints[1], ("co".to_string(), 4));
+    }
+}
@@ -0,0 +1,171 @@
+//! This module provides `Alphabet`, the letters a language's puzzles are made of, so that games in
+//! languages like German and Spanish can check their letters against the right alphabet, with ß
+//! and ñ as letters of their own.
+//!
+//! An alphabet also knows the accented forms of its letters, like é for e. By default, those are
+//! letters of their own too, so a word with é is only an answer to a puzzle with é. With accent
+//! folding on, an accented form counts as its base letter instead: "canción" is an answer to a
+//! puzzle with c, a, n, i, and o, and the player can type it either way.
+
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::translit::Transliteration;
+use crate::utils::{js_array, letters};
+
+/// The letters from `a` to `z`, which every built-in alphabet starts with.
+const LATIN: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// A language code, the letters its alphabet has beyond `LATIN`, and the accented forms that fold
+/// to each base letter.
+type BuiltIn = (&'static str, &'static str, &'static [(char, char)]);
+
+/// The built-in alphabets.
+const BUILT_IN: &[BuiltIn] = &[
+    ("en", "", &[]),
+    ("de", "äöüß", &[('ä', 'a'), ('ö', 'o'), ('ü', 'u')]),
+    ("es", "ñ", &[('á', 'a'), ('é', 'e'), ('í', 'i'), ('ó', 'o'), ('ú', 'u'), ('ü', 'u')]),
+    ("fr", "", &[('à', 'a'), ('â', 'a'), ('ç', 'c'), ('é', 'e'), ('è', 'e'), ('ê', 'e'),
+                 ('ë', 'e'), ('î', 'i'), ('ï', 'i'), ('ô', 'o'), ('ù', 'u'), ('û', 'u'),
+                 ('ü', 'u'), ('ÿ', 'y')]),
+    ("it", "", &[('à', 'a'), ('è', 'e'), ('é', 'e'), ('ì', 'i'), ('ò', 'o'), ('ù', 'u')]),
+    ("pt", "", &[('á', 'a'), ('â', 'a'), ('ã', 'a'), ('à', 'a'), ('ç', 'c'), ('é', 'e'),
+                 ('ê', 'e'), ('í', 'i'), ('ó', 'o'), ('ô', 'o'), ('õ', 'o'), ('ú', 'u')]),
+];
+
+/// A language's letters and the accented forms of them, passed to
+/// `SpellingBeeGame::new_with_alphabet()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Alphabet {
+    /// The locale whose case rules are used to lowercase letters and words.
+    language: String,
+
+    /// The letters puzzles can be made of, each a grapheme cluster.
+    letters: BTreeSet<String>,
+
+    /// The base letter of each accented form.
+    variants: BTreeMap<String, String>,
+
+    /// Whether accented forms count as their base letters.
+    fold_accents: bool,
+}
+
+#[wasm_bindgen]
+impl Alphabet {
+    /// Creates an alphabet for the given locale with the given letters, which are used as they
+    /// are, without lowercasing, and no accented forms.
+    pub fn new(language: &str, alphabet: &str) -> Alphabet {
+        Alphabet {
+            language: language.to_string(),
+            letters: letters(alphabet).into_iter().map(String::from).collect(),
+            variants: BTreeMap::new(),
+            fold_accents: false,
+        }
+    }
+
+    /// Returns the built-in alphabet for the given language code, like `es` or `de-CH`, if
+    /// there is one: English, German, Spanish, French, Italian, or Portuguese. Accent folding
+    /// starts off.
+    pub fn for_language(language: &str) -> Option<Alphabet> {
+        let code = language.split(['-', '_']).next()?.to_lowercase();
+        let &(_, extra, variants) = BUILT_IN.iter().find(|(l, _, _)| *l == code)?;
+        let mut alphabet = Alphabet::new(language, &(LATIN.to_string() + extra));
+        for &(variant, base) in variants {
+            alphabet.add_variant(&variant.to_string(), &base.to_string());
+        }
+        Some(alphabet)
+    }
+
+    /// Returns the locale whose case rules are used to lowercase letters and words.
+    pub fn language(&self) -> String {
+        self.language.clone()
+    }
+
+    /// Returns the letters puzzles can be made of, as an array of strings in sorted order.
+    /// Accented forms that fold to another letter aren't included while accent folding is on.
+    pub fn letters(&self) -> JsValue {
+        js_array(self.letters.iter().filter(|l| self.is_puzzle_letter(l)))
+    }
+
+    /// Returns `true` if the letter can be one of a puzzle's letters: it's in the alphabet, and
+    /// it isn't an accented form that folds to another letter.
+    pub fn is_puzzle_letter(&self, letter: &str) -> bool {
+        self.letters.contains(letter) && !(self.fold_accents && self.variants.contains_key(letter))
+    }
+
+    /// Makes the given letter an accented form of the base letter, for accent folding.
+    pub fn add_variant(&mut self, variant: &str, base: &str) {
+        self.variants.insert(variant.to_string(), base.to_string());
+    }
+
+    /// Returns `true` if accented forms count as their base letters.
+    pub fn fold_accents(&self) -> bool {
+        self.fold_accents
+    }
+
+    /// Sets whether accented forms count as their base letters.
+    pub fn set_fold_accents(&mut self, fold: bool) {
+        self.fold_accents = fold;
+    }
+
+    /// Returns the letter that the given one counts as: its base letter if it's an accented form
+    /// and accent folding is on, and otherwise the letter itself.
+    pub fn base_letter(&self, letter: &str) -> String {
+        match self.variants.get(letter) {
+            Some(base) if self.fold_accents => base.clone(),
+            _ => letter.to_string(),
+        }
+    }
+}
+
+impl Alphabet {
+    /// Spells a word with the letters each of its letters counts as (see `base_letter()`).
+    pub fn fold(&self, word: &str) -> String {
+        letters(word).into_iter().map(|l| self.base_letter(l)).collect()
+    }
+
+    /// Returns the letters that count as any of the given letters, including themselves.
+    pub fn forms_of<'a>(&'a self, base_letters: &'a [&str]) -> impl Iterator<Item = &'a str> {
+        let variants = self.variants.iter()
+            .filter(move |(_, base)| self.fold_accents && base_letters.contains(&base.as_str()))
+            .map(|(variant, _)| variant.as_str());
+        base_letters.iter().copied().chain(variants)
+    }
+
+    /// Returns a transliteration that lets players type the base letters for accented forms, if
+    /// accent folding is on and there are any.
+    pub fn transliteration(&self) -> Option<Transliteration> {
+        if !self.fold_accents || self.variants.is_empty() {
+            return None;
+        }
+        let table: String = self.variants.iter()
+            .map(|(variant, base)| format!("{}\t{}\n", variant, base))
+            .collect();
+        Some(Transliteration::from_table(&table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabet() {
+        let mut spanish = Alphabet::for_language("es-MX").unwrap();
+        assert!(spanish.is_puzzle_letter("ñ") && spanish.is_puzzle_letter("a"));
+        assert!(!spanish.is_puzzle_letter("ß"));
+        assert_eq!(spanish.fold("canción"), "canción");
+        spanish.set_fold_accents(true);
+        assert_eq!(spanish.fold("canción"), "cancion");
+        assert_eq!(spanish.forms_of(&["o", "n"]).collect::<Vec<&str>>(), ["o", "n", "ó"]);
+
+        let mut german = Alphabet::for_language("de").unwrap();
+        german.set_fold_accents(true);
+        assert!(german.is_puzzle_letter("ß") && !german.is_puzzle_letter("ä"));
+        assert_eq!(german.base_letter("ü"), "u");
+        assert_eq!(Alphabet::for_language("xx"), None);
+    }
+}
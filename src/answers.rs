@@ -0,0 +1,312 @@
+//! This module provides `AnswerSet`, the sorted set of words a game keeps its answers in. Rather
+//! than a `String` for each answer, each a separate allocation with its own bookkeeping, the
+//! answers are stored back to back in one buffer along with where each one ends, so that a large
+//! answer list is two allocations and about half the size, and doesn't fragment WebAssembly's
+//! memory. Next to them is a small hash table of places, so that `contains()` hashes the word once
+//! and compares it with about one answer, rather than with a string at every step of a binary
+//! search.
+//!
+//! Adding or removing a word moves the words after it along the buffer and rebuilds the table, so
+//! many changes at once are made in batches, with `extend()` and `retain()`. A game's answers only
+//! change when it's created, or when an overlay, layout, or swears list is applied. The words
+//! found so far are added one at a time, but are few and short enough that this is cheap.
+
+use std::iter::{FromIterator, Peekable};
+use std::mem::size_of;
+use crate::utils::fnv1a;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A sorted set of words, stored in a single buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize),
+           serde(from = "Vec<String>", into = "Vec<String>"))]
+pub struct AnswerSet {
+    /// The words, in sorted order, one after another.
+    buffer: String,
+
+    /// Where each word ends in `buffer`.
+    ends: Vec<u32>,
+
+    /// An open-addressing hash table of the words, with one more than each word's place, or 0
+    /// for an empty slot. Its length is a power of two at least twice the number of words, or 0
+    /// for an empty set.
+    slots: Vec<u32>,
+}
+
+impl AnswerSet {
+    /// Creates an empty set.
+    pub fn new() -> AnswerSet {
+        AnswerSet::default()
+    }
+
+    /// Returns the number of words.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns `true` if the word is in the set.
+    pub fn contains(&self, word: &str) -> bool {
+        self.position(word).is_some()
+    }
+
+    /// Returns the place of the word in sorted order, if it's in the set.
+    pub fn position(&self, word: &str) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mask = self.slots.len() - 1;
+        let mut slot = hash(word) & mask;
+        loop {
+            match self.slots[slot] as usize {
+                0 => return None,
+                place if self.get(place - 1) == word => return Some(place - 1),
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+    }
+
+    /// Returns the words in sorted order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { set: self, front: 0, back: self.len() }
+    }
+
+    /// Returns the words that sort at or after the given one, in sorted order.
+    pub fn iter_from(&self, word: &str) -> Iter<'_> {
+        let front = self.search(word).unwrap_or_else(|i| i);
+        Iter { set: self, front, back: self.len() }
+    }
+
+    /// Adds a word, returning `true` if it wasn't already in the set.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let i = match self.search(word) {
+            Ok(_) => return false,
+            Err(i) => i,
+        };
+        let start = self.start(i);
+        self.buffer.insert_str(start, word);
+        self.ends.insert(i, start as u32);
+        for end in &mut self.ends[i..] {
+            *end += word.len() as u32;
+        }
+        self.index();
+        true
+    }
+
+    /// Removes a word, returning `true` if it was in the set.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let i = match self.search(word) {
+            Ok(i) => i,
+            Err(_) => return false,
+        };
+        let start = self.start(i);
+        self.buffer.replace_range(start..self.ends[i] as usize, "");
+        self.ends.remove(i);
+        for end in &mut self.ends[i..] {
+            *end -= word.len() as u32;
+        }
+        self.index();
+        true
+    }
+
+    /// Returns the words that aren't in the other set, in sorted order. Both sets are sorted, so
+    /// this walks through them side by side rather than looking up each word.
+    pub fn difference<'a>(&'a self, other: &'a AnswerSet) -> impl Iterator<Item = &'a str> + 'a {
+        let mut others = other.iter().peekable();
+        self.iter().filter(move |w| !skip_to(&mut others, w))
+    }
+
+    /// Returns the words that are also in the other set, in sorted order, like `difference()`.
+    pub fn intersection<'a>(&'a self,
+                            other: &'a AnswerSet) -> impl Iterator<Item = &'a str> + 'a {
+        let mut others = other.iter().peekable();
+        self.iter().filter(move |w| skip_to(&mut others, w))
+    }
+
+    /// Keeps only the words the predicate is `true` for.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        let kept: AnswerSet = self.iter().filter(|w| keep(w)).collect();
+        *self = kept;
+    }
+
+    /// Returns the estimated bytes the words take up: the buffer, the end of each word, and the
+    /// hash table.
+    pub fn heap_bytes(&self) -> usize {
+        self.buffer.capacity() + (self.ends.capacity() + self.slots.capacity()) * size_of::<u32>()
+    }
+
+    /// Returns the word at the given place in sorted order.
+    fn get(&self, i: usize) -> &str {
+        &self.buffer[self.start(i)..self.ends[i] as usize]
+    }
+
+    /// Returns where the word at the given place starts in `buffer`.
+    fn start(&self, i: usize) -> usize {
+        if i == 0 { 0 } else { self.ends[i - 1] as usize }
+    }
+
+    /// Rebuilds the hash table from the words.
+    fn index(&mut self) {
+        self.slots.clear();
+        if self.is_empty() {
+            return;
+        }
+        self.slots.resize((2 * self.len()).next_power_of_two(), 0);
+        let mask = self.slots.len() - 1;
+        for place in 0..self.len() {
+            let mut slot = hash(self.get(place)) & mask;
+            while self.slots[slot] != 0 {
+                slot = (slot + 1) & mask;
+            }
+            self.slots[slot] = place as u32 + 1;
+        }
+    }
+
+    /// Finds the place of a word in sorted order, like `slice::binary_search()`.
+    fn search(&self, word: &str) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid).cmp(word) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+}
+
+/// Hashes a word for the hash table.
+fn hash(word: &str) -> usize {
+    fnv1a(word.bytes()) as usize
+}
+
+/// Moves a sorted iterator past the words before the given one, returning `true` if the word is
+/// next.
+fn skip_to(words: &mut Peekable<Iter>, word: &str) -> bool {
+    while words.peek().is_some_and(|w| *w < word) {
+        words.next();
+    }
+    words.peek() == Some(&word)
+}
+
+impl<S: AsRef<str>> FromIterator<S> for AnswerSet {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> AnswerSet {
+        let mut words: Vec<S> = words.into_iter().collect();
+        words.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        words.dedup_by(|a, b| a.as_ref() == b.as_ref());
+
+        let mut buffer = String::with_capacity(words.iter().map(|w| w.as_ref().len()).sum());
+        let mut ends = Vec::with_capacity(words.len());
+        for word in &words {
+            buffer.push_str(word.as_ref());
+            ends.push(buffer.len() as u32);
+        }
+        let mut set = AnswerSet { buffer, ends, slots: Vec::new() };
+        set.index();
+        set
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for AnswerSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        let words: Vec<S> = words.into_iter().collect();
+        let merged: AnswerSet = self.iter().chain(words.iter().map(|w| w.as_ref())).collect();
+        *self = merged;
+    }
+}
+
+impl From<Vec<String>> for AnswerSet {
+    fn from(words: Vec<String>) -> AnswerSet {
+        words.into_iter().collect()
+    }
+}
+
+impl From<AnswerSet> for Vec<String> {
+    fn from(set: AnswerSet) -> Vec<String> {
+        set.iter().map(String::from).collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a AnswerSet {
+    type Item = &'a str;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the words of an `AnswerSet`, in sorted order.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    /// The set.
+    set: &'a AnswerSet,
+
+    /// The place of the next word from the front.
+    front: usize,
+
+    /// The place after the next word from the back.
+    back: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.set.get(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.set.get(self.back))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_set() {
+        let mut set: AnswerSet = ["will", "coil", "cowgirl", "will"].iter().collect();
+        assert_eq!(set.iter().collect::<Vec<&str>>(), ["coil", "cowgirl", "will"]);
+        assert!(set.contains("cowgirl") && !set.contains("cow"));
+        assert_eq!((set.position("will"), AnswerSet::new().position("will")), (Some(2), None));
+        assert_eq!(set.iter_from("cow").next(), Some("cowgirl"));
+        assert_eq!(set.iter().next_back(), Some("will"));
+
+        set.extend(vec!["grill", "coil"]);
+        set.retain(|w| w != "will");
+        assert_eq!(Vec::from(set.clone()), ["coil", "cowgirl", "grill"]);
+        let mut found = AnswerSet::new();
+        assert!(found.insert("grill") && found.insert("coil") && !found.insert("coil"));
+        assert!(found.insert("wig") && found.remove("wig") && !found.remove("wig"));
+        assert_eq!(found.iter().collect::<Vec<&str>>(), ["coil", "grill"]);
+        assert_eq!(set.difference(&found).collect::<Vec<&str>>(), ["cowgirl"]);
+        assert_eq!(set.intersection(&found).collect::<Vec<&str>>(), ["coil", "grill"]);
+        assert!(found.contains("coil") && !found.contains("wig"));
+        assert!(set.heap_bytes() >= "coilcowgirlgrill".len() + 3 * size_of::<u32>());
+    }
+}
@@ -0,0 +1,1416 @@
+//! This module provides the HTTP API served by `spellingbee-server`, which lets clients that
+//! can't run Rust or WebAssembly, like chat bots and mobile apps, play games with the same
+//! validation and scoring as the other frontends. It is only built natively, with the `http-api`
+//! feature.
+//!
+//! Requests and responses are JSON, with the bodies in this module. A game is created from its
+//! letters, with answers from the server's word lists, and is then referred to by the id in its
+//! state:
+//!
+//! ```text
+//! POST    /sessions          {"name": "ana"}                        the new session
+//! POST    /games             {"letters": "cglorw", "center": "i"}   the new game's state
+//! GET     /games/ID                                                 the game's state
+//! POST    /games/ID/words    {"word": "cowgirl"}                    the result of the play
+//! GET     /games/ID/hints                                           the hint grid and pairs
+//! POST    /games/ID/finish                                          the game's state
+//! DELETE  /games/ID                                                 nothing
+//! GET     /leaderboard/PUZZLE                                       the puzzle's leaderboard
+//! GET     /openapi.json                                             the OpenAPI document
+//! POST    /graphql           {"query": "{ game(id: 1) { score } }"} the query's result
+//! ```
+//!
+//! A game's state has its `id`, `letters`, `center`, `score`, `max_score`, `rank`, the `words`
+//! found so far in the order they were found, the `answer_count`, and whether it's `finished`.
+//! A play has the `word`, the catalog key of its `result` (see `i18n::play_result_key()`) and its
+//! English `message`, the `points` it earned, whether it's a `pangram`, and the game's new `score`
+//! and `rank`; a play that isn't accepted still succeeds, with no points. Hints have the `grid`,
+//! the number of answers not found yet by starting letter and then by length, and the `pairs`, the
+//! number by their first two letters. Anything that fails has a status other than 200 and an
+//! `error` saying why.
+//!
+//! Players don't need an account, but can start a session (see the `session` module) by giving
+//! a name, and get back the `player` id made up for them, their `name`, and a `token` to send
+//! with later requests, in an `Authorization: Bearer TOKEN` header. A game created with a token
+//! belongs to that player, and any other request for it without their token is refused; a game
+//! created without one is open to anyone. A request with a token that isn't good, because it's
+//! been tampered with or has expired, is refused whatever it's for.
+//!
+//! Finishing a game ends it, so no more words can be played. If the request has a token, the
+//! result is also put on the leaderboard for the game's puzzle, whose id is as from
+//! `PuzzleDef::default_id()`, like `i-cglorw`. A leaderboard has the `puzzle` id and its
+//! `entries`, best first: each has the `player` name and `player_id`, their `score` and `rank`
+//! from the words they found before any answers were revealed, less hint costs, the number of
+//! `words`, the milliseconds they took, as `elapsed`, or `null` if the game didn't keep time, and
+//! the `splits`, each rank they reached with the milliseconds they reached it at, as its `time`.
+//! Ties on score go to whoever was quicker, and then to whoever got there first.
+//!
+//! The times come from the server's own clock as words are played, never from the client, and
+//! each entry's are signed with the session key when it's recorded. An entry whose signature
+//! doesn't check out, because its times were changed in storage or it was recorded under another
+//! key, is shown without them, so it can't win a tie.
+//!
+//! Games are only kept in memory, unless the API is given a `Storage` (see the `storage` module)
+//! with `Api::with_storage()`. Then every change to a game is saved to it, as the id of the player
+//! it belongs to (empty if it's open to anyone) in the string format of `save` followed by the
+//! game's `save_bytes()`, a game that isn't in
+//! memory is loaded from it when there's a request for it, and the leaderboard is kept in it; a
+//! server without storage doesn't keep a leaderboard.
+//!
+//! The server only keeps `DEFAULT_MAX_GAMES` games in memory, unless told otherwise: creating or
+//! loading one more drops the game that has gone longest without a request, which is then gone
+//! for good unless there's storage to load it back from. Bodies longer than `MAX_BODY_BYTES`
+//! are refused.
+//!
+//! So that a hosted puzzle can't be solved by trying every word in a dictionary, plays are rate
+//! limited with a token bucket for each player with a session, and for each client address
+//! otherwise: a client can make `DEFAULT_PLAY_BURST` plays at once, and then
+//! `DEFAULT_PLAYS_PER_SECOND`, unless `Api::set_play_rate()` says otherwise. A play beyond that
+//! is refused with status 429 and a `retry_after`, the milliseconds until the next one is
+//! allowed.
+//!
+//! The OpenAPI document, from `openapi()`, describes the same routes and bodies for clients that
+//! generate code from one. It's generated with utoipa from the handlers requests are routed to,
+//! which are only served at the methods and paths they're documented with, and the bodies' types.
+//!
+//! Clients that want several of those at once, or only a few fields of them, can use the GraphQL
+//! schema (see the `graphql` module) instead, by sending a `query`, with `variables` and an
+//! `operationName` if it has them, and get back its `data` and any `errors`, with status 200
+//! either way. Its queries and mutations do what the routes do, with the bodies in this module as
+//! their types, and games belong to players for them just as for the routes.
+//!
+//! `Api` handles requests without any networking, and `run()` serves it over HTTP.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use async_graphql::{ServerError, SimpleObject, Variables, ID};
+use serde::{Deserialize, Serialize};
+use utoipa::openapi::path::{HttpMethod, Operation};
+use utoipa::openapi::{self, Ref};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi, Path, ToSchema};
+use crate::graphql;
+use crate::hints::two_letter_counts;
+use crate::i18n::{play_result_key, Messages, FALLBACK_LOCALE};
+use crate::lexicon::LexiconHandle;
+use crate::puzzle::PuzzleDef;
+use crate::save::{read_str, write_str};
+use crate::session::{Player, Sessions};
+use crate::spellingbee::SpellingBeeGame;
+use crate::storage::{LeaderboardEntry, Storage, StorageError};
+use crate::utils::now;
+
+/// The number of games kept unless `Api::set_max_games()` says otherwise.
+pub const DEFAULT_MAX_GAMES: usize = 1000;
+
+/// The longest request body accepted, in bytes.
+pub const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// The plays a client can make at once unless `Api::set_play_rate()` says otherwise.
+pub const DEFAULT_PLAY_BURST: f64 = 30.0;
+
+/// The plays a client can make each second, once it has made a burst of them, unless
+/// `Api::set_play_rate()` says otherwise.
+pub const DEFAULT_PLAYS_PER_SECOND: f64 = 1.0;
+
+/// Why a play beyond the rate limit is refused.
+pub(crate) const TOO_MANY_PLAYS: &str = "too many plays; wait a moment";
+
+/// The number of rate limiting buckets kept before those that have filled back up are dropped.
+const MAX_BUCKETS: usize = 10_000;
+
+/// The body of a request to start a session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, ToSchema)]
+pub struct NewSession {
+    /// The name the player goes by.
+    pub name: String,
+}
+
+/// A session that has been started.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+pub struct Session {
+    /// The id made up for the player.
+    pub player: String,
+
+    /// The name the player goes by.
+    pub name: String,
+
+    /// The token to send with later requests.
+    pub token: String,
+}
+
+/// The body of a request to create a game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, ToSchema)]
+pub struct NewGame {
+    /// The optional letters.
+    pub letters: String,
+
+    /// The required letter.
+    pub center: String,
+}
+
+/// The state of a game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+#[graphql(complex)]
+pub struct Game {
+    /// The game's id.
+    #[schema(value_type = String)]
+    pub id: ID,
+
+    /// The optional letters.
+    pub letters: String,
+
+    /// The required letter.
+    pub center: String,
+
+    /// The score so far.
+    pub score: usize,
+
+    /// The score for finding every answer.
+    pub max_score: usize,
+
+    /// The name of the rank reached so far.
+    pub rank: String,
+
+    /// The words found so far, in the order they were found.
+    pub words: Vec<String>,
+
+    /// The number of answers.
+    pub answer_count: usize,
+
+    /// Whether the game is finished, so no more words can be played.
+    pub finished: bool,
+}
+
+/// The body of a request to play a word.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, ToSchema)]
+pub struct NewPlay {
+    /// The word.
+    pub word: String,
+}
+
+/// The result of playing a word.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+pub struct Play {
+    /// The word.
+    pub word: String,
+
+    /// The catalog key of the result, from `i18n::play_result_key()`.
+    pub result: String,
+
+    /// The result, in English.
+    pub message: String,
+
+    /// The points the word earned.
+    pub points: usize,
+
+    /// Whether the word is a pangram.
+    pub pangram: bool,
+
+    /// The game's new score.
+    pub score: usize,
+
+    /// The name of the game's new rank.
+    pub rank: String,
+}
+
+/// The hints for the answers to a game that haven't been found yet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub struct Hints {
+    /// The number of answers by their first letter, and then by their length.
+    pub grid: BTreeMap<String, BTreeMap<usize, usize>>,
+
+    /// The number of answers by their first two letters.
+    pub pairs: BTreeMap<String, usize>,
+}
+
+/// A puzzle's leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+pub struct Leaderboard {
+    /// The puzzle's id, like `i-cglorw`.
+    pub puzzle: String,
+
+    /// The entries, best first.
+    pub entries: Vec<Entry>,
+}
+
+/// A finished game on a leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+pub struct Entry {
+    /// The name of the player who played it.
+    pub player: String,
+
+    /// The id of the player who played it.
+    pub player_id: String,
+
+    /// The score from the words found before any answers were revealed, less hint costs.
+    pub score: usize,
+
+    /// The name of the rank that score reaches.
+    pub rank: String,
+
+    /// The number of words found.
+    pub words: usize,
+
+    /// The milliseconds the game took, or `None` if it didn't keep time or the times weren't
+    /// signed with the server's key.
+    pub elapsed: Option<u64>,
+
+    /// Each rank reached, with when it was reached.
+    pub splits: Vec<Split>,
+}
+
+/// A rank reached in a game on a leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, SimpleObject, ToSchema)]
+pub struct Split {
+    /// The name of the rank.
+    pub rank: String,
+
+    /// The milliseconds into the game it was reached.
+    pub time: u64,
+}
+
+/// The body of a response refusing a play because the client has made too many.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub struct RateLimited {
+    /// Why the play was refused.
+    pub error: String,
+
+    /// The milliseconds until the next play is allowed.
+    pub retry_after: u64,
+}
+
+/// The body of a response to a request that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub struct Failure {
+    /// Why the request failed.
+    pub error: String,
+}
+
+/// The body of a GraphQL request.
+#[derive(Debug, Clone, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequest {
+    /// The query.
+    pub query: String,
+
+    /// The values of the query's variables, by name.
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub variables: serde_json::Value,
+
+    /// The name of the operation to make, if the query has more than one.
+    #[serde(default)]
+    pub operation_name: Option<String>,
+}
+
+/// The result of a GraphQL request.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GraphQLResponse {
+    /// The fields selected, or `null` if the request couldn't be made at all.
+    #[schema(value_type = Object)]
+    pub data: async_graphql::Value,
+
+    /// What went wrong, if anything did, with a `message` and the `path` to the field it went
+    /// wrong for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<Object>)]
+    pub errors: Vec<ServerError>,
+}
+
+/// A request, without the networking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Request<'a> {
+    /// The HTTP method, like `GET`.
+    pub method: &'a str,
+
+    /// The path, which may have a query string.
+    pub path: &'a str,
+
+    /// The body, which is empty if there isn't one.
+    pub body: &'a str,
+
+    /// The session token sent with the request, if there is one.
+    pub token: Option<&'a str>,
+
+    /// The address of the client that made the request, like `127.0.0.1`, if it's known.
+    pub client: Option<&'a str>,
+
+    /// When the request was made, in milliseconds since the Unix epoch.
+    pub now: u64,
+}
+
+/// The response to a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+
+    /// The JSON body, or `None` if there isn't one.
+    pub body: Option<String>,
+}
+
+/// The plays one client can still make, as a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bucket {
+    /// The plays the client can make straight away, which may be fractional.
+    plays: f64,
+
+    /// When `plays` was worked out, in milliseconds since the Unix epoch.
+    at: u64,
+}
+
+impl Response {
+    /// Creates a response with the given status and JSON body.
+    fn json<T: Serialize>(status: u16, body: &T) -> Response {
+        let body = serde_json::to_string(body).expect("the bodies all serialize");
+        Response { status, body: Some(body) }
+    }
+
+    /// Creates a response with the given status and an `error` saying what went wrong.
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, &Failure { error: message.to_string() })
+    }
+
+    /// Creates a response with the given status and body, or refusing the request with the
+    /// status and message it failed with.
+    fn from_result<T: Serialize>(status: u16, result: Result<T, (u16, String)>) -> Response {
+        match result {
+            Ok(body) => Response::json(status, &body),
+            Err((status, message)) => Response::error(status, &message),
+        }
+    }
+}
+
+/// The games being played, and the word lists new games get their answers from.
+pub struct Api {
+    /// Everything requests use and change, which the GraphQL schema's fields are resolved
+    /// against too.
+    state: Arc<Mutex<State>>,
+
+    /// The GraphQL schema queries are made against.
+    schema: graphql::ApiSchema,
+
+    /// The routes requests are handled by.
+    routes: Vec<Route>,
+}
+
+/// Everything requests use and change.
+pub(crate) struct State {
+    /// The word lists new games get their answers from.
+    lexicon: LexiconHandle,
+
+    /// The messages plays are described with.
+    messages: Messages,
+
+    /// The games, by id.
+    games: BTreeMap<u64, SpellingBeeGame>,
+
+    /// The ids of the players the games that belong to someone belong to, by game id.
+    owners: BTreeMap<u64, String>,
+
+    /// When each game last had a request, by id, counted in requests.
+    last_used: BTreeMap<u64, u64>,
+
+    /// The number of requests for games so far.
+    requests: u64,
+
+    /// The most games kept at once.
+    max_games: usize,
+
+    /// The id of the next game to be created.
+    next_game: u64,
+
+    /// Where games and the leaderboard are kept, if anywhere.
+    storage: Option<Box<dyn Storage + Send>>,
+
+    /// The key session tokens are signed with.
+    sessions: Sessions,
+
+    /// The plays a client can make at once.
+    play_burst: f64,
+
+    /// The plays a client can make each second after a burst.
+    plays_per_second: f64,
+
+    /// The plays each client can still make, by player id or client address.
+    buckets: BTreeMap<String, Bucket>,
+}
+
+impl fmt::Debug for Api {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Api")
+            .field("games", &state.games.len())
+            .field("max_games", &state.max_games)
+            .field("next_game", &state.next_game)
+            .field("storage", &state.storage.is_some())
+            .finish()
+    }
+}
+
+impl Api {
+    /// Creates an API without any games, whose games get their answers from the given lexicon.
+    /// Its session tokens are signed with a random key, so they're only good until it's dropped,
+    /// unless `set_session_key()` says otherwise.
+    pub fn new(lexicon: LexiconHandle) -> Api {
+        let state = Arc::new(Mutex::new(State {
+            lexicon,
+            messages: Messages::new(FALLBACK_LOCALE),
+            games: BTreeMap::new(),
+            owners: BTreeMap::new(),
+            last_used: BTreeMap::new(),
+            requests: 0,
+            max_games: DEFAULT_MAX_GAMES,
+            next_game: 1,
+            storage: None,
+            sessions: Sessions::with_random_key(),
+            play_burst: DEFAULT_PLAY_BURST,
+            plays_per_second: DEFAULT_PLAYS_PER_SECOND,
+            buckets: BTreeMap::new(),
+        }));
+        Api { schema: graphql::schema(state.clone()), state, routes: routes() }
+    }
+
+    /// Creates an API like `new()` that keeps its games and leaderboard in the given storage,
+    /// carrying on from the games already in it. Fails if the storage can't be read.
+    pub fn with_storage(lexicon: LexiconHandle,
+                        storage: Box<dyn Storage + Send>) -> Result<Api, StorageError>
+    {
+        let api = Api::new(lexicon);
+        let mut state = api.lock();
+        state.next_game = storage.last_game_id()?.map_or(1, |id| id + 1);
+        state.storage = Some(storage);
+        drop(state);
+        Ok(api)
+    }
+
+    /// Sets the most games kept at once, which is at least one. Games beyond it are dropped
+    /// straight away, those that have gone longest without a request first.
+    pub fn set_max_games(&mut self, max_games: usize) {
+        let mut state = self.lock();
+        state.max_games = max_games.max(1);
+        while state.games.len() > state.max_games {
+            state.evict();
+        }
+    }
+
+    /// Sets the secret key session tokens are signed with, which should be at least 32 random
+    /// bytes. Tokens signed with another key stop being good.
+    pub fn set_session_key(&mut self, key: &[u8]) {
+        self.lock().sessions = Sessions::new(key);
+    }
+
+    /// Sets how many plays a client can make at once, which is at least one, and how many each
+    /// second once it has.
+    pub fn set_play_rate(&mut self, burst: f64, per_second: f64) {
+        let mut state = self.lock();
+        state.play_burst = burst.max(1.0);
+        state.plays_per_second = per_second.max(0.0);
+        state.buckets.clear();
+    }
+
+    /// Returns a copy of the game with the given id, if it's in memory.
+    pub fn game(&self, id: u64) -> Option<SpellingBeeGame> {
+        self.lock().games.get(&id).cloned()
+    }
+
+    /// Handles a request for the given method and path, which may have a query string, with the
+    /// given body, made now without a session token.
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> Response {
+        self.respond(&Request { method, path, body, token: None, client: None, now: now() as u64 })
+    }
+
+    /// Handles a request.
+    pub fn respond(&mut self, request: &Request) -> Response {
+        let checked = request.token.map(|token| self.lock().sessions.check(token, request.now));
+        let player = match checked {
+            Some(Some(player)) => Some(player),
+            Some(None) => return Response::error(401, "the session token isn't good"),
+            None => None,
+        };
+        let path = request.path.split('?').next().unwrap_or_default();
+        let mut allowed = Vec::new();
+        for route in &self.routes {
+            let parameters = match route.parameters(path) {
+                Some(parameters) => parameters,
+                None => continue,
+            };
+            if route.methods.iter().any(|method| method_name(method) == request.method) {
+                return (route.handler)(self, &Call { request, player, parameters });
+            }
+            allowed.extend(route.methods.iter().map(method_name));
+        }
+        match allowed.as_slice() {
+            [] => Response::error(404, "there's nothing at this path"),
+            _ => Response::error(405, &format!("use {} at this path", allowed.join(" or "))),
+        }
+    }
+
+    /// Returns the state requests use and change, once no other request is using it.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+}
+
+impl State {
+    /// Returns the given game's id if the player can have it, loading it from storage if it
+    /// isn't in memory, or the status and message to refuse the request with.
+    pub(crate) fn open(&mut self, id: u64, player: Option<&Player>)
+        -> Result<u64, (u16, String)>
+    {
+        match self.find(id) {
+            Ok(true) => {}
+            Ok(false) => return Err((404, "there's no game with this id".to_string())),
+            Err(e) => return Err((500, e.to_string())),
+        }
+        self.touch(id);
+        match (self.owners.get(&id), player) {
+            (None, _) => Ok(id),
+            (Some(owner), Some(player)) if player.id == *owner => Ok(id),
+            (Some(_), Some(_)) => Err((403, "this game belongs to another player".to_string())),
+            (Some(_), None) => Err((401, "this game needs its player's token".to_string())),
+        }
+    }
+
+    /// Returns the given game, which must have been opened.
+    pub(crate) fn game(&self, id: u64) -> &SpellingBeeGame {
+        &self.games[&id]
+    }
+
+    /// Starts a session for the player with the given name at the given time.
+    pub(crate) fn start_session(&mut self, name: &str, now: u64)
+        -> Result<Session, (u16, String)>
+    {
+        match self.sessions.start(name, now) {
+            Some((player, token)) => Ok(Session { player: player.id, name: player.name, token }),
+            None => Err((422, "the name has to be short, without tabs or line breaks".to_string())),
+        }
+    }
+
+    /// Creates a game from its letters, returning its state. The game belongs to the given
+    /// player, if there is one.
+    pub(crate) fn create(&mut self, letters: &str, center: &str, player: Option<&Player>)
+        -> Result<Game, (u16, String)>
+    {
+        let game = SpellingBeeGame::try_from_lexicon(letters, center, &self.lexicon)
+            .map_err(|e| (422, e.to_string()))?;
+        if self.games.len() >= self.max_games {
+            self.evict();
+        }
+        let id = self.next_game;
+        self.next_game += 1;
+        self.games.insert(id, game);
+        if let Some(player) = player {
+            self.owners.insert(id, player.id.clone());
+        }
+        self.touch(id);
+        self.persist(id).map_err(|e| (500, e.to_string()))?;
+        Ok(self.describe(id))
+    }
+
+    /// Plays the word in the given game, which must have been opened, returning the result.
+    pub(crate) fn play(&mut self, id: u64, word: &str) -> Result<Play, (u16, String)> {
+        let game = self.games.get_mut(&id).expect("the game was checked to exist");
+        let outcome = game.play_outcome(word);
+        self.persist(id).map_err(|e| (500, e.to_string()))?;
+        let game = &self.games[&id];
+        Ok(Play {
+            word: word.to_string(),
+            result: play_result_key(outcome.result()).to_string(),
+            message: self.messages.play_result(outcome.result(), game),
+            points: outcome.points(),
+            pangram: outcome.is_pangram(),
+            score: game.score(),
+            rank: game.rank_name_in(&self.messages),
+        })
+    }
+
+    /// Returns the state of the given game, which must have been opened.
+    pub(crate) fn describe(&self, id: u64) -> Game {
+        let game = &self.games[&id];
+        Game {
+            id: ID(id.to_string()),
+            letters: game.optional_letters(),
+            center: game.required_letter(),
+            score: game.score(),
+            max_score: game.max_score(),
+            rank: game.rank_name_in(&self.messages),
+            words: game.play_order().to_vec(),
+            answer_count: game.total_answer_count(),
+            finished: game.is_finished(),
+        }
+    }
+
+    /// Deletes the given game, from storage too if there is any.
+    fn delete(&mut self, id: u64) -> Result<(), StorageError> {
+        self.games.remove(&id);
+        self.owners.remove(&id);
+        self.last_used.remove(&id);
+        match self.storage.as_mut() {
+            Some(storage) => storage.delete_game(id),
+            None => Ok(()),
+        }
+    }
+
+    /// Finishes the given game, which must have been opened, putting it on the leaderboard for
+    /// the given player, if there is one, and returns its state.
+    pub(crate) fn finish(&mut self, id: u64, player: Option<Player>)
+        -> Result<Game, (u16, String)>
+    {
+        if player.is_some() && self.storage.is_none() {
+            return Err((501, "this server doesn't keep a leaderboard".to_string()));
+        }
+        if self.games[&id].is_finished() {
+            return Err((409, "the game is already finished".to_string()));
+        }
+
+        let game = self.games.get_mut(&id).expect("the game was checked to exist");
+        game.finish_game();
+        let game = &self.games[&id];
+        let entry = player.map(|player| LeaderboardEntry {
+            puzzle_id: PuzzleDef::default_id(&game.optional_letters(), &game.required_letter()),
+            player_id: player.id,
+            player: player.name,
+            score: game.unaided_score(),
+            rank: game.unaided_rank(),
+            words: game.play_order().len(),
+            elapsed: game.elapsed_time().map(|t| t as u64),
+            splits: game.rank_splits(),
+            proof: String::new(),
+        }).map(|mut entry| {
+            entry.proof = self.sessions.sign(&entry.timing_payload());
+            entry
+        });
+        let recorded = match (&entry, self.storage.as_mut()) {
+            (Some(entry), Some(storage)) => storage.record_entry(entry),
+            _ => Ok(()),
+        };
+        recorded.and_then(|_| self.persist(id)).map_err(|e| (500, e.to_string()))?;
+        Ok(self.describe(id))
+    }
+
+    /// Returns the leaderboard for the puzzle with the given id, or the status and message to
+    /// refuse the request with.
+    pub(crate) fn leaderboard(&self, puzzle_id: &str) -> Result<Leaderboard, (u16, String)> {
+        let storage = self.storage.as_ref()
+            .ok_or_else(|| (501, "this server doesn't keep a leaderboard".to_string()))?;
+        let mut entries = storage.entries(puzzle_id).map_err(|e| (500, e.to_string()))?;
+        for entry in &mut entries {
+            if !self.sessions.is_signed(&entry.timing_payload(), &entry.proof) {
+                entry.elapsed = None;
+                entry.splits.clear();
+            }
+        }
+        // The sort is stable, so entries that tie stay in the order they were recorded.
+        entries.sort_by_key(|e| (std::cmp::Reverse(e.score), e.elapsed.is_none(), e.elapsed));
+        let entries = entries.into_iter().map(|e| Entry {
+            player: e.player,
+            player_id: e.player_id,
+            score: e.score,
+            rank: self.messages.rank(e.rank),
+            words: e.words,
+            elapsed: e.elapsed,
+            splits: e.splits.iter()
+                .map(|&(rank, time)| Split { rank: self.messages.rank(rank), time })
+                .collect(),
+        }).collect();
+        Ok(Leaderboard { puzzle: puzzle_id.to_string(), entries })
+    }
+
+    /// Returns `true` if the given game is in memory, loading it from storage first if it's
+    /// there instead.
+    fn find(&mut self, id: u64) -> Result<bool, StorageError> {
+        if self.games.contains_key(&id) {
+            return Ok(true);
+        }
+        let bytes = match self.storage.as_ref().map(|s| s.load_game(id)).transpose()? {
+            Some(Some(bytes)) => bytes,
+            _ => return Ok(false),
+        };
+        let bytes = &mut bytes.as_slice();
+        let loaded = read_str(bytes).and_then(|owner| {
+            Some((owner, SpellingBeeGame::load_bytes_from_lexicon(bytes, &self.lexicon)?))
+        });
+        let (owner, game) = loaded
+            .ok_or_else(|| StorageError::Corrupt(format!("game {} doesn't load", id)))?;
+        if self.games.len() >= self.max_games {
+            self.evict();
+        }
+        self.games.insert(id, game);
+        if !owner.is_empty() {
+            self.owners.insert(id, owner);
+        }
+        Ok(true)
+    }
+
+    /// Saves the given game to storage, if there is any.
+    fn persist(&mut self, id: u64) -> Result<(), StorageError> {
+        match self.storage.as_mut() {
+            Some(storage) => {
+                let mut bytes = Vec::new();
+                write_str(&mut bytes, self.owners.get(&id).map_or("", String::as_str));
+                bytes.extend(self.games[&id].save_bytes());
+                storage.save_game(id, &bytes)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Takes a play from the given client's bucket at the given time, or returns the milliseconds
+    /// until it will have one if it's empty.
+    pub(crate) fn take_play(&mut self, client: String, now: u64) -> Result<(), u64> {
+        let (burst, rate) = (self.play_burst, self.plays_per_second / 1000.0);
+        if self.buckets.len() >= MAX_BUCKETS {
+            self.buckets.retain(|_, b| b.plays + now.saturating_sub(b.at) as f64 * rate < burst);
+        }
+        let bucket = self.buckets.entry(client).or_insert(Bucket { plays: burst, at: now });
+        bucket.plays = (bucket.plays + now.saturating_sub(bucket.at) as f64 * rate).min(burst);
+        bucket.at = bucket.at.max(now);
+        if bucket.plays >= 1.0 {
+            bucket.plays -= 1.0;
+            Ok(())
+        } else if rate > 0.0 {
+            Err(((1.0 - bucket.plays) / rate).ceil() as u64)
+        } else {
+            Err(u64::MAX)
+        }
+    }
+
+    /// Records that the given game just had a request.
+    fn touch(&mut self, id: u64) {
+        self.requests += 1;
+        self.last_used.insert(id, self.requests);
+    }
+
+    /// Drops the game that has gone longest without a request.
+    fn evict(&mut self) {
+        if let Some((&id, _)) = self.last_used.iter().min_by_key(|(_, &used)| used) {
+            self.games.remove(&id);
+            self.owners.remove(&id);
+            self.last_used.remove(&id);
+        }
+    }
+
+    /// Returns the hints for the given game, which must have been opened.
+    pub(crate) fn hints(&self, id: u64) -> Hints {
+        let game = &self.games[&id];
+        Hints {
+            grid: game.hint_grid().rows().clone(),
+            pairs: two_letter_counts(game.answers().difference(game.played_words())),
+        }
+    }
+}
+
+/// Locks the state requests use and change, once no other request is using it. A request that
+/// panicked while it had the state doesn't stop later ones from having it.
+pub(crate) fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the key of the rate limiting bucket for the given player, if there is one, and
+/// otherwise for the client at the given address.
+fn bucket(player: Option<&Player>, client: Option<&str>) -> String {
+    match player {
+        Some(player) => format!("player {}", player.id),
+        None => format!("client {}", client.unwrap_or_default()),
+    }
+}
+
+/// A route the API serves.
+struct Route {
+    /// The HTTP methods it serves.
+    methods: Vec<HttpMethod>,
+
+    /// The path, with its parameters in braces, like `/games/{id}`.
+    path: String,
+
+    /// What the OpenAPI document says about it.
+    operation: Operation,
+
+    /// What handles requests for it.
+    handler: fn(&Api, &Call) -> Response,
+}
+
+impl Route {
+    /// Creates the route for the given handler, which is served at the method and path it's
+    /// documented with, `P`.
+    fn new<P: Path>(handler: fn(&Api, &Call) -> Response) -> Route {
+        Route { methods: P::methods(), path: P::path(), operation: P::operation(), handler }
+    }
+
+    /// Returns the values of the route's path parameters in the given path, in order, or `None`
+    /// if the path isn't the route's.
+    fn parameters<'a>(&self, path: &'a str) -> Option<Vec<&'a str>> {
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        let mut parameters = Vec::new();
+        for pattern in self.path.split('/').filter(|s| !s.is_empty()) {
+            let segment = segments.next()?;
+            if pattern.starts_with('{') {
+                parameters.push(segment);
+            } else if pattern != segment {
+                return None;
+            }
+        }
+        match segments.next() {
+            Some(_) => None,
+            None => Some(parameters),
+        }
+    }
+}
+
+/// A request that has been routed to a handler.
+struct Call<'a> {
+    /// The request.
+    request: &'a Request<'a>,
+
+    /// The player whose session token came with the request, if one did.
+    player: Option<Player>,
+
+    /// The values of the route's path parameters, like the game id in `/games/{id}`, in order.
+    parameters: Vec<&'a str>,
+}
+
+impl Call<'_> {
+    /// Returns the id of the game in the path if the player can have it, or the response
+    /// refusing the request.
+    fn open(&self, state: &mut State) -> Result<u64, Response> {
+        let id = self.parameters[0].parse()
+            .map_err(|_| Response::error(404, "there's no game with this id"))?;
+        state.open(id, self.player.as_ref()).map_err(|(status, message)| {
+            Response::error(status, &message)
+        })
+    }
+}
+
+/// Returns the routes the API serves.
+fn routes() -> Vec<Route> {
+    vec![
+        Route::new::<__path_start_session>(start_session),
+        Route::new::<__path_create_game>(create_game),
+        Route::new::<__path_get_game>(get_game),
+        Route::new::<__path_delete_game>(delete_game),
+        Route::new::<__path_play>(play),
+        Route::new::<__path_get_hints>(get_hints),
+        Route::new::<__path_finish>(finish),
+        Route::new::<__path_get_leaderboard>(get_leaderboard),
+        Route::new::<__path_get_openapi>(get_openapi),
+        Route::new::<__path_query>(query),
+    ]
+}
+
+/// Starts a session for the player with the `name` in the body.
+#[utoipa::path(
+    post, path = "/sessions", request_body = NewSession,
+    responses((status = 201, description = "The new session", body = Session)),
+)]
+fn start_session(api: &Api, call: &Call) -> Response {
+    match serde_json::from_str::<NewSession>(call.request.body) {
+        Ok(new) => {
+            Response::from_result(201, api.lock().start_session(&new.name, call.request.now))
+        }
+        Err(_) => Response::error(400, "the body needs a name string"),
+    }
+}
+
+/// Creates a game from the `letters` and `center` in the body.
+#[utoipa::path(
+    post, path = "/games", request_body = NewGame,
+    responses((status = 201, description = "The new game's state", body = Game)),
+)]
+fn create_game(api: &Api, call: &Call) -> Response {
+    match serde_json::from_str::<NewGame>(call.request.body) {
+        Ok(new) => {
+            let created = api.lock().create(&new.letters, &new.center, call.player.as_ref());
+            Response::from_result(201, created)
+        }
+        Err(_) => Response::error(400, "the body needs letters and a center"),
+    }
+}
+
+/// Gets a game's state.
+#[utoipa::path(
+    get, path = "/games/{id}",
+    params(("id" = String, Path, description = "The game's id")),
+    responses((status = 200, description = "The game's state", body = Game)),
+)]
+fn get_game(api: &Api, call: &Call) -> Response {
+    let mut state = api.lock();
+    match call.open(&mut state) {
+        Ok(id) => Response::json(200, &state.describe(id)),
+        Err(refused) => refused,
+    }
+}
+
+/// Deletes a game.
+#[utoipa::path(
+    delete, path = "/games/{id}",
+    params(("id" = String, Path, description = "The game's id")),
+    responses((status = 204, description = "The game was deleted")),
+)]
+fn delete_game(api: &Api, call: &Call) -> Response {
+    let mut state = api.lock();
+    let deleted = call.open(&mut state)
+        .and_then(|id| state.delete(id).map_err(|e| Response::error(500, &e.to_string())));
+    match deleted {
+        Ok(()) => Response { status: 204, body: None },
+        Err(refused) => refused,
+    }
+}
+
+/// Plays the `word` in the body in a game.
+#[utoipa::path(
+    post, path = "/games/{id}/words", request_body = NewPlay,
+    params(("id" = String, Path, description = "The game's id")),
+    responses(
+        (status = 200, description = "The result of the play", body = Play),
+        (status = 429, description = "Too many plays", body = RateLimited),
+    ),
+)]
+fn play(api: &Api, call: &Call) -> Response {
+    let mut state = api.lock();
+    let id = match call.open(&mut state) {
+        Ok(id) => id,
+        Err(refused) => return refused,
+    };
+    let bucket = bucket(call.player.as_ref(), call.request.client);
+    match state.take_play(bucket, call.request.now) {
+        Ok(()) => match serde_json::from_str::<NewPlay>(call.request.body) {
+            Ok(new) => Response::from_result(200, state.play(id, &new.word)),
+            Err(_) => Response::error(400, "the body needs a word string"),
+        },
+        Err(wait) => Response::json(429, &RateLimited {
+            error: TOO_MANY_PLAYS.to_string(),
+            retry_after: wait,
+        }),
+    }
+}
+
+/// Gets a game's hints.
+#[utoipa::path(
+    get, path = "/games/{id}/hints",
+    params(("id" = String, Path, description = "The game's id")),
+    responses((status = 200, description = "The game's hints", body = Hints)),
+)]
+fn get_hints(api: &Api, call: &Call) -> Response {
+    let mut state = api.lock();
+    match call.open(&mut state) {
+        Ok(id) => Response::json(200, &state.hints(id)),
+        Err(refused) => refused,
+    }
+}
+
+/// Finishes a game, putting it on the leaderboard if there's a session.
+#[utoipa::path(
+    post, path = "/games/{id}/finish",
+    params(("id" = String, Path, description = "The game's id")),
+    responses((status = 200, description = "The game's state", body = Game)),
+)]
+fn finish(api: &Api, call: &Call) -> Response {
+    let mut state = api.lock();
+    match call.open(&mut state) {
+        Ok(id) => Response::from_result(200, state.finish(id, call.player.clone())),
+        Err(refused) => refused,
+    }
+}
+
+/// Gets a puzzle's leaderboard.
+#[utoipa::path(
+    get, path = "/leaderboard/{puzzle}",
+    params(("puzzle" = String, Path, description = "The puzzle's id, like i-cglorw")),
+    responses((status = 200, description = "The puzzle's leaderboard", body = Leaderboard)),
+)]
+fn get_leaderboard(api: &Api, call: &Call) -> Response {
+    Response::from_result(200, api.lock().leaderboard(call.parameters[0]))
+}
+
+/// Gets this document.
+#[utoipa::path(
+    get, path = "/openapi.json",
+    responses((status = 200, description = "This document", content_type = "application/json")),
+)]
+fn get_openapi(_: &Api, _: &Call) -> Response {
+    Response { status: 200, body: Some(openapi()) }
+}
+
+/// Makes a GraphQL query.
+#[utoipa::path(
+    post, path = "/graphql", request_body = GraphQLRequest,
+    responses((status = 200, description = "The query's result", body = GraphQLResponse)),
+)]
+fn query(api: &Api, call: &Call) -> Response {
+    let GraphQLRequest { query, variables, operation_name } =
+        match serde_json::from_str(call.request.body) {
+            Ok(request) => request,
+            Err(_) => return Response::error(400, "the body needs a query string"),
+        };
+    let mut query = async_graphql::Request::new(query)
+        .variables(Variables::from_json(variables))
+        .data(graphql::Caller {
+            player: call.player.clone(),
+            bucket: bucket(call.player.as_ref(), call.request.client),
+            now: call.request.now,
+        });
+    if let Some(name) = operation_name {
+        query = query.operation_name(name);
+    }
+    let result = pollster::block_on(api.schema.execute(query));
+    Response::json(200, &GraphQLResponse { data: result.data, errors: result.errors })
+}
+
+/// Returns the name of an HTTP method, like `GET`.
+fn method_name(method: &HttpMethod) -> &'static str {
+    match method {
+        HttpMethod::Get => "GET",
+        HttpMethod::Post => "POST",
+        HttpMethod::Put => "PUT",
+        HttpMethod::Delete => "DELETE",
+        HttpMethod::Options => "OPTIONS",
+        HttpMethod::Head => "HEAD",
+        HttpMethod::Patch => "PATCH",
+        HttpMethod::Trace => "TRACE",
+    }
+}
+
+/// The parts of the OpenAPI document that aren't about a route.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Spelling Bee", license(name = "MIT OR Apache-2.0"),
+         description = "Play Spelling Bee games with the same validation and scoring as \
+                        the other frontends"),
+    components(schemas(NewSession, Session, NewGame, Game, NewPlay, Play, Hints, Leaderboard,
+                        Entry, Split, RateLimited, GraphQLRequest, GraphQLResponse, Failure)),
+    modifiers(&BearerTokens),
+    // Every route can be used with a session or without one.
+    security((), ("session" = [])),
+)]
+struct Document;
+
+/// Adds the session tokens to the OpenAPI document, as bearer tokens.
+struct BearerTokens;
+
+impl Modify for BearerTokens {
+    fn modify(&self, document: &mut openapi::OpenApi) {
+        document.components.get_or_insert_with(Default::default).add_security_scheme(
+            "session", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+    }
+}
+
+/// Returns the OpenAPI document describing the API, as JSON, from the routes' handlers and the
+/// bodies' types.
+pub fn openapi() -> String {
+    let mut document = Document::openapi();
+    let failure = openapi::ResponseBuilder::new()
+        .description("Something went wrong")
+        .content("application/json", openapi::Content::new(Some(Ref::from_schema_name("Failure"))))
+        .build();
+    for Route { methods, path, mut operation, .. } in routes() {
+        // Anything can fail, with an error saying why.
+        operation.responses.responses.insert("default".to_string(), failure.clone().into());
+        document.paths.add_path_operation(path, methods, operation);
+    }
+    document.to_json().expect("the document is JSON")
+}
+
+/// Serves the API over HTTP at the given address, like `127.0.0.1:8080`, one request at a time,
+/// until the listening socket can't be opened.
+pub fn run(address: &str, mut api: Api) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server = tiny_http::Server::http(address)?;
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json")
+        .expect("the header is valid");
+    for mut request in server.incoming_requests() {
+        // Reading one byte past the limit tells a body that's too long from one that fits.
+        let mut body = String::new();
+        let limit = MAX_BODY_BYTES as u64 + 1;
+        let response = match request.as_reader().take(limit).read_to_string(&mut body) {
+            Ok(read) if read > MAX_BODY_BYTES => Response::error(413, "the body is too long"),
+            Ok(_) => {
+                let client = request.remote_addr().map(|a| a.ip().to_string());
+                let token = request.headers().iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .and_then(|h| h.value.as_str().strip_prefix("Bearer "));
+                api.respond(&Request {
+                    method: request.method().as_str(),
+                    path: request.url(),
+                    body: &body,
+                    token,
+                    client: client.as_deref(),
+                    now: now() as u64,
+                })
+            }
+            Err(_) => Response::error(400, "the body isn't UTF-8"),
+        };
+        let reply = match response.body {
+            Some(body) => tiny_http::Response::from_string(body).with_header(content_type.clone()),
+            None => tiny_http::Response::from_string(""),
+        };
+        // A client that has gone away doesn't need its response.
+        let _ = request.respond(reply.with_status_code(response.status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rank::Rank;
+    use crate::storage::DirStorage;
+
+    /// Sends a request with the given token, at a fixed time.
+    fn send(api: &mut Api, method: &str, path: &str, body: &str,
+            token: Option<&str>) -> Response
+    {
+        api.respond(&Request { method, path, body, token, client: None, now: 1_000 })
+    }
+
+    /// Starts a session for the player with the given name, returning their token.
+    fn start_session(api: &mut Api, name: &str) -> String {
+        let body = format!("{{\"name\": \"{}\"}}", name);
+        let started = send(api, "POST", "/sessions", &body, None).body.unwrap();
+        let started: serde_json::Value = serde_json::from_str(&started).unwrap();
+        started["token"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_api() {
+        let mut api = Api::new(LexiconHandle::new("will\ncowgirl\ncoil\n", ""));
+        let created = api.handle("POST", "/games", r#"{"letters": "cglorw", "center": "i"}"#);
+        assert_eq!(created.status, 201);
+        assert_eq!(created.body.unwrap(), "{\"id\":\"1\",\"letters\":\"cglorw\",\"center\":\"i\",\
+                   \"score\":0,\"max_score\":16,\"rank\":\"Good Start\",\"words\":[],\
+                   \"answer_count\":3,\"finished\":false}");
+
+        let played = api.handle("POST", "/games/1/words", r#"{"word": "cowgirl"}"#);
+        assert!(played.body.unwrap().starts_with(
+            "{\"word\":\"cowgirl\",\"result\":\"play.valid\""));
+        assert_eq!(api.game(1).unwrap().score(), 14);
+        let hints = api.handle("GET", "/games/1/hints?format=json", "").body.unwrap();
+        assert_eq!(hints, "{\"grid\":{\"c\":{\"4\":1},\"w\":{\"4\":1}},\
+                           \"pairs\":{\"co\":1,\"wi\":1}}");
+
+        assert_eq!(api.handle("POST", "/games", "{\"letters\": \"cglor\", \"center\": \"i\"}")
+                      .status, 422);
+        assert_eq!(api.handle("POST", "/games/1/words", "cowgirl").status, 400);
+        assert_eq!(api.handle("PUT", "/games/1", "").status, 405);
+        assert_eq!(api.handle("DELETE", "/games/1", "").status, 204);
+        assert_eq!(api.handle("GET", "/games/1", "").status, 404);
+
+        let nested = "[".repeat(100_000);
+        assert_eq!(api.handle("POST", "/games", &nested).status, 400);
+    }
+
+    #[test]
+    fn test_max_games() {
+        let mut api = Api::new(LexiconHandle::new("will\ncowgirl\ncoil\n", ""));
+        api.set_max_games(2);
+        let body = r#"{"letters": "cglorw", "center": "i"}"#;
+        for _ in 0..2 {
+            api.handle("POST", "/games", body);
+        }
+        api.handle("GET", "/games/1", "");
+        api.handle("POST", "/games", body);
+        assert!(api.game(1).is_some() && api.game(2).is_none() && api.game(3).is_some());
+    }
+
+    #[test]
+    fn test_openapi() {
+        let document: serde_json::Value = serde_json::from_str(&openapi()).unwrap();
+        let schemas = &document["components"]["schemas"];
+
+        // Every schema the document refers to is in it.
+        let mut values = vec![&document];
+        while let Some(value) = values.pop() {
+            match value {
+                serde_json::Value::Object(fields) => {
+                    if let Some(reference) = fields.get("$ref").and_then(|r| r.as_str()) {
+                        let name = reference.trim_start_matches("#/components/schemas/");
+                        assert!(schemas[name].is_object(), "{} has no schema", name);
+                    }
+                    values.extend(fields.values());
+                }
+                serde_json::Value::Array(items) => values.extend(items),
+                _ => {}
+            }
+        }
+
+        // Every route in the document is served, with the statuses and fields it says.
+        let dir = std::env::temp_dir().join(format!("spellingbee-openapi-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let lexicon = LexiconHandle::new("will\ncowgirl\ncoil\n", "");
+        let storage = Box::new(DirStorage::open(&dir).unwrap());
+        let mut api = Api::with_storage(lexicon, storage).unwrap();
+        let ana = start_session(&mut api, "ana");
+        let requests = [
+            ("POST", "/sessions", "/sessions", r#"{"name": "ben"}"#),
+            ("POST", "/games", "/games", r#"{"letters": "cglorw", "center": "i"}"#),
+            ("GET", "/games/{id}", "/games/1", ""),
+            ("POST", "/games/{id}/words", "/games/1/words", r#"{"word": "cowl"}"#),
+            ("GET", "/games/{id}/hints", "/games/1/hints", ""),
+            ("POST", "/games/{id}/finish", "/games/1/finish", ""),
+            ("GET", "/leaderboard/{puzzle}", "/leaderboard/i-cglorw", ""),
+            ("GET", "/openapi.json", "/openapi.json", ""),
+            ("POST", "/graphql", "/graphql", r#"{"query": "{ me { name } }"}"#),
+            ("DELETE", "/games/{id}", "/games/1", ""),
+            ("GET", "/games/{id}", "/games/1", ""),
+        ];
+        let mut served = std::collections::BTreeSet::new();
+        for (method, route, path, body) in requests.iter().copied() {
+            let response = send(&mut api, method, path, body, Some(&ana));
+            let responses = &document["paths"][route][method.to_lowercase()]["responses"];
+            let documented = match &responses[response.status.to_string()] {
+                serde_json::Value::Null => &responses["default"],
+                documented => documented,
+            };
+            assert!(documented.is_object(), "{} {} gave {}", method, path, response.status);
+            let content = &documented["content"]["application/json"];
+            match (content["schema"]["$ref"].as_str(), response.body) {
+                (Some(reference), Some(body)) => {
+                    let schema = &schemas[reference.trim_start_matches("#/components/schemas/")];
+                    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+                    let fields = body.as_object().unwrap();
+                    for field in fields.keys() {
+                        assert!(schema["properties"][field].is_object(), "{} in {}", field, path);
+                    }
+                    for field in schema["required"].as_array().into_iter().flatten() {
+                        assert!(fields.contains_key(field.as_str().unwrap()), "{}", field);
+                    }
+                }
+                (None, body) => assert_eq!(content.is_object(), body.is_some(), "{}", path),
+                (Some(_), None) => panic!("{} {} has no body", method, path),
+            }
+            served.insert((route.to_string(), method.to_lowercase()));
+        }
+        for (path, operations) in document["paths"].as_object().unwrap() {
+            for method in operations.as_object().unwrap().keys() {
+                assert!(served.contains(&(path.clone(), method.clone())), "{} {}", method, path);
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_graphql() {
+        let mut api = Api::new(LexiconHandle::new("will\ncowgirl\ncoil\n", ""));
+        let ana = start_session(&mut api, "ana");
+        send(&mut api, "POST", "/games", r#"{"letters": "cglorw", "center": "i"}"#, Some(&ana));
+        send(&mut api, "POST", "/games/1/words", r#"{"word": "coil"}"#, Some(&ana));
+
+        let query = concat!(r#"{"query": "query($id: ID!) { game(id: $id) { score rank "#,
+                            r#"hints { pairs { pair count } } stats { wordsFound pangramCount } "#,
+                            r#"} me { name } }", "variables": {"id": "1"}}"#);
+        let answered = send(&mut api, "POST", "/graphql", query, Some(&ana));
+        assert_eq!(answered.status, 200);
+        assert_eq!(answered.body.unwrap(), "{\"data\":{\"game\":{\"score\":1,\"rank\":\"Good\",\
+                   \"hints\":{\"pairs\":[{\"pair\":\"co\",\"count\":1},{\"pair\":\"wi\",\
+                   \"count\":1}]},\"stats\":{\"wordsFound\":1,\"pangramCount\":1}},\
+                   \"me\":{\"name\":\"ana\"}}}");
+
+        // The game is Ana's, so it can't be queried without Ana's token.
+        let answered = send(&mut api, "POST", "/graphql", query, None).body.unwrap();
+        let answered: serde_json::Value = serde_json::from_str(&answered).unwrap();
+        assert!(answered["data"]["game"].is_null());
+        assert_eq!(answered["errors"][0]["extensions"]["status"], 401);
+
+        let mutation = concat!(r#"{"query": "mutation { createGame(letters: \"cglorw\", "#,
+                               r#"center: \"i\") { id } play(game: \"2\", word: \"cowgirl\") "#,
+                               r#"{ points pangram } finish(game: \"2\") { finished } }"}"#);
+        assert_eq!(api.handle("POST", "/graphql", mutation).body.unwrap(),
+                   "{\"data\":{\"createGame\":{\"id\":\"2\"},\
+                   \"play\":{\"points\":14,\"pangram\":true},\"finish\":{\"finished\":true}}}");
+        assert!(api.game(2).unwrap().is_finished());
+
+        let leaderboard = r#"{"query": "{ leaderboard(puzzle: \"x\") { puzzle } }"}"#;
+        let answered = api.handle("POST", "/graphql", leaderboard);
+        assert!(answered.body.unwrap().contains("doesn't keep a leaderboard"));
+        let introspection = r#"{"query": "{ __type(name: \"Game\") { name } }"}"#;
+        assert!(api.handle("POST", "/graphql", introspection).body.unwrap().contains("Game"));
+        assert_eq!(api.handle("POST", "/graphql", r#"{"query": "{"}"#).status, 200);
+        assert_eq!(api.handle("POST", "/graphql", "{}").status, 400);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut api = Api::new(LexiconHandle::new("will\ncowgirl\ncoil\n", ""));
+        api.set_play_rate(2.0, 0.5);
+        api.handle("POST", "/games", r#"{"letters": "cglorw", "center": "i"}"#);
+        let mut play = |client, now| api.respond(&Request {
+            method: "POST",
+            path: "/games/1/words",
+            body: r#"{"word": "cowl"}"#,
+            token: None,
+            client: Some(client),
+            now,
+        });
+        assert_eq!(play("10.0.0.1", 1_000).status, 200);
+        assert_eq!(play("10.0.0.1", 1_000).status, 200);
+        let refused = play("10.0.0.1", 1_500);
+        assert_eq!((refused.status, refused.body.unwrap().ends_with("\"retry_after\":1500}")),
+                   (429, true));
+        assert_eq!(play("10.0.0.2", 1_500).status, 200);
+        assert_eq!(play("10.0.0.1", 3_000).status, 200);
+        assert_eq!(play("10.0.0.1", 3_000).status, 429);
+    }
+
+    #[test]
+    fn test_storage() {
+        let lexicon = LexiconHandle::new("will\ncowgirl\ncoil\n", "");
+        let dir = std::env::temp_dir().join(format!("spellingbee-api-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let open = || Box::new(DirStorage::open(&dir).unwrap());
+        assert_eq!(Api::new(lexicon.clone()).handle("GET", "/leaderboard/i-cglorw", "").status,
+                   501);
+
+        let mut api = Api::with_storage(lexicon.clone(), open()).unwrap();
+        api.set_session_key(b"the server's secret key");
+        api.set_max_games(1);
+        let (ana, ben) = (start_session(&mut api, "ana"), start_session(&mut api, "ben"));
+        let body = r#"{"letters": "cglorw", "center": "i"}"#;
+        send(&mut api, "POST", "/games", body, Some(&ana));
+        send(&mut api, "POST", "/games/1/words", r#"{"word": "will"}"#, Some(&ana));
+        send(&mut api, "POST", "/games", body, Some(&ben));
+        assert!(api.game(1).is_none());
+        assert_eq!(send(&mut api, "GET", "/games/1", "", None).status, 401);
+        assert_eq!(send(&mut api, "GET", "/games/1", "", Some(&ben)).status, 403);
+        assert_eq!(send(&mut api, "GET", "/games/1", "", Some("a.b.c")).status, 401);
+        let finished = send(&mut api, "POST", "/games/1/finish", "", Some(&ana));
+        assert!(finished.body.unwrap().ends_with("\"words\":[\"will\"],\"answer_count\":3,\
+                                                  \"finished\":true}"));
+        assert_eq!(send(&mut api, "POST", "/games/1/finish", "", Some(&ana)).status, 409);
+        send(&mut api, "POST", "/games/2/words", r#"{"word": "cowgirl"}"#, Some(&ben));
+        send(&mut api, "POST", "/games/2/finish", "", Some(&ben));
+
+        // A new server with the same key carries on from what the old one stored.
+        let mut api = Api::with_storage(lexicon, open()).unwrap();
+        api.set_session_key(b"the server's secret key");
+        let leaderboard = api.handle("GET", "/leaderboard/i-cglorw", "").body.unwrap();
+        assert!(leaderboard.starts_with("{\"puzzle\":\"i-cglorw\",\"entries\":[\
+                                         {\"player\":\"ben\",\"player_id\":"));
+        assert!(leaderboard.contains("\"score\":1,\"rank\":\"Good\",\"words\":1,\"elapsed\":0,\
+                                      \"splits\":[{\"rank\":\"Moving Up\",\"time\":0},"));
+
+        // Times that weren't signed with the server's key aren't shown.
+        let forged = LeaderboardEntry {
+            puzzle_id: "i-cglorw".to_string(),
+            player_id: "0123456789abcdef".to_string(),
+            player: "cy".to_string(),
+            score: 1,
+            rank: Rank::Good,
+            words: 1,
+            elapsed: Some(0),
+            splits: vec![(Rank::Good, 0)],
+            proof: "0".repeat(64),
+        };
+        api.lock().storage.as_mut().unwrap().record_entry(&forged).unwrap();
+        let leaderboard = api.handle("GET", "/leaderboard/i-cglorw", "").body.unwrap();
+        assert!(leaderboard.ends_with("{\"player\":\"cy\",\"player_id\":\"0123456789abcdef\",\
+                                       \"score\":1,\"rank\":\"Good\",\"words\":1,\"elapsed\":null,\
+                                       \"splits\":[]}]}"));
+        assert_eq!(send(&mut api, "GET", "/games/1", "", Some(&ana)).status, 200);
+        let created = api.handle("POST", "/games", body).body.unwrap();
+        assert!(created.starts_with("{\"id\":\"3\""));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
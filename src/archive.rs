@@ -0,0 +1,231 @@
+//! This module provides `Archive`, a collection of past puzzles by date, so that frontends can
+//! offer a "play previous days" screen. Each puzzle has its letters and, if they're known, its
+//! official answers; a game is only built when a puzzle is played, from its official answers if
+//! it has them and from a lexicon otherwise.
+//!
+//! An archive is saved as text with one puzzle per line and tab-separated fields (shown as
+//! spaces below): the date, the optional letters, the required letter, and optionally the
+//! official answers, separated by commas.
+//!
+//! ```text
+//! 2024-05-06  cglorw  i  coil,cowgirl,will
+//! 2024-05-07  aehnrt  p
+//! ```
+
+use std::collections::BTreeMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::{is_date, js_array};
+#[cfg(feature = "nyt-fetch")]
+use crate::nyt::OfficialPuzzle;
+
+/// A puzzle in an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArchivedPuzzle {
+    /// The date the puzzle is for, as `YYYY-MM-DD`.
+    pub date: String,
+
+    /// The optional letters.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: String,
+
+    /// The official answers, if they're known.
+    pub answers: Option<Vec<String>>,
+}
+
+impl ArchivedPuzzle {
+    /// Builds a game for the puzzle. With official answers, its answers are exactly those, like
+    /// `SpellingBeeGame::from_answers()`; otherwise they come from the given lexicons, like
+    /// `SpellingBeeGame::new()`.
+    pub fn game(&self, main_words: &str, swears: &str) -> SpellingBeeGame {
+        match &self.answers {
+            Some(answers) => SpellingBeeGame::from_answers(&self.optional_letters,
+                                                           &self.required_letter,
+                                                           &answers.join("\n")),
+            None => SpellingBeeGame::new(&self.optional_letters, &self.required_letter,
+                                         main_words, swears),
+        }
+    }
+
+    /// Encodes the puzzle as a line of the format described in the module documentation.
+    fn encode(&self) -> String {
+        let mut line = format!("{}\t{}\t{}", self.date, self.optional_letters,
+                               self.required_letter);
+        if let Some(answers) = &self.answers {
+            line.push('\t');
+            line.push_str(&answers.join(","));
+        }
+        line
+    }
+
+    /// Decodes a line written by `encode()`, returning `None` if it isn't valid.
+    fn decode(line: &str) -> Option<ArchivedPuzzle> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (date, optional_letters, required_letter, answers) = match fields.as_slice() {
+            [date, optional, required] => (date, optional, required, None),
+            [date, optional, required, answers] => {
+                (date, optional, required, Some(answers.split(',').map(String::from).collect()))
+            }
+            _ => return None,
+        };
+        if !is_date(date) || optional_letters.is_empty() || required_letter.is_empty() {
+            return None;
+        }
+        Some(ArchivedPuzzle {
+            date: date.to_string(),
+            optional_letters: optional_letters.to_string(),
+            required_letter: required_letter.to_string(),
+            answers,
+        })
+    }
+}
+
+/// Past puzzles, by date.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Archive {
+    /// The puzzles, by date.
+    puzzles: BTreeMap<String, ArchivedPuzzle>,
+}
+
+#[wasm_bindgen]
+impl Archive {
+    /// Creates an empty archive.
+    pub fn new() -> Archive {
+        Archive::default()
+    }
+
+    /// Adds the puzzle for the given `YYYY-MM-DD` date, replacing any puzzle already archived for
+    /// it. The official answers are one per line, and an empty list means they aren't known.
+    /// Returns `false`, adding nothing, if the date isn't valid or a letter is missing.
+    pub fn add(&mut self, date: &str, optional_letters: &str, required_letter: &str,
+               answers: &str) -> bool
+    {
+        let answers: Vec<String> = answers.lines()
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .map(String::from)
+            .collect();
+        let optional_letters = optional_letters.trim();
+        let required_letter = required_letter.trim();
+        if !is_date(date) || optional_letters.is_empty() || required_letter.is_empty() {
+            return false;
+        }
+        self.insert(ArchivedPuzzle {
+            date: date.to_string(),
+            optional_letters: optional_letters.to_string(),
+            required_letter: required_letter.to_string(),
+            answers: if answers.is_empty() { None } else { Some(answers) },
+        });
+        true
+    }
+
+    /// Removes the puzzle for the given date, returning `true` if there was one.
+    pub fn remove(&mut self, date: &str) -> bool {
+        self.puzzles.remove(date).is_some()
+    }
+
+    /// Returns the number of puzzles.
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /// Returns `true` if there are no puzzles.
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    /// Returns the dates of the puzzles as an array of strings, oldest first.
+    pub fn dates(&self) -> JsValue {
+        js_array(self.puzzles.keys())
+    }
+
+    /// Returns `true` if the puzzle for the given date has official answers. Returns `false` if
+    /// there's no puzzle for it.
+    pub fn has_official_answers(&self, date: &str) -> bool {
+        self.puzzles.get(date).is_some_and(|p| p.answers.is_some())
+    }
+
+    /// Builds a game for the puzzle for the given date, like `ArchivedPuzzle::game()`, or returns
+    /// `None` if there's no puzzle for it.
+    pub fn game(&self, date: &str, main_words: &str, swears: &str) -> Option<SpellingBeeGame> {
+        self.puzzles.get(date).map(|p| p.game(main_words, swears))
+    }
+
+    /// Encodes the archive in the format described in the module documentation, oldest puzzle
+    /// first.
+    pub fn encode(&self) -> String {
+        self.puzzles.values().map(|p| p.encode() + "\n").collect()
+    }
+
+    /// Decodes an archive written by `encode()`, returning `None` if any line isn't valid. Blank
+    /// lines are skipped, and a later puzzle for the same date replaces an earlier one.
+    pub fn decode(text: &str) -> Option<Archive> {
+        let mut archive = Archive::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            archive.insert(ArchivedPuzzle::decode(line.trim_end_matches('\r'))?);
+        }
+        Some(archive)
+    }
+}
+
+impl Archive {
+    /// Adds a puzzle, replacing any puzzle already archived for its date.
+    pub fn insert(&mut self, puzzle: ArchivedPuzzle) {
+        self.puzzles.insert(puzzle.date.clone(), puzzle);
+    }
+
+    /// Returns the puzzle for the given date, if there is one.
+    pub fn puzzle(&self, date: &str) -> Option<&ArchivedPuzzle> {
+        self.puzzles.get(date)
+    }
+
+    /// Returns the puzzles, oldest first.
+    pub fn puzzles(&self) -> impl Iterator<Item = &ArchivedPuzzle> {
+        self.puzzles.values()
+    }
+
+    /// Adds an official puzzle with its answers, replacing any puzzle already archived for its
+    /// date.
+    #[cfg(feature = "nyt-fetch")]
+    pub fn insert_official(&mut self, puzzle: &OfficialPuzzle) {
+        self.insert(ArchivedPuzzle {
+            date: puzzle.date.clone(),
+            optional_letters: puzzle.outer_letters.clone(),
+            required_letter: puzzle.center_letter.clone(),
+            answers: Some(puzzle.answers.clone()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive() {
+        let mut archive = Archive::new();
+        assert!(archive.add("2024-05-07", "cglorw", "i", ""));
+        assert!(archive.add("2024-05-06", "cglorw", "i", "will\ncowgirl\ncoil\n"));
+        assert!(!archive.add("May 8", "cglorw", "i", ""));
+        assert!(archive.has_official_answers("2024-05-06") &&
+                !archive.has_official_answers("2024-05-07"));
+
+        let text = "2024-05-06\tcglorw\ti\twill,cowgirl,coil\n2024-05-07\tcglorw\ti\n";
+        assert_eq!(archive.encode(), text);
+        assert_eq!(Archive::decode(text), Some(archive.clone()));
+        assert_eq!(Archive::decode("2024-05-06\tcglorw"), None);
+
+        let official = archive.game("2024-05-06", "", "").unwrap();
+        assert_eq!(official.total_answer_count(), 3);
+        let game = archive.game("2024-05-07", "will\ncoil\nwig\n", "").unwrap();
+        assert_eq!(game.total_answer_count(), 2);
+        assert!(archive.game("2024-05-08", "", "").is_none());
+    }
+}
@@ -0,0 +1,49 @@
+//! Compiles a main word list and swears list into the compact dictionary format loaded by
+//! `SpellingBeeGame::from_dawg()`:
+//!
+//! ```text
+//! spellingbee-dawg MAIN_WORDS SWEARS OUTPUT
+//! ```
+//!
+//! The word lists are read the same way as by `SpellingBeeGame::new()`, and the swears are left
+//! out of the compiled dictionary.
+
+use std::env;
+use std::fs;
+use std::process;
+use spelling_bee::dawg::Dawg;
+use spelling_bee::lexicon::LexiconHandle;
+
+/// Reads a file, exiting with a message if it can't be read.
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("couldn't read {}: {}", path, e);
+        process::exit(1);
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (main_words, swears, output) = match args.as_slice() {
+        [main_words, swears, output] => (main_words, swears, output),
+        _ => {
+            eprintln!("usage: spellingbee-dawg MAIN_WORDS SWEARS OUTPUT");
+            process::exit(2);
+        }
+    };
+
+    let main_text = read(main_words);
+    let lexicon = LexiconHandle::new(&main_text, &read(swears));
+    if lexicon.uses_fallback_dictionary() {
+        eprintln!("couldn't parse {}", main_words);
+        process::exit(1);
+    }
+
+    let bytes = Dawg::from_words(lexicon.words()).to_bytes();
+    if let Err(e) = fs::write(output, &bytes) {
+        eprintln!("couldn't write {}: {}", output, e);
+        process::exit(1);
+    }
+    println!("{} words, {} bytes of text, {} bytes compiled", lexicon.len(), main_text.len(),
+             bytes.len());
+}
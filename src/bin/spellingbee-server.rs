@@ -0,0 +1,125 @@
+//! Serves the HTTP API described in the `api` module, so that clients that can't use the library
+//! can play games:
+//!
+//! ```text
+//! spellingbee-server [--address ADDRESS] [--words FILE] [--swears FILE] [--max-games N]
+//!                    [--data DIR | --sled PATH | --sqlite PATH] [--secret FILE]
+//! ```
+//!
+//! The address defaults to `127.0.0.1:8080`, the word lists default to the ones the web frontend
+//! uses, relative to the repository root, and the number of games kept in memory defaults to
+//! `api::DEFAULT_MAX_GAMES`. Games and the leaderboard are only kept in memory unless they're
+//! stored in a directory with `--data`, in a sled database with `--sled` (with the
+//! `storage-sled` feature), or in a SQLite database with `--sqlite` (with the `storage-sqlite`
+//! feature), so that they survive restarts. Session tokens are signed with the contents of the
+//! `--secret` file, or else with a random key, so that players have to start new sessions after
+//! a restart. It's only built with the `http-api` feature.
+
+use std::env;
+use std::fs;
+use std::process;
+use spelling_bee::api::{self, Api};
+use spelling_bee::lexicon::LexiconHandle;
+use spelling_bee::storage::{DirStorage, Storage, StorageError};
+
+/// The address served at when `--address` isn't given.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+/// The main word list used when `--words` isn't given.
+const DEFAULT_WORDS: &str = "www/2of12inf.txt";
+/// The swears list used when `--swears` isn't given.
+const DEFAULT_SWEARS: &str = "www/swears.txt";
+
+const USAGE: &str = "usage: spellingbee-server [--address ADDRESS] [--words FILE] \
+                     [--swears FILE] [--max-games N] [--data DIR | --sled PATH | --sqlite PATH] \
+                     [--secret FILE]";
+
+/// Reads a word list, exiting with a message if it can't be read.
+fn read_list(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("couldn't read {}: {}", path, e);
+        process::exit(1);
+    })
+}
+
+/// Opens the storage named by a storage flag and its value.
+fn open_storage(flag: &str, path: &str) -> Result<Box<dyn Storage + Send>, StorageError> {
+    match flag {
+        #[cfg(feature = "storage-sled")]
+        "--sled" => Ok(Box::new(spelling_bee::storage::SledStorage::open(path)?)),
+        #[cfg(feature = "storage-sqlite")]
+        "--sqlite" => Ok(Box::new(spelling_bee::storage::SqliteStorage::open(path)?)),
+        _ => Ok(Box::new(DirStorage::open(path)?)),
+    }
+}
+
+fn main() {
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut words = DEFAULT_WORDS.to_string();
+    let mut swears = DEFAULT_SWEARS.to_string();
+    let mut max_games = api::DEFAULT_MAX_GAMES;
+    let mut storage = None;
+    let mut secret = None;
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| {
+            eprintln!("{} needs a value\n{}", flag, USAGE);
+            process::exit(2);
+        });
+        match flag.as_str() {
+            "--address" => address = value,
+            "--words" => words = value,
+            "--swears" => swears = value,
+            "--max-games" => max_games = value.parse().unwrap_or_else(|_| {
+                eprintln!("--max-games needs a number\n{}", USAGE);
+                process::exit(2);
+            }),
+            "--secret" => secret = Some(value),
+            "--data" => storage = Some((flag, value)),
+            #[cfg(feature = "storage-sled")]
+            "--sled" => storage = Some((flag, value)),
+            #[cfg(feature = "storage-sqlite")]
+            "--sqlite" => storage = Some((flag, value)),
+            _ => {
+                eprintln!("unknown argument {}\n{}", flag, USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let lexicon = LexiconHandle::new(&read_list(&words), &read_list(&swears));
+    if lexicon.uses_fallback_dictionary() {
+        eprintln!("couldn't parse {}", words);
+        process::exit(1);
+    }
+    println!("Serving the API at {}", address);
+    let mut api = match storage {
+        Some((flag, path)) => {
+            match open_storage(&flag, &path).and_then(|s| Api::with_storage(lexicon, s)) {
+                Ok(api) => api,
+                Err(e) => {
+                    eprintln!("couldn't open {}: {}", path, e);
+                    process::exit(1);
+                }
+            }
+        }
+        None => Api::new(lexicon),
+    };
+    api.set_max_games(max_games);
+    if let Some(path) = secret {
+        match fs::read(&path) {
+            Ok(key) if key.len() >= 32 => api.set_session_key(&key),
+            Ok(_) => {
+                eprintln!("{} needs at least 32 bytes of secret", path);
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("couldn't read {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+    if let Err(e) = api::run(&address, api) {
+        eprintln!("couldn't serve the API: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,215 @@
+//! A terminal frontend for playing puzzles without a browser:
+//!
+//! ```text
+//! spellingbee --letters clwgro --center i [--words FILE] [--swears FILE] [--tui]
+//!             [--serve ADDRESS]
+//! ```
+//!
+//! Each line typed is played as a word, except for the commands starting with `:`, listed by
+//! `:help`. With `--tui`, it runs the full-screen frontend instead, if it was built with the `tui`
+//! feature, and with `--serve`, it hosts co-op and versus lobbies for the puzzle over WebSockets
+//! at the given address, like `0.0.0.0:8080`, if it was built with the `server` feature. The word
+//! lists default to the ones the web frontend uses, relative to the repository root.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+use spelling_bee::i18n::{Messages, FALLBACK_LOCALE};
+use spelling_bee::SpellingBeeGame;
+
+/// The main word list used when `--words` isn't given.
+const DEFAULT_WORDS: &str = "www/2of12inf.txt";
+/// The swears list used when `--swears` isn't given.
+const DEFAULT_SWEARS: &str = "www/swears.txt";
+
+const USAGE: &str = "usage: spellingbee --letters LETTERS --center LETTER [--words FILE] \
+                     [--swears FILE] [--tui] [--serve ADDRESS]";
+
+const HELP: &str = "\
+Type a word to play it, or one of these commands:
+  :score   show the score and rank
+  :hints   show the hint grid and two-letter list
+  :words   list the words found so far
+  :undo    take back the last word
+  :finish  give up and show the words you missed
+  :help    show this message
+  :quit    stop playing";
+
+/// The command-line options.
+struct Options {
+    letters: String,
+    center: String,
+    words: String,
+    swears: String,
+    tui: bool,
+    serve: Option<String>,
+}
+
+/// Parses the command-line arguments, or returns a message saying what's wrong with them.
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut letters = None;
+    let mut center = None;
+    let mut words = DEFAULT_WORDS.to_string();
+    let mut swears = DEFAULT_SWEARS.to_string();
+    let mut tui = false;
+    let mut serve = None;
+    while let Some(flag) = args.next() {
+        if flag == "--tui" {
+            tui = true;
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        match flag.as_str() {
+            "--letters" => letters = Some(value),
+            "--center" => center = Some(value),
+            "--words" => words = value,
+            "--swears" => swears = value,
+            "--serve" => serve = Some(value),
+            _ => return Err(format!("unknown argument {}", flag)),
+        }
+    }
+
+    Ok(Options {
+        letters: letters.ok_or("--letters is required")?,
+        center: center.ok_or("--center is required")?,
+        words,
+        swears,
+        tui,
+        serve,
+    })
+}
+
+/// Reads a word list, exiting with a message if it can't be read.
+fn read_list(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("couldn't read {}: {}", path, e);
+        process::exit(1);
+    })
+}
+
+/// Prints the score and rank, and how far it is to the next rank.
+fn print_score(game: &SpellingBeeGame) {
+    print!("{} points, {}", game.score(), game.rank_name());
+    match game.next_rank_threshold() {
+        Some(next) => println!(" ({} to the next rank)", next - game.score()),
+        None => println!(),
+    }
+}
+
+/// Runs the full-screen frontend, exiting with a message if the terminal can't be used.
+#[cfg(feature = "tui")]
+fn run_tui(game: &mut SpellingBeeGame) {
+    if let Err(e) = spelling_bee::tui::run(game) {
+        eprintln!("couldn't run the full-screen frontend: {}", e);
+        process::exit(1);
+    }
+    print_score(game);
+}
+
+/// Exits with a message, since the full-screen frontend wasn't built.
+#[cfg(not(feature = "tui"))]
+fn run_tui(_game: &mut SpellingBeeGame) {
+    eprintln!("--tui needs spellingbee to be built with the tui feature");
+    process::exit(2);
+}
+
+/// Hosts lobbies playing the game until the server fails, exiting with a message when it does.
+#[cfg(feature = "server")]
+fn run_server(address: &str, game: SpellingBeeGame) {
+    println!("Serving {} (center {}) at {}", game.optional_letters(), game.required_letter(),
+             address);
+    if let Err(e) = spelling_bee::server::run(address, game) {
+        eprintln!("couldn't serve the game: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Exits with a message, since the server wasn't built.
+#[cfg(not(feature = "server"))]
+fn run_server(_address: &str, _game: SpellingBeeGame) {
+    eprintln!("--serve needs spellingbee to be built with the server feature");
+    process::exit(2);
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let main_words = read_list(&options.words);
+    let swears = read_list(&options.swears);
+    let mut game = SpellingBeeGame::try_new_in(FALLBACK_LOCALE, &options.letters,
+                                               &options.center, &main_words, &swears)
+        .unwrap_or_else(|e| {
+            eprintln!("couldn't start the game: {}", e);
+            process::exit(1);
+        });
+    if options.tui {
+        run_tui(&mut game);
+        return;
+    }
+    if let Some(address) = &options.serve {
+        run_server(address, game);
+        return;
+    }
+
+    let messages = Messages::new(FALLBACK_LOCALE);
+
+    println!("Letters: {} (center {}), {} answers", game.optional_letters(),
+             game.required_letter(), game.answers().len());
+    println!("Type :help for the commands.");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+
+        match line.trim() {
+            "" => {}
+            ":quit" => break,
+            ":help" => println!("{}", HELP),
+            ":score" => print_score(&game),
+            ":hints" => {
+                print!("{}", game.hint_grid().to_text());
+                println!("{}", game.two_letter_list());
+            }
+            ":words" => println!("{}", game.play_order().join(" ")),
+            ":undo" => match game.undo() {
+                Some(word) => println!("took back {}", word),
+                None => println!("nothing to take back"),
+            },
+            ":finish" => {
+                for missed in game.finish_game() {
+                    let pangram = if missed.pangram { " (pangram)" } else { "" };
+                    println!("{} +{}{}", missed.word, missed.points, pangram);
+                }
+                print_score(&game);
+                break;
+            }
+            command if command.starts_with(':') => {
+                println!("unknown command {}; type :help for the commands", command);
+            }
+            word => {
+                let outcome = game.play_outcome(word);
+                print!("{}", messages.play_result(outcome.result(), &game));
+                if outcome.points() > 0 {
+                    print!(" +{}", outcome.points());
+                }
+                if outcome.is_pangram() {
+                    print!(" Pangram!");
+                }
+                println!();
+                print_score(&game);
+                if game.score() == game.max_score() {
+                    println!("You found every answer!");
+                    break;
+                }
+            }
+        }
+    }
+}
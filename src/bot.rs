@@ -0,0 +1,283 @@
+//! This module provides `Bot`, a computer opponent for a versus mode, which plays its own copy of
+//! the puzzle at a steady pace so a frontend can show it racing the player.
+//!
+//! Each time it plays, the bot draws one of the answers it hasn't found yet, preferring common
+//! words (when frequencies have been loaded, see `SpellingBeeGame::load_frequencies()`) and short
+//! ones, the way a person would find them. Its `BotLevel` sets how strongly it prefers them, how
+//! often it plays, and the rank it stops at. Its words are played like anyone else's, so they're
+//! scored by the puzzle's own rules.
+//!
+//! Like `Royale`, the bot doesn't keep time itself: callers pass in the current time (in
+//! milliseconds, as from `Date.now()`), and `advance()` returns each play that has come due, at
+//! the time it was due, so a frontend that only checks in now and then still shows them in order.
+
+use wasm_bindgen::prelude::*;
+use crate::rank::Rank;
+use crate::spellingbee::{PlayResult, SpellingBeeGame};
+use crate::utils::{letter_count, Rng};
+
+/// How well a bot plays.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BotLevel {
+    /// Plays the most common short words, slowly, and stops at Good.
+    Easy,
+
+    /// Stops at Great.
+    Medium,
+
+    /// Stops at Genius.
+    Hard,
+
+    /// Plays quickly, barely prefers easy words, and keeps going until it has every answer.
+    Expert,
+}
+
+impl BotLevel {
+    /// Returns the average number of milliseconds between plays.
+    pub fn interval(self) -> u64 {
+        match self {
+            BotLevel::Easy => 40_000,
+            BotLevel::Medium => 25_000,
+            BotLevel::Hard => 15_000,
+            BotLevel::Expert => 8_000,
+        }
+    }
+
+    /// Returns the rank the bot stops playing at.
+    pub fn target(self) -> Rank {
+        match self {
+            BotLevel::Easy => Rank::Good,
+            BotLevel::Medium => Rank::Great,
+            BotLevel::Hard => Rank::Genius,
+            BotLevel::Expert => Rank::QueenBee,
+        }
+    }
+
+    /// Returns how strongly the bot prefers common words and short words, as the powers their
+    /// weights are raised to: 0 is no preference at all.
+    fn biases(self) -> (f64, f64) {
+        match self {
+            BotLevel::Easy => (2.0, 2.0),
+            BotLevel::Medium => (1.0, 1.0),
+            BotLevel::Hard => (0.5, 0.5),
+            BotLevel::Expert => (0.25, 0.0),
+        }
+    }
+}
+
+/// A word a bot played.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BotPlay {
+    /// The word.
+    word: String,
+
+    /// The points it earned.
+    points: usize,
+
+    /// The bot's score after it.
+    score: usize,
+
+    /// When it was played, in milliseconds since the Unix epoch.
+    at: u64,
+}
+
+#[wasm_bindgen]
+impl BotPlay {
+    /// Returns the word.
+    pub fn word(&self) -> String {
+        self.word.clone()
+    }
+
+    /// Returns the points the word earned.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Returns the bot's score after the word.
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// Returns when the word was played, in milliseconds since the Unix epoch.
+    pub fn at(&self) -> f64 {
+        self.at as f64
+    }
+}
+
+/// A computer opponent playing its own copy of a puzzle.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bot {
+    /// The bot's copy of the puzzle.
+    game: SpellingBeeGame,
+
+    /// How well the bot plays.
+    level: BotLevel,
+
+    /// The average number of milliseconds between plays.
+    interval: u64,
+
+    /// Where the bot's choices come from.
+    rng: Rng,
+
+    /// When the next play is due, or `None` if the bot hasn't started or is done.
+    next_play_at: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl Bot {
+    /// Creates a bot at the given level that plays its own copy of the game, which should be the
+    /// puzzle before anyone has played it. The same seed always makes the same choices.
+    pub fn new(game: &SpellingBeeGame, level: BotLevel, seed: u32) -> Bot {
+        Bot {
+            game: game.clone(),
+            level,
+            interval: level.interval(),
+            rng: Rng::new(u64::from(seed)),
+            next_play_at: None,
+        }
+    }
+
+    /// Returns how well the bot plays.
+    pub fn level(&self) -> BotLevel {
+        self.level
+    }
+
+    /// Returns the average number of milliseconds between plays.
+    pub fn interval(&self) -> f64 {
+        self.interval as f64
+    }
+
+    /// Sets the average number of milliseconds between plays, instead of the level's. It takes
+    /// effect after the play that's already due.
+    pub fn set_interval(&mut self, millis: f64) {
+        self.interval = millis.max(0.0) as u64;
+    }
+
+    /// Starts the bot's clock at the given time, scheduling its first play. Does nothing if a
+    /// play is already scheduled.
+    pub fn start(&mut self, now: f64) {
+        if self.next_play_at.is_none() {
+            self.game.start_clock(now);
+            self.schedule(now as u64);
+        }
+    }
+
+    /// Returns when the next play is due, or `None` if the bot hasn't started or is done.
+    pub fn next_play_at(&self) -> Option<f64> {
+        self.next_play_at.map(|at| at as f64)
+    }
+
+    /// Makes the next play if it's due by the given time, returning it, or returns `None` if it
+    /// isn't. Call it until it returns `None` to catch up on every play that's due.
+    pub fn advance(&mut self, now: f64) -> Option<BotPlay> {
+        let at = self.next_play_at.filter(|&at| at <= now as u64)?;
+        let word = self.choose()?.to_string();
+        let before = self.game.score();
+        if self.game.play_at(&word, at as f64) != PlayResult::Valid {
+            // The bot only draws answers it hasn't found, so this means the game won't take any
+            // more words, such as after it's been finished. Stop rather than retry forever.
+            self.next_play_at = None;
+            return None;
+        }
+        self.schedule(at);
+        Some(BotPlay { word, points: self.game.score() - before, score: self.game.score(), at })
+    }
+
+    /// Returns `true` if the bot has reached its level's rank or found every answer, so it won't
+    /// play again.
+    pub fn is_done(&self) -> bool {
+        self.game.score() >= self.game.rank_threshold(self.level.target()) ||
+            self.game.played_words().len() == self.game.answers().len()
+    }
+
+    /// Returns the bot's score.
+    pub fn score(&self) -> usize {
+        self.game.score()
+    }
+
+    /// Returns the bot's rank.
+    pub fn rank(&self) -> Rank {
+        self.game.rank()
+    }
+
+    /// Returns a copy of the bot's game, with the words it has found.
+    pub fn game(&self) -> SpellingBeeGame {
+        self.game.clone()
+    }
+}
+
+impl Bot {
+    /// Returns the bot's game.
+    pub fn game_ref(&self) -> &SpellingBeeGame {
+        &self.game
+    }
+
+    /// Schedules the next play for some time after the given one, between half and one and a half
+    /// intervals, unless the bot is done.
+    fn schedule(&mut self, after: u64) {
+        self.next_play_at = if self.is_done() {
+            None
+        } else {
+            Some(after + (self.interval as f64 * (0.5 + self.rng.unit())) as u64)
+        };
+    }
+
+    /// Draws one of the answers the bot hasn't found yet, weighted by how common and how short it
+    /// is, or returns `None` if there aren't any.
+    fn choose(&mut self) -> Option<&str> {
+        let (frequency_bias, length_bias) = self.level.biases();
+        let min_length = self.game.config().min_length();
+        let missing = self.game.missing_easiest_first();
+        let weights: Vec<f64> = missing.iter().map(|word| {
+            let frequency = self.game.frequency(word).unwrap_or(0.0);
+            let extra_letters = letter_count(word).saturating_sub(min_length) as f64;
+            (1.0 + frequency.ln_1p()).powf(frequency_bias) / (1.0 + extra_letters).powf(length_bias)
+        }).collect();
+
+        let mut target = self.rng.unit() * weights.iter().sum::<f64>();
+        for (word, weight) in missing.iter().zip(&weights) {
+            if target < *weight {
+                return Some(word);
+            }
+            target -= weight;
+        }
+        missing.last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bot() {
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\nwigwig\n");
+        let mut bot = Bot::new(&game, BotLevel::Expert, 7);
+        assert_eq!(bot.advance(1_000.0), None);
+        bot.set_interval(1_000.0);
+        bot.start(0.0);
+
+        let first = bot.next_play_at().unwrap();
+        assert!((500.0..1_500.0).contains(&first));
+        assert_eq!(bot.advance(first - 1.0), None);
+        let mut words = Vec::new();
+        while let Some(play) = bot.advance(10_000.0) {
+            assert_eq!(play.points(), game.word_points(&play.word()));
+            words.push(play.word());
+        }
+        words.sort();
+        assert_eq!(words, ["coil", "cowgirl", "wigwig", "will"]);
+        assert!(bot.is_done() && bot.next_play_at().is_none());
+        assert_eq!(bot.score(), game.max_score());
+
+        let mut finished = game.clone();
+        finished.finish_game();
+        let mut bot = Bot::new(&finished, BotLevel::Expert, 7);
+        bot.start(0.0);
+        assert_eq!(bot.advance(10_000.0), None);
+        assert_eq!(bot.next_play_at(), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! This module provides `PuzzleCache`, which builds games ahead of time so they're ready the
+//! moment they're needed. Building a game filters the whole lexicon, which can take a noticeable
+//! moment; a frontend that knows tomorrow's letters can prepare its game while idle today, so that
+//! the daily rollover doesn't have to wait for it.
+//!
+//! Games are kept under whatever id the frontend likes, but daily puzzles should be kept under
+//! their dates, as `YYYY-MM-DD`, so that `evict_before()` can drop the days that have gone by
+//! without the cache growing by a game every day.
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::is_date;
+
+/// Games that have been built ahead of time, by id.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PuzzleCache {
+    /// The prepared games, by id.
+    games: BTreeMap<String, SpellingBeeGame>,
+}
+
+#[wasm_bindgen]
+impl PuzzleCache {
+    /// Creates an empty cache.
+    pub fn new() -> PuzzleCache {
+        PuzzleCache::default()
+    }
+
+    /// Builds the game with the given letters and lexicons (see `SpellingBeeGame::new()`) and
+    /// keeps it under the given id, replacing any game already there. Returns the number of
+    /// answers.
+    pub fn prepare(&mut self, id: &str, optional_letters: &str, required_letter: &str,
+                   main_words: &str, swears: &str) -> usize
+    {
+        let game = SpellingBeeGame::new(optional_letters, required_letter, main_words, swears);
+        let answers = game.puzzle_def().answer_count();
+        self.insert(id, game);
+        answers
+    }
+
+    /// Returns `true` if a game with the given id has been prepared.
+    pub fn is_prepared(&self, id: &str) -> bool {
+        self.games.contains_key(id)
+    }
+
+    /// Removes the game with the given id from the cache and returns it, if it was prepared.
+    pub fn take(&mut self, id: &str) -> Option<SpellingBeeGame> {
+        self.games.remove(id)
+    }
+
+    /// Returns the number of prepared games.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Returns `true` if no games are prepared.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Removes every prepared game.
+    pub fn clear(&mut self) {
+        self.games.clear();
+    }
+
+    /// Removes the games kept under dates, as `YYYY-MM-DD`, before the given date, such as
+    /// yesterday's once today's has been taken. Games under ids that aren't dates stay. Returns
+    /// the number of games removed.
+    pub fn evict_before(&mut self, date: &str) -> usize {
+        let before = self.games.len();
+        // Dates in this format sort the same as text as they do in time.
+        self.games.retain(|id, _| !is_date(id) || id.as_str() >= date);
+        before - self.games.len()
+    }
+}
+
+impl PuzzleCache {
+    /// Keeps an already-built game under the given id, replacing any game already there.
+    pub fn insert(&mut self, id: &str, game: SpellingBeeGame) {
+        self.games.insert(id.to_string(), game);
+    }
+
+    /// Returns the game with the given id, if it was prepared, leaving it in the cache.
+    pub fn get(&self, id: &str) -> Option<&SpellingBeeGame> {
+        self.games.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let mut cache = PuzzleCache::new();
+        let words = "will\ncowgirl\ncoil\nzebra\n";
+        assert_eq!(cache.prepare("2024-05-06", "cglorw", "i", words, ""), 3);
+        assert_eq!(cache.prepare("2024-05-07", "cgiorw", "l", words, ""), 3);
+        cache.insert("practice", SpellingBeeGame::from_answers("cglorw", "i", "will\n"));
+        assert!(cache.is_prepared("2024-05-06") && !cache.is_prepared("2024-05-08"));
+        assert_eq!(cache.get("practice").map(|g| g.answers().len()), Some(1));
+
+        let today = cache.take("2024-05-07").unwrap();
+        assert_eq!(today.required_letter(), "l");
+        assert_eq!(cache.take("2024-05-07"), None);
+        cache.prepare("2024-05-08", "cglorw", "i", words, "");
+        assert_eq!(cache.evict_before("2024-05-07"), 1);
+        assert!(cache.is_prepared("practice") && cache.is_prepared("2024-05-08"));
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! This module provides `PuzzleCode`, a short code for a puzzle that's safe to put in a URL, so
+//! that puzzles can be shared as links and rebuilt exactly with
+//! `SpellingBeeGame::from_puzzle_code()`. A code has the letters and, optionally, a version id
+//! for the word list, so that a frontend can tell when a link was made with a different
+//! dictionary than the one it has.
+//!
+//! A code is some bytes in unpadded URL-safe base64. The first byte is a set of flags: `VERSIONED`
+//! if a word list version follows the letters, and `TEXT` if the letters are written as text.
+//! Puzzles whose letters are all from `a` to `z` are packed into four bytes, little-endian, with
+//! a bit for each optional letter in the low 26 bits and the required letter's place in the
+//! alphabet in the top five, so that their codes are seven characters long, or a few more with a
+//! version. Other puzzles write the required letter and then the optional letters as a string,
+//! as in `save`. The version is an unsigned LEB128 varint.
+
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use crate::save::{read_number, read_str, write_number, write_str};
+use crate::spellingbee::check_letters;
+use crate::utils::letters;
+
+/// The flag for a code with a word list version.
+const VERSIONED: u8 = 1;
+
+/// The flag for a code with its letters written as text.
+const TEXT: u8 = 2;
+
+/// Where the required letter goes in packed letters.
+const REQUIRED_SHIFT: u32 = 27;
+
+/// The characters of URL-safe base64, in order of value.
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A puzzle's letters and word list version, as shared in a link.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzleCode {
+    /// The optional letters, in sorted order.
+    optional_letters: String,
+
+    /// The required letter.
+    required_letter: String,
+
+    /// The version id of the word list, if there is one.
+    lexicon_version: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl PuzzleCode {
+    /// Creates a code for the puzzle with the given letters, already lowercased, and word list
+    /// version id, if any. Returns `None` if there aren't six different optional letters and a
+    /// different required letter.
+    pub fn new(optional_letters: &str, required_letter: &str,
+               lexicon_version: Option<u32>) -> Option<PuzzleCode>
+    {
+        check_letters(optional_letters, required_letter).ok()?;
+        let sorted: BTreeSet<&str> = letters(optional_letters).into_iter().collect();
+        Some(PuzzleCode {
+            optional_letters: sorted.into_iter().collect(),
+            required_letter: required_letter.to_string(),
+            lexicon_version,
+        })
+    }
+
+    /// Returns the optional letters, in sorted order.
+    pub fn optional_letters(&self) -> String {
+        self.optional_letters.clone()
+    }
+
+    /// Returns the required letter.
+    pub fn required_letter(&self) -> String {
+        self.required_letter.clone()
+    }
+
+    /// Returns the version id of the word list, if there is one.
+    pub fn lexicon_version(&self) -> Option<u32> {
+        self.lexicon_version
+    }
+
+    /// Encodes the code in the format described in the module documentation.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![0];
+        match self.packed() {
+            Some(packed) => bytes.extend_from_slice(&packed.to_le_bytes()),
+            None => {
+                bytes[0] |= TEXT;
+                write_str(&mut bytes, &(self.required_letter.clone() + &self.optional_letters));
+            }
+        }
+        if let Some(version) = self.lexicon_version {
+            bytes[0] |= VERSIONED;
+            write_number(&mut bytes, version as usize);
+        }
+        to_base64(&bytes)
+    }
+
+    /// Decodes a code written by `encode()`, returning `None` if it isn't a valid code.
+    pub fn decode(code: &str) -> Option<PuzzleCode> {
+        let bytes = from_base64(code)?;
+        let (&flags, mut rest) = bytes.split_first()?;
+        if flags & !(VERSIONED | TEXT) != 0 {
+            return None;
+        }
+
+        let rest = &mut rest;
+        let (optional, required) = if flags & TEXT != 0 {
+            let text = read_str(rest)?;
+            let letters = letters(&text);
+            let (required, optional) = letters.split_first()?;
+            (optional.concat(), required.to_string())
+        } else {
+            if rest.len() < 4 {
+                return None;
+            }
+            let (packed, tail) = rest.split_at(4);
+            *rest = tail;
+            unpack(u32::from_le_bytes(<[u8; 4]>::try_from(packed).ok()?))?
+        };
+        let lexicon_version = if flags & VERSIONED != 0 {
+            Some(u32::try_from(read_number(rest)?).ok()?)
+        } else {
+            None
+        };
+
+        let puzzle = PuzzleCode::new(&optional, &required, lexicon_version)?;
+        // Only the code `encode()` gives is accepted, so that each puzzle has a single code.
+        if puzzle.encode() == code { Some(puzzle) } else { None }
+    }
+}
+
+impl PuzzleCode {
+    /// Packs the letters into four bytes as described in the module documentation, if they're
+    /// all from `a` to `z`.
+    fn packed(&self) -> Option<u32> {
+        let place = |l: &str| match l.as_bytes() {
+            &[b] if b.is_ascii_lowercase() => Some(u32::from(b - b'a')),
+            _ => None,
+        };
+        let mut packed = place(&self.required_letter)? << REQUIRED_SHIFT;
+        for l in letters(&self.optional_letters) {
+            packed |= 1 << place(l)?;
+        }
+        Some(packed)
+    }
+}
+
+/// Unpacks letters packed by `PuzzleCode::packed()` into the optional letters and the required
+/// letter, returning `None` if the required letter is past `z`.
+fn unpack(packed: u32) -> Option<(String, String)> {
+    let letter = |place: u32| char::from(b'a' + place as u8).to_string();
+    let required = packed >> REQUIRED_SHIFT;
+    if required >= 26 || packed & (1 << 26) != 0 {
+        return None;
+    }
+    let optional = (0..26).filter(|place| packed & (1 << place) != 0).map(letter).collect();
+    Some((optional, letter(required)))
+}
+
+/// Encodes bytes as unpadded URL-safe base64.
+fn to_base64(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        // Each byte takes up a character and a third.
+        for i in 0..=chunk.len() {
+            text.push(char::from(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize]));
+        }
+    }
+    text
+}
+
+/// Decodes unpadded URL-safe base64, returning `None` if the text isn't valid base64.
+fn from_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for chunk in text.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut n = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = BASE64.iter().position(|&b| b == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_puzzle_code() {
+        let code = PuzzleCode::new("wrcglo", "i", None).unwrap();
+        assert_eq!(code.optional_letters(), "cglorw");
+        assert_eq!(code.encode().len(), 7);
+        assert_eq!(PuzzleCode::decode(&code.encode()), Some(code));
+
+        let versioned = PuzzleCode::new("cglorw", "i", Some(300)).unwrap();
+        assert_eq!(PuzzleCode::decode(&versioned.encode()), Some(versioned));
+        let accented = PuzzleCode::new("abcdeé", "ç", Some(2)).unwrap();
+        assert_eq!(PuzzleCode::decode(&accented.encode()), Some(accented));
+
+        assert_eq!(PuzzleCode::new("cglorr", "i", None), None);
+        assert_eq!(PuzzleCode::decode("not a code"), None);
+        assert_eq!(PuzzleCode::decode("AAAAAAA"), None);
+
+        let words = "will\ncowgirl\ncoil\n";
+        let game = SpellingBeeGame::new("cglorw", "i", words, "");
+        let shared = SpellingBeeGame::from_puzzle_code(&game.puzzle_code(Some(1)).unwrap(),
+                                                       words, "");
+        assert_eq!(shared, Some(game));
+        assert_eq!(SpellingBeeGame::new("cglorr", "i", words, "").puzzle_code(None), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! This module provides `GameConfig`, a description of the rules a `SpellingBeeGame` is played
+//! with, so that a frontend can explain them and a save can check that it's being loaded into a
+//! compatible game.
+
+use wasm_bindgen::prelude::*;
+use crate::answers::AnswerSet;
+use crate::scoring::ScoringRules;
+use crate::utils::fnv1a;
+
+/// The rules of a game, from `SpellingBeeGame::config()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameConfig {
+    /// The points each answer is worth and the minimum length of a play.
+    scoring: ScoringRules,
+
+    /// The locale whose case rules are used.
+    locale: String,
+
+    /// The hive ring, if words have to trace a path of adjacent cells.
+    layout: Option<String>,
+
+    /// Whether ASCII spellings of accented letters are accepted.
+    transliteration: bool,
+
+    /// A fingerprint of the answer list.
+    dictionary: String,
+}
+
+#[wasm_bindgen]
+impl GameConfig {
+    /// Returns the minimum length of a play.
+    pub fn min_length(&self) -> usize {
+        self.scoring.min_length()
+    }
+
+    /// Returns the bonus for playing a pangram.
+    pub fn pangram_bonus(&self) -> usize {
+        self.scoring.pangram_bonus()
+    }
+
+    /// Returns the points each answer is worth.
+    pub fn scoring(&self) -> ScoringRules {
+        self.scoring.clone()
+    }
+
+    /// Returns the locale whose case rules are used.
+    pub fn locale(&self) -> String {
+        self.locale.clone()
+    }
+
+    /// Returns the letters around the hive ring in order, if words have to trace a path of
+    /// adjacent cells.
+    pub fn layout(&self) -> Option<String> {
+        self.layout.clone()
+    }
+
+    /// Returns `true` if ASCII spellings of accented letters are accepted.
+    pub fn transliteration(&self) -> bool {
+        self.transliteration
+    }
+
+    /// Returns a fingerprint of the answer list, as 16 hex digits. Two games have the same
+    /// fingerprint when they have the same answers, whichever lexicon they came from.
+    pub fn dictionary(&self) -> String {
+        self.dictionary.clone()
+    }
+
+    /// Returns `true` if a game with this configuration plays by exactly the same rules as one
+    /// with the other.
+    pub fn matches(&self, other: &GameConfig) -> bool {
+        self == other
+    }
+}
+
+impl GameConfig {
+    /// Creates a configuration from its parts, fingerprinting the given answers.
+    pub fn new(scoring: ScoringRules, locale: &str, layout: Option<String>,
+               transliteration: bool, answers: &AnswerSet) -> GameConfig
+    {
+        GameConfig {
+            scoring,
+            locale: locale.to_string(),
+            layout,
+            transliteration,
+            dictionary: format!("{:016x}", fingerprint(answers)),
+        }
+    }
+}
+
+/// Hashes the answers, in order, each followed by a newline (see `utils::fnv1a()`).
+fn fingerprint(answers: &AnswerSet) -> u64 {
+    fnv1a(answers.iter().flat_map(|word| word.bytes().chain(std::iter::once(b'\n'))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_config() {
+        let game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\nzebra\n", "");
+        let config = game.config();
+        assert_eq!((config.min_length(), config.pangram_bonus()), (4, 7));
+        assert_eq!((config.locale(), config.layout(), config.transliteration()),
+                   ("en".to_string(), None, false));
+        assert_eq!(config.dictionary().len(), 16);
+
+        let same = SpellingBeeGame::from_answers("cglorw", "i", "cowgirl\nwill\n");
+        assert!(config.matches(&same.config()));
+        let other = SpellingBeeGame::from_answers("cglorw", "i", "cowgirl\nwill\ncoil\n");
+        assert_ne!(config.dictionary(), other.config().dictionary());
+        assert!(!config.matches(&other.config()));
+
+        let mut kids = ScoringRules::new();
+        kids.set_min_length(3);
+        let kids = SpellingBeeGame::new_with_scoring("cglorw", "i", "will\ncowgirl\n", "", &kids);
+        assert_eq!(kids.config().dictionary(), config.dictionary());
+        assert!(!config.matches(&kids.config()));
+    }
+}
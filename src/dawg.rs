@@ -0,0 +1,227 @@
+//! This module provides `Dawg`, a compact dictionary format: a directed acyclic word graph, the
+//! trie of the words with identical subtrees merged, so that words sharing an ending share nodes
+//! as well as words sharing a beginning. It takes a fraction of the space of the plain word list,
+//! and a game can be built from it by walking only the edges with the puzzle's letters, instead
+//! of parsing and filtering every word.
+//!
+//! The `spellingbee-dawg` binary compiles a main word list and swears list into the format, and
+//! `SpellingBeeGame::from_dawg()` and `LexiconHandle::from_dawg()` load it.
+//!
+//! The format starts with the bytes `DAWG` and a version byte, currently `DAWG_VERSION`. Then
+//! comes the number of nodes, followed by the nodes. Each node is its number of edges, doubled
+//! and plus one if a word ends there, followed by each edge as a character code and the index of
+//! the node it leads to. Every edge leads to an earlier node, so the graph can't have cycles, and
+//! the last node is the root. Numbers are unsigned LEB128 varints, as in `save`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use crate::save::{read_number, write_number};
+
+/// The version of the format written by `to_bytes()`.
+pub const DAWG_VERSION: u8 = 1;
+
+/// The bytes every compiled dictionary starts with.
+const MAGIC: &[u8] = b"DAWG";
+
+/// A node of the graph.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Node {
+    /// Whether a word ends at the node.
+    is_final: bool,
+
+    /// The index of the node's first edge in `Dawg::edges`.
+    first_edge: usize,
+
+    /// The number of edges from the node.
+    edge_count: usize,
+}
+
+/// A word list compiled into a graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dawg {
+    /// The nodes, with every edge leading to an earlier one. The root is last.
+    nodes: Vec<Node>,
+
+    /// Every node's edges, in order of character within each node, as the character and the
+    /// index of the node it leads to.
+    edges: Vec<(char, usize)>,
+}
+
+/// Whether a word ends at a node, and its edges: everything that makes two subtrees identical.
+type Signature = (bool, Vec<(char, usize)>);
+
+/// A node of the trie a `Dawg` is built from.
+#[derive(Default)]
+struct TrieNode {
+    /// Whether a word ends at the node.
+    is_final: bool,
+
+    /// The children, by character.
+    children: BTreeMap<char, TrieNode>,
+}
+
+impl Dawg {
+    /// Compiles the given words. Empty words are skipped.
+    pub fn from_words<I, S>(words: I) -> Dawg
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let mut root = TrieNode::default();
+        for word in words {
+            let word = word.as_ref();
+            if word.is_empty() {
+                continue;
+            }
+            let node = word.chars().fold(&mut root, |node, c| node.children.entry(c).or_default());
+            node.is_final = true;
+        }
+
+        let mut dawg = Dawg { nodes: Vec::new(), edges: Vec::new() };
+        dawg.merge(&root, &mut HashMap::new());
+        dawg
+    }
+
+    /// Adds the node's subtree to the graph, reusing any identical subtree already added, and
+    /// returns the index of its node.
+    fn merge(&mut self, node: &TrieNode, added: &mut HashMap<Signature, usize>) -> usize {
+        let edges: Vec<(char, usize)> = node.children.iter()
+            .map(|(&c, child)| (c, self.merge(child, added)))
+            .collect();
+        let key = (node.is_final, edges);
+        if let Some(&index) = added.get(&key) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            is_final: node.is_final,
+            first_edge: self.edges.len(),
+            edge_count: key.1.len(),
+        });
+        self.edges.extend_from_slice(&key.1);
+        added.insert(key, index);
+        index
+    }
+
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the edges from the node at the given index.
+    fn edges_from(&self, index: usize) -> &[(char, usize)] {
+        let node = &self.nodes[index];
+        &self.edges[node.first_edge..node.first_edge + node.edge_count]
+    }
+
+    /// Returns `true` if the word is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        let mut index = self.nodes.len() - 1;
+        for c in word.chars() {
+            match self.edges_from(index).iter().find(|&&(label, _)| label == c) {
+                Some(&(_, next)) => index = next,
+                None => return false,
+            }
+        }
+        self.nodes[index].is_final
+    }
+
+    /// Returns every word, in order of character code.
+    pub fn words(&self) -> Vec<String> {
+        self.words_using(|_| true)
+    }
+
+    /// Returns the words made only of characters for which `allowed` returns `true`, in order of
+    /// character code. Only edges with allowed characters are followed, so this is much faster
+    /// than going through every word when few characters are allowed.
+    pub fn words_using<F: Fn(char) -> bool>(&self, allowed: F) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut stack = vec![(self.nodes.len() - 1, String::new())];
+        while let Some((index, prefix)) = stack.pop() {
+            if self.nodes[index].is_final {
+                words.push(prefix.clone());
+            }
+            // The stack is last in, first out, so the edges go on it backwards.
+            for &(c, next) in self.edges_from(index).iter().rev() {
+                if allowed(c) {
+                    let mut word = prefix.clone();
+                    word.push(c);
+                    stack.push((next, word));
+                }
+            }
+        }
+        words
+    }
+
+    /// Encodes the dictionary in the format described in the module documentation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(DAWG_VERSION);
+        write_number(&mut bytes, self.nodes.len());
+        for (index, node) in self.nodes.iter().enumerate() {
+            write_number(&mut bytes, node.edge_count * 2 + usize::from(node.is_final));
+            for &(c, next) in self.edges_from(index) {
+                write_number(&mut bytes, c as usize);
+                write_number(&mut bytes, next);
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a dictionary written by `to_bytes()`. Returns `None` if the bytes aren't a
+    /// dictionary of a version this build understands.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Dawg> {
+        let rest = bytes.strip_prefix(MAGIC)?;
+        let (&version, mut rest) = rest.split_first()?;
+        if version != DAWG_VERSION {
+            return None;
+        }
+
+        let rest = &mut rest;
+        let count = read_number(rest)?;
+        // Every node takes at least a byte, which bounds how much a bad count can allocate.
+        let mut dawg = Dawg { nodes: Vec::with_capacity(count.min(rest.len())), edges: Vec::new() };
+        for index in 0..count {
+            let header = read_number(rest)?;
+            let node = Node { is_final: header % 2 == 1, first_edge: dawg.edges.len(),
+                              edge_count: header / 2 };
+            for _ in 0..node.edge_count {
+                let c = char::from_u32(u32::try_from(read_number(rest)?).ok()?)?;
+                let next = read_number(rest)?;
+                if next >= index {
+                    return None;
+                }
+                dawg.edges.push((c, next));
+            }
+            dawg.nodes.push(node);
+        }
+
+        if rest.is_empty() && !dawg.nodes.is_empty() { Some(dawg) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_dawg() {
+        let words = ["coil", "coils", "roil", "roils", "will", "wills", "cowgirl"];
+        let dawg = Dawg::from_words(words.iter());
+        // The "oil" and "oils" endings of "coil" and "roil" are shared, as are the "s" endings.
+        assert!(dawg.node_count() < 20);
+        assert!(dawg.contains("roils") && !dawg.contains("roi") && !dawg.contains("soil"));
+        let mut sorted = words.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(dawg.words(), sorted);
+        assert_eq!(dawg.words_using(|c| "cilo".contains(c)), ["coil"]);
+
+        let bytes = dawg.to_bytes();
+        assert_eq!(Dawg::from_bytes(&bytes), Some(dawg));
+        assert_eq!(Dawg::from_bytes(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(Dawg::from_bytes(b"DAWG\x01\x01\x03\x61\x00"), None);
+
+        let game = SpellingBeeGame::from_dawg("cglorw", "i", &bytes).unwrap();
+        assert_eq!(game, SpellingBeeGame::new("cglorw", "i", &words.join("\n"), ""));
+    }
+}
@@ -0,0 +1,94 @@
+//! This module provides `Definitions`, the trait for anything that can look up what a word means,
+//! so that games can attach a definition to each accepted word and to their hints.
+//!
+//! There are two providers. `WordData` is the offline one, reading definitions from a word→gloss
+//! TSV that a frontend bundles and passes to `SpellingBeeGame::load_definitions()`. `JsDefinitions`
+//! calls a JavaScript function instead, from `SpellingBeeGame::set_definition_provider()`, so that
+//! a frontend can look words up however it likes. A game asks its JavaScript provider first, and
+//! falls back to the definitions it's loaded.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use crate::worddata::WordData;
+
+/// Something that can look up the definitions of words.
+pub trait Definitions {
+    /// Returns the definition of the given word, already lowercased, if there is one.
+    fn define(&self, word: &str) -> Option<String>;
+}
+
+impl Definitions for WordData {
+    fn define(&self, word: &str) -> Option<String> {
+        self.definition(word).map(String::from)
+    }
+}
+
+/// A JavaScript function that takes a word and returns its definition as a string, or anything
+/// else if it doesn't have one. Outside of WebAssembly there's no JavaScript to call, so it never
+/// has a definition.
+#[derive(Clone)]
+pub struct JsDefinitions {
+    /// The function to call.
+    #[cfg(target_arch = "wasm32")]
+    function: js_sys::Function,
+}
+
+impl JsDefinitions {
+    /// Creates a provider that calls the given function.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(function: js_sys::Function) -> JsDefinitions {
+        JsDefinitions { function }
+    }
+
+    /// Creates a provider that calls the given function.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(_function: js_sys::Function) -> JsDefinitions {
+        JsDefinitions {}
+    }
+}
+
+impl Definitions for JsDefinitions {
+    #[cfg(target_arch = "wasm32")]
+    fn define(&self, word: &str) -> Option<String> {
+        let definition = self.function.call1(&wasm_bindgen::JsValue::NULL, &word.into()).ok()?;
+        definition.as_string().filter(|d| !d.trim().is_empty())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn define(&self, _word: &str) -> Option<String> {
+        None
+    }
+}
+
+// A provider is how a game looks words up, not part of the game's state, so any two compare
+// equal, and games that only differ in their providers are the same game.
+impl PartialEq for JsDefinitions {
+    fn eq(&self, _other: &JsDefinitions) -> bool {
+        true
+    }
+}
+
+impl Eq for JsDefinitions {}
+
+impl Hash for JsDefinitions {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl fmt::Debug for JsDefinitions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JsDefinitions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitions() {
+        let mut data = WordData::new();
+        data.load_definitions("will\tthe faculty of choosing\ncoil\t  \n", "en");
+        assert_eq!(data.define("will"), Some("the faculty of choosing".to_string()));
+        assert_eq!(data.define("coil"), None);
+    }
+}
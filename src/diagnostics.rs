@@ -0,0 +1,164 @@
+//! This module reports how much memory games and lexicons take up, so that integrators can measure
+//! what a dictionary choice costs. The sizes of strings and collections are estimates: they count
+//! each string's contents and the string itself, but not the allocator's or the collections'
+//! bookkeeping, which depends on the allocator.
+
+use std::mem::size_of;
+use lexi::{VecLexicon, wordlist};
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::GameError;
+
+/// The size of a WebAssembly memory page.
+#[cfg(target_arch = "wasm32")]
+const WASM_PAGE: usize = 64 * 1024;
+
+/// Returns the estimated heap size of some strings: their contents plus the strings themselves.
+pub fn string_bytes<'a, I: IntoIterator<Item = &'a String>>(strings: I) -> usize {
+    strings.into_iter().map(|s| s.capacity() + size_of::<String>()).sum()
+}
+
+/// The memory taken up by a game, from `SpellingBeeGame::diagnostics()`.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GameDiagnostics {
+    /// The number of answers.
+    answers: usize,
+
+    /// The estimated bytes taken up by the answers.
+    answer_bytes: usize,
+
+    /// The estimated bytes taken up by the words found so far.
+    played_bytes: usize,
+
+    /// The estimated bytes taken up by the overlay sources of answers.
+    source_bytes: usize,
+
+    /// The estimated bytes taken up by definitions and frequencies.
+    word_data_bytes: usize,
+}
+
+#[wasm_bindgen]
+impl GameDiagnostics {
+    /// Returns the number of answers.
+    pub fn answers(&self) -> usize {
+        self.answers
+    }
+
+    /// Returns the estimated bytes taken up by the answers.
+    pub fn answer_bytes(&self) -> usize {
+        self.answer_bytes
+    }
+
+    /// Returns the estimated bytes taken up by the words found so far.
+    pub fn played_bytes(&self) -> usize {
+        self.played_bytes
+    }
+
+    /// Returns the estimated bytes taken up by the sources of overlay answers (see
+    /// `SpellingBeeGame::add_overlay()`).
+    pub fn source_bytes(&self) -> usize {
+        self.source_bytes
+    }
+
+    /// Returns the estimated bytes taken up by definitions and frequencies.
+    pub fn word_data_bytes(&self) -> usize {
+        self.word_data_bytes
+    }
+
+    /// Returns the estimated bytes taken up by the game altogether.
+    pub fn total_bytes(&self) -> usize {
+        self.answer_bytes + self.played_bytes + self.source_bytes + self.word_data_bytes
+    }
+}
+
+impl GameDiagnostics {
+    /// Creates a report from its parts.
+    pub fn new(answers: usize, answer_bytes: usize, played_bytes: usize, source_bytes: usize,
+               word_data_bytes: usize) -> GameDiagnostics
+    {
+        GameDiagnostics { answers, answer_bytes, played_bytes, source_bytes, word_data_bytes }
+    }
+}
+
+/// The size of a lexicon, from `lexicon_diagnostics()`.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LexiconDiagnostics {
+    /// The number of bytes of text the lexicon was parsed from.
+    text_bytes: usize,
+
+    /// The number of words in the parsed lexicon.
+    words: usize,
+
+    /// The estimated bytes taken up by the parsed lexicon.
+    word_bytes: usize,
+}
+
+#[wasm_bindgen]
+impl LexiconDiagnostics {
+    /// Returns the number of bytes of text the lexicon was parsed from.
+    pub fn text_bytes(&self) -> usize {
+        self.text_bytes
+    }
+
+    /// Returns the number of words in the parsed lexicon.
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    /// Returns the estimated bytes the parsed lexicon takes up while a game is being built. Games
+    /// only keep their answers, so this is freed once construction is done.
+    pub fn word_bytes(&self) -> usize {
+        self.word_bytes
+    }
+}
+
+/// Parses the same main word list and swears buffers taken by `SpellingBeeGame::new()` and
+/// reports how big they are. Fails with `GameError::BadWordList`, thrown in JavaScript, if they
+/// can't be parsed.
+#[wasm_bindgen]
+pub fn lexicon_diagnostics(main_words: &str, swears: &str) -> Result<LexiconDiagnostics, JsError> {
+    let lex: VecLexicon = wordlist::parse_strings(main_words, swears)
+        .map_err(|_| GameError::BadWordList)?
+        .into();
+    let words: Vec<String> = lex.into_iter().collect();
+    Ok(LexiconDiagnostics {
+        text_bytes: main_words.len() + swears.len(),
+        words: words.len(),
+        word_bytes: string_bytes(&words),
+    })
+}
+
+/// Returns the size of the WebAssembly module's memory in bytes, or 0 when not running as
+/// WebAssembly. The memory only grows, so this is the most the module has needed at once.
+#[wasm_bindgen]
+pub fn memory_bytes() -> usize {
+    #[cfg(target_arch = "wasm32")]
+    {
+        core::arch::wasm32::memory_size(0) * WASM_PAGE
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_diagnostics() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\n");
+        let before = game.diagnostics();
+        assert_eq!(before.answers(), 2);
+        assert!(before.answer_bytes() >= "willcowgirl".len());
+        assert_eq!(before.played_bytes(), 0);
+
+        game.play("will");
+        game.load_definitions("will\tthe faculty of choosing\n");
+        let after = game.diagnostics();
+        assert!(after.played_bytes() > 0 && after.word_data_bytes() > 0);
+        assert!(after.total_bytes() > before.total_bytes());
+    }
+}
@@ -0,0 +1,199 @@
+//! This module provides `PuzzleGenerator`, which builds games from a word list without anyone
+//! having to pick the letters by hand. Given a pangram, it tries each of its letters as the
+//! required one and keeps the choice that best fits a `LetterChoice`, so that the puzzle isn't
+//! left with a handful of answers or hundreds of them.
+//!
+//! It can also pick the letters itself, at random, under the constraints in a `RandomConfig`, and
+//! `daily_config()` seeds that from a date so that every player gets the same puzzle each day.
+
+use std::collections::BTreeSet;
+use crate::search::{Candidate, Interest, PuzzleSearch};
+use crate::spellingbee::{GameError, SpellingBeeGame};
+use crate::utils::{fnv1a, is_date, Rng};
+
+/// How to choose the required letter from a pangram's letters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LetterChoice {
+    /// The letter that gives the most answers.
+    MostAnswers,
+
+    /// The letter that gives the fewest answers.
+    FewestAnswers,
+
+    /// The letter that gives the most answers without going over `max`, as long as there are at
+    /// least `min`.
+    Between { min: usize, max: usize },
+}
+
+impl LetterChoice {
+    /// Picks the puzzle that fits best out of the given ones, if any of them fit at all. Ties go
+    /// to the earliest.
+    fn pick(self, candidates: Vec<Candidate>) -> Option<Candidate> {
+        let count = |c: &Candidate| c.answers.len();
+        let mut best: Option<Candidate> = None;
+        for candidate in candidates {
+            let better = match (self, &best) {
+                (LetterChoice::Between { min, max }, _)
+                    if count(&candidate) < min || count(&candidate) > max => false,
+                (_, None) => true,
+                (LetterChoice::FewestAnswers, Some(b)) => count(&candidate) < count(b),
+                (_, Some(b)) => count(&candidate) > count(b),
+            };
+            if better {
+                best = Some(candidate);
+            }
+        }
+        best
+    }
+}
+
+/// The constraints on a randomly generated puzzle. Every puzzle has at least one pangram.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RandomConfig {
+    /// The fewest answers the puzzle may have.
+    pub min_answers: usize,
+
+    /// The most answers the puzzle may have.
+    pub max_answers: usize,
+
+    /// Letters the puzzle may not use. By default, this is `s`, as in the official game, so that
+    /// plurals don't pad out the answers.
+    pub excluded_letters: String,
+
+    /// The seed used to pick the letters.
+    pub seed: u64,
+}
+
+impl Default for RandomConfig {
+    fn default() -> RandomConfig {
+        RandomConfig {
+            min_answers: 20,
+            max_answers: 80,
+            excluded_letters: "s".to_string(),
+            seed: 0,
+        }
+    }
+}
+
+/// A word list prepared for generating puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleGenerator {
+    /// The words, prepared for finding each puzzle's answers.
+    search: PuzzleSearch,
+}
+
+impl PuzzleGenerator {
+    /// Creates a generator from the same main word list and swears buffers taken by
+    /// `SpellingBeeGame::new()`. Fails with `GameError::BadWordList` if they can't be parsed.
+    pub fn new(main_words: &str, swears: &str) -> Result<PuzzleGenerator, GameError> {
+        Ok(PuzzleGenerator { search: PuzzleSearch::new(main_words, swears)? })
+    }
+
+    /// Creates a generator over the given words, which are filtered as in
+    /// `PuzzleSearch::from_words()`.
+    pub fn from_words<I: IntoIterator<Item = String>>(words: I) -> PuzzleGenerator {
+        PuzzleGenerator { search: PuzzleSearch::from_words(words) }
+    }
+
+    /// Returns the word list prepared for searching.
+    pub fn search(&self) -> &PuzzleSearch {
+        &self.search
+    }
+
+    /// Returns the puzzle for each choice of required letter from the pangram's letters, in
+    /// alphabetical order of the required letter. Returns nothing if the pangram doesn't have
+    /// exactly seven distinct lowercase ASCII letters.
+    pub fn candidates(&self, pangram: &str) -> Vec<Candidate> {
+        let distinct: BTreeSet<char> = pangram.chars().collect();
+        distinct.iter()
+            .filter_map(|&required| self.search.evaluate(pangram, required, &Interest::default()))
+            .collect()
+    }
+
+    /// Builds the game with the pangram's letters and the required letter picked by `choice`.
+    /// Returns `None` if the pangram isn't seven distinct lowercase ASCII letters, or if no
+    /// letter fits `choice`.
+    pub fn from_pangram(&self, pangram: &str, choice: LetterChoice) -> Option<SpellingBeeGame> {
+        choice.pick(self.candidates(pangram)).map(|c| game(&c))
+    }
+
+    /// Builds a game with random letters that meet the constraints in `config`. Returns `None`
+    /// if no puzzle meets them.
+    ///
+    /// Rather than trying letters blindly, this goes through the letter sets that have a pangram
+    /// in a random order, trying the required letters of each in a random order, so it stops at
+    /// the first puzzle that fits and only sees every puzzle when none do.
+    pub fn random(&self, config: &RandomConfig) -> Option<SpellingBeeGame> {
+        let mut rng = Rng::new(config.seed);
+        let mut sets: Vec<String> = self.search.pangram_letters()
+            .filter(|letters| !letters.chars().any(|c| config.excluded_letters.contains(c)))
+            .collect();
+        rng.shuffle(&mut sets);
+
+        let fits = |c: &Candidate| {
+            c.answers.len() >= config.min_answers && c.answers.len() <= config.max_answers
+        };
+        for letters in sets {
+            let mut candidates = self.candidates(&letters);
+            rng.shuffle(&mut candidates);
+            if let Some(candidate) = candidates.into_iter().find(fits) {
+                return Some(game(&candidate));
+            }
+        }
+        None
+    }
+}
+
+/// Returns the default constraints with a seed derived from the given `YYYY-MM-DD` date, or
+/// `None` if it isn't a date. The seed is the same on every platform, so every player with the
+/// same word lists gets the same puzzle for the same date.
+pub fn daily_config(date: &str) -> Option<RandomConfig> {
+    if !is_date(date) {
+        return None;
+    }
+    Some(RandomConfig { seed: fnv1a(date.bytes()), ..RandomConfig::default() })
+}
+
+/// Builds the game for a puzzle found by a search.
+pub fn game(candidate: &Candidate) -> SpellingBeeGame {
+    SpellingBeeGame::from_answers(&candidate.optional_letters,
+                                  &candidate.required_letter.to_string(),
+                                  &candidate.answers.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pangram() {
+        let generator = PuzzleGenerator::from_words([
+            "cowgirl", "will", "crow", "grill", "growl", "wilco", "clog", "glow", "logic", "coil",
+            "roil", "girl", "wool",
+        ].iter().map(|w| w.to_string()));
+        assert_eq!(generator.candidates("cowgirl").len(), 7);
+
+        let most = generator.from_pangram("cowgirl", LetterChoice::MostAnswers).unwrap();
+        assert_eq!(most.required_letter(), "l");
+        assert_eq!(most.answers().len(), 12);
+        let fewest = generator.from_pangram("cowgirl", LetterChoice::FewestAnswers).unwrap();
+        assert_eq!((fewest.required_letter(), fewest.answers().len()), ("c".to_string(), 6));
+
+        let choice = LetterChoice::Between { min: 2, max: 8 };
+        let between = generator.from_pangram("cowgirl", choice).unwrap();
+        assert_eq!((between.required_letter(), between.answers().len()), ("i".to_string(), 8));
+        assert_eq!(generator.from_pangram("cowgirl", LetterChoice::Between { min: 20, max: 30 }),
+                   None);
+        assert_eq!(generator.from_pangram("cowgirls", LetterChoice::MostAnswers), None);
+
+        let config = RandomConfig { min_answers: 7, max_answers: 7, ..RandomConfig::default() };
+        let random = generator.random(&config).unwrap();
+        assert!(["g", "w"].contains(&random.required_letter().as_str()));
+        let config = RandomConfig { excluded_letters: "w".to_string(), ..config };
+        assert_eq!(generator.random(&config), None);
+
+        assert_eq!(daily_config("2024-05-06"), daily_config("2024-05-06"));
+        assert_ne!(daily_config("2024-05-06"), daily_config("2024-05-07"));
+        assert_eq!(daily_config("May 6"), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! This module provides the GraphQL schema the HTTP API serves at `/graphql`, built with
+//! async-graphql, so that clients can ask for exactly the parts of games, their hints and
+//! statistics, and leaderboards they want in one request, with any GraphQL client or tool. It is
+//! only built natively, with the `http-api` feature.
+//!
+//! The schema can be introspected like any other. In short, it is:
+//!
+//! ```text
+//! type Query {
+//!   game(id: ID!): Game!
+//!   leaderboard(puzzle: String!): Leaderboard!
+//!   me: Player
+//! }
+//!
+//! type Mutation {
+//!   startSession(name: String!): Session!
+//!   createGame(letters: String!, center: String!): Game!
+//!   play(game: ID!, word: String!): Play!
+//!   finish(game: ID!): Game!
+//! }
+//! ```
+//!
+//! Each of those does what the route for it does, and its type has the same fields as the body
+//! of the route's response, in camel case, like `maxScore`. A `Game` also has its `hints`, with
+//! the grid and pairs as lists rather than objects, and its `stats`. `me` is the player whose
+//! session token came with the request, if one did, and games belong to players just as for the
+//! routes. A field that fails is `null`, with an error saying why, whose `status` extension is
+//! the status the route would have refused the request with; a play beyond the rate limit also
+//! has a `retryAfter` extension, in milliseconds.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, Error, ErrorExtensions, Object, Result, Schema,
+    SimpleObject, ID,
+};
+use crate::api::{lock, Game, Hints, Leaderboard, Play, Session, State, TOO_MANY_PLAYS};
+use crate::session::Player;
+
+/// The GraphQL schema the HTTP API serves.
+pub type ApiSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// Who a request was made by, which is in every request's data.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Caller {
+    /// The player whose session token came with the request, if one did.
+    pub(crate) player: Option<Player>,
+
+    /// The key of the rate limiting bucket plays come out of.
+    pub(crate) bucket: String,
+
+    /// When the request was made, in milliseconds since the Unix epoch.
+    pub(crate) now: u64,
+}
+
+/// Builds the schema, whose fields are resolved against the given state.
+pub(crate) fn schema(state: Arc<Mutex<State>>) -> ApiSchema {
+    Schema::build(Query, Mutation, EmptySubscription).data(state).finish()
+}
+
+/// The fields that can be queried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The state of the game with the given id.
+    async fn game(&self, ctx: &Context<'_>, id: ID) -> Result<Game> {
+        let (mut state, caller) = parts(ctx);
+        let id = open(&mut state, caller, &id)?;
+        Ok(state.describe(id))
+    }
+
+    /// The leaderboard for the puzzle with the given id, like `i-cglorw`.
+    async fn leaderboard(&self, ctx: &Context<'_>, puzzle: String) -> Result<Leaderboard> {
+        parts(ctx).0.leaderboard(&puzzle).map_err(refused)
+    }
+
+    /// The player whose session token came with the request, if one did.
+    async fn me(&self, ctx: &Context<'_>) -> Option<Player> {
+        ctx.data_unchecked::<Caller>().player.clone()
+    }
+}
+
+/// The changes that can be made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Starts a session for the player with the given name.
+    async fn start_session(&self, ctx: &Context<'_>, name: String) -> Result<Session> {
+        let (mut state, caller) = parts(ctx);
+        state.start_session(&name, caller.now).map_err(refused)
+    }
+
+    /// Creates a game, which belongs to the player whose session token came with the request, if
+    /// one did.
+    async fn create_game(&self, ctx: &Context<'_>, letters: String,
+                         center: String) -> Result<Game>
+    {
+        let (mut state, caller) = parts(ctx);
+        state.create(&letters, &center, caller.player.as_ref()).map_err(refused)
+    }
+
+    /// Plays a word in the game with the given id.
+    async fn play(&self, ctx: &Context<'_>, game: ID, word: String) -> Result<Play> {
+        let (mut state, caller) = parts(ctx);
+        let id = open(&mut state, caller, &game)?;
+        state.take_play(caller.bucket.clone(), caller.now).map_err(|wait| {
+            Error::new(TOO_MANY_PLAYS).extend_with(|_, e| {
+                e.set("status", 429);
+                e.set("retryAfter", wait);
+            })
+        })?;
+        state.play(id, &word).map_err(refused)
+    }
+
+    /// Finishes the game with the given id, putting it on the leaderboard if the request came
+    /// with a session token.
+    async fn finish(&self, ctx: &Context<'_>, game: ID) -> Result<Game> {
+        let (mut state, caller) = parts(ctx);
+        let id = open(&mut state, caller, &game)?;
+        state.finish(id, caller.player.clone()).map_err(refused)
+    }
+}
+
+#[ComplexObject]
+impl Game {
+    /// The hints for the answers that haven't been found yet.
+    async fn hints(&self, ctx: &Context<'_>) -> Result<Hints> {
+        let (mut state, caller) = parts(ctx);
+        let id = open(&mut state, caller, &self.id)?;
+        Ok(state.hints(id))
+    }
+
+    /// The game's statistics.
+    async fn stats(&self, ctx: &Context<'_>) -> Result<Stats> {
+        let (mut state, caller) = parts(ctx);
+        let id = open(&mut state, caller, &self.id)?;
+        let game = state.game(id);
+        let pangrams_found = game.played_words().iter().filter(|w| game.pangrams().contains(w));
+        Ok(Stats {
+            words_found: game.words_found(),
+            pangrams_found: pangrams_found.count(),
+            pangram_count: game.pangram_count(),
+            percent_words_found: game.percent_words_found(),
+            percent_score: game.percent_score(),
+            points_to_genius: game.points_to_genius(),
+            hint_points_spent: game.hint_points_spent(),
+            best_streak: game.best_streak(),
+            elapsed: game.elapsed_time().map(|t| t.round() as u64),
+        })
+    }
+}
+
+#[Object]
+impl Hints {
+    /// The number of answers by their first letter, and then by their length.
+    async fn grid(&self) -> Vec<HintRow> {
+        self.grid.iter().map(|(start, lengths)| HintRow {
+            start: start.clone(),
+            lengths: lengths.iter()
+                .map(|(&length, &count)| LengthCount { length, count })
+                .collect(),
+        }).collect()
+    }
+
+    /// The number of answers by their first two letters.
+    async fn pairs(&self) -> Vec<PairCount> {
+        self.pairs.iter()
+            .map(|(pair, &count)| PairCount { pair: pair.clone(), count })
+            .collect()
+    }
+}
+
+/// The answers that haven't been found yet that start with one letter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SimpleObject)]
+pub struct HintRow {
+    /// The letter.
+    pub start: String,
+
+    /// The number of answers by their length, shortest first.
+    pub lengths: Vec<LengthCount>,
+}
+
+/// The number of answers of one length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SimpleObject)]
+pub struct LengthCount {
+    /// The length.
+    pub length: usize,
+
+    /// The number of answers.
+    pub count: usize,
+}
+
+/// The number of answers that start with two letters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SimpleObject)]
+pub struct PairCount {
+    /// The two letters.
+    pub pair: String,
+
+    /// The number of answers.
+    pub count: usize,
+}
+
+/// A game's statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SimpleObject)]
+pub struct Stats {
+    /// The number of words found.
+    pub words_found: usize,
+
+    /// The number of pangrams found.
+    pub pangrams_found: usize,
+
+    /// The number of pangrams.
+    pub pangram_count: usize,
+
+    /// The percentage of the answers found, rounded down.
+    pub percent_words_found: u32,
+
+    /// The score as a percentage of the most it can be, rounded down.
+    pub percent_score: u32,
+
+    /// The points still needed to reach Genius.
+    pub points_to_genius: usize,
+
+    /// The points spent on hints.
+    pub hint_points_spent: usize,
+
+    /// The longest streak of valid plays.
+    pub best_streak: usize,
+
+    /// The milliseconds from the start of the clock to the most recent word found, if the clock
+    /// has started.
+    pub elapsed: Option<u64>,
+}
+
+/// Returns the state a field is resolved against, locked, and who the request was made by.
+fn parts<'a>(ctx: &Context<'a>) -> (MutexGuard<'a, State>, &'a Caller) {
+    (lock(ctx.data_unchecked::<Arc<Mutex<State>>>()), ctx.data_unchecked::<Caller>())
+}
+
+/// Returns the id of the game with the given id if the caller can have it, as for the routes.
+fn open(state: &mut State, caller: &Caller, id: &ID) -> Result<u64> {
+    let id = id.parse().map_err(|_| refused((404, "there's no game with this id".to_string())))?;
+    state.open(id, caller.player.as_ref()).map_err(refused)
+}
+
+/// Returns the error for a request the API refused with the given status and message.
+fn refused((status, message): (u16, String)) -> Error {
+    Error::new(message).extend_with(|_, e| e.set("status", status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema() {
+        let sdl = Schema::build(Query, Mutation, EmptySubscription).finish().sdl();
+        for field in ["game(id: ID!): Game!", "me: Player", "play(game: ID!, word: String!): Play!",
+                      "hints: Hints!", "lengths: [LengthCount!]!", "elapsed: Int"] {
+            assert!(sdl.contains(field), "the schema has no {}", field);
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! This module provides the hints a game can give about the answers that haven't been found yet.
+//! Hints go easiest first: answers are ordered by how common they are, using frequency data if
+//! it's been loaded (see `SpellingBeeGame::load_frequencies()`), so a hint points at a word the
+//! player most likely knows before it spoils an obscure one.
+//!
+//! For players who want less of a nudge, `HintGrid` gives the grid from the official hints
+//! column instead: how many answers start with each letter at each length. `two_letter_list()`
+//! gives the other half of that column, how many answers start with each pair of letters.
+//!
+//! Games can also charge for hints, with `HintCosts`: buying a hint deducts its cost from the
+//! net score (see `SpellingBeeGame::net_score()`), and every purchase goes in a ledger that's
+//! saved with the game.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::utils::{js_array, letters, letter_count};
+use crate::definitions::Definitions;
+use crate::worddata::WordData;
+
+/// The number of letters a hint gives away.
+pub const HINT_START_LETTERS: usize = 2;
+
+/// A hint about one answer: how it starts and how long it is.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hint {
+    /// The first letters of the answer.
+    start: String,
+
+    /// The number of letters in the answer.
+    length: usize,
+
+    /// The definition of the answer, if it has one.
+    definition: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Hint {
+    /// Returns the first letters of the answer.
+    pub fn start(&self) -> String {
+        self.start.clone()
+    }
+
+    /// Returns the number of letters in the answer.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the definition of the answer, if it has one.
+    pub fn definition(&self) -> Option<String> {
+        self.definition.clone()
+    }
+}
+
+impl Hint {
+    /// Creates a hint about the given answer.
+    pub fn for_word(word: &str) -> Hint {
+        Hint {
+            start: letters(word).into_iter().take(HINT_START_LETTERS).collect(),
+            length: letter_count(word),
+            definition: None,
+        }
+    }
+
+    /// Creates a hint about the given answer, with its definition if the
+    /// given definitions have it.
+    pub fn defined(word: &str, definitions: &dyn Definitions) -> Hint {
+        Hint { definition: definitions.define(word), ..Hint::for_word(word) }
+    }
+}
+
+/// The kinds of hints that can be bought.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HintKind {
+    /// One more letter of an answer, from the start.
+    Letter,
+
+    /// The length of an answer.
+    Length,
+
+    /// The first `HINT_START_LETTERS` letters of a pangram.
+    PangramStart,
+}
+
+impl HintKind {
+    /// Every kind of hint, in the order they're numbered in saves.
+    pub const ALL: [HintKind; 3] = [HintKind::Letter, HintKind::Length, HintKind::PangramStart];
+}
+
+/// The points each kind of hint costs, passed to `SpellingBeeGame::set_hint_costs()`.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HintCosts {
+    /// The points for a letter.
+    letter: usize,
+
+    /// The points for a length.
+    length: usize,
+
+    /// The points for the start of a pangram.
+    pangram_start: usize,
+}
+
+#[wasm_bindgen]
+impl HintCosts {
+    /// Creates costs with every hint free, to be changed with the setters.
+    pub fn new() -> HintCosts {
+        HintCosts::default()
+    }
+
+    /// Returns the points the given kind of hint costs.
+    pub fn cost(&self, kind: HintKind) -> usize {
+        match kind {
+            HintKind::Letter => self.letter,
+            HintKind::Length => self.length,
+            HintKind::PangramStart => self.pangram_start,
+        }
+    }
+
+    /// Sets the points the given kind of hint costs.
+    pub fn set_cost(&mut self, kind: HintKind, points: usize) {
+        match kind {
+            HintKind::Letter => self.letter = points,
+            HintKind::Length => self.length = points,
+            HintKind::PangramStart => self.pangram_start = points,
+        }
+    }
+}
+
+/// A hint bought in a game, as kept in its ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HintPurchase {
+    /// The kind of hint.
+    pub kind: HintKind,
+
+    /// The answer the hint was about.
+    pub word: String,
+
+    /// The points it cost.
+    pub cost: usize,
+}
+
+/// Orders words easiest first: the most frequent first, then words without frequency data,
+/// shortest first, then alphabetically.
+pub fn easiest_first<'a, I>(words: I, data: &WordData) -> Vec<&'a str>
+    where I: IntoIterator<Item = &'a str>
+{
+    let mut words: Vec<&'a str> = words.into_iter().collect();
+    words.sort_by_key(|w| (Reverse(data.frequency(w)), letter_count(w), *w));
+    words
+}
+
+/// Returns the number of answers that start with each pair of letters, such as `co`. Answers
+/// shorter than two letters are counted under their whole spelling.
+pub fn two_letter_counts<I, S>(answers: I) -> BTreeMap<String, usize>
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+{
+    let mut counts = BTreeMap::new();
+    for word in answers {
+        let start: String = letters(word.as_ref()).into_iter().take(2).collect();
+        *counts.entry(start).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Formats counts from `two_letter_counts()` in the community hint format, in uppercase:
+/// `CO: 4, GI: 2`.
+pub fn two_letter_list<I, S>(answers: I) -> String
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+{
+    two_letter_counts(answers).iter()
+        .map(|(start, n)| format!("{}: {}", start.to_uppercase(), n))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// The number of answers that start with each letter at each length.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HintGrid {
+    /// For each starting letter, the number of answers of each length.
+    rows: BTreeMap<String, BTreeMap<usize, usize>>,
+}
+
+#[wasm_bindgen]
+impl HintGrid {
+    /// Returns the starting letters that have answers, as an array of strings in alphabetical
+    /// order.
+    pub fn starts(&self) -> JsValue {
+        js_array(self.rows.keys())
+    }
+
+    /// Returns the lengths that have answers, shortest first.
+    pub fn lengths(&self) -> Vec<usize> {
+        self.length_counts().keys().copied().collect()
+    }
+
+    /// Returns the number of answers that start with the given letter and have the given length.
+    pub fn count(&self, start: &str, length: usize) -> usize {
+        self.rows.get(start).and_then(|row| row.get(&length)).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of answers that start with the given letter.
+    pub fn row_total(&self, start: &str) -> usize {
+        self.rows.get(start).map_or(0, |row| row.values().sum())
+    }
+
+    /// Returns the number of answers with the given length.
+    pub fn column_total(&self, length: usize) -> usize {
+        self.rows.values().filter_map(|row| row.get(&length)).sum()
+    }
+
+    /// Returns the number of answers.
+    pub fn total(&self) -> usize {
+        self.rows.values().flat_map(|row| row.values()).sum()
+    }
+
+    /// Renders the grid as plain text, with a column for each length and a row for each starting
+    /// letter, then a row and a column of totals. Lengths without answers for a letter show as
+    /// `-`.
+    pub fn to_text(&self) -> String {
+        let lengths = self.lengths();
+        let mut text = String::from("  ");
+        for len in &lengths {
+            text.push_str(&format!("{:>4}", len));
+        }
+        text.push_str("   Σ\n");
+        for (start, row) in &self.rows {
+            text.push_str(&start.to_uppercase());
+            text.push(':');
+            for len in &lengths {
+                match row.get(len) {
+                    Some(n) => text.push_str(&format!("{:>4}", n)),
+                    None => text.push_str("   -"),
+                }
+            }
+            text.push_str(&format!("{:>4}\n", row.values().sum::<usize>()));
+        }
+        text.push_str("Σ:");
+        for n in self.length_counts().values() {
+            text.push_str(&format!("{:>4}", n));
+        }
+        text.push_str(&format!("{:>4}\n", self.total()));
+        text
+    }
+}
+
+impl HintGrid {
+    /// Creates the grid for the given answers.
+    pub fn new<I, S>(answers: I) -> HintGrid
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let mut rows: BTreeMap<String, BTreeMap<usize, usize>> = BTreeMap::new();
+        for word in answers {
+            let word = word.as_ref();
+            if let Some(first) = letters(word).first() {
+                *rows.entry(first.to_string()).or_default()
+                    .entry(letter_count(word)).or_insert(0) += 1;
+            }
+        }
+        HintGrid { rows }
+    }
+
+    /// Returns the rows: for each starting letter, the number of answers of each length.
+    pub fn rows(&self) -> &BTreeMap<String, BTreeMap<usize, usize>> {
+        &self.rows
+    }
+
+    /// Returns the number of answers of each length.
+    pub fn length_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for row in self.rows.values() {
+            for (&len, &n) in row {
+                *counts.entry(len).or_insert(0) += n;
+            }
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_grid() {
+        let answers: Vec<String> = ["will", "cowgirl", "coil", "logic", "girl"].iter()
+            .map(|w| w.to_string())
+            .collect();
+        let grid = HintGrid::new(&answers);
+        assert_eq!(grid.lengths(), vec![4, 5, 7]);
+        assert_eq!(grid.count("c", 7), 1);
+        assert_eq!(grid.count("c", 5), 0);
+        assert_eq!((grid.row_total("c"), grid.column_total(4), grid.total()), (2, 3, 5));
+        assert!(grid.to_text().starts_with("     4   5   7   Σ\nC:   1   -   1   2\n"));
+        assert_eq!(two_letter_list(&answers), "CO: 2, GI: 1, LO: 1, WI: 1");
+    }
+
+    #[test]
+    fn test_easiest_first() {
+        let mut data = WordData::new();
+        data.load_frequencies("cowgirl\t900\nlogic\t40\n", "en");
+        let words = ["will", "logic", "girl", "cowgirl", "coil", "rococo"];
+        assert_eq!(easiest_first(words.iter().copied(), &data),
+                   vec!["cowgirl", "logic", "coil", "girl", "will", "rococo"]);
+        assert_eq!(easiest_first(Vec::new(), &data), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,337 @@
+//! This module provides `History`, the complete record of every game a player has played, so that
+//! it can be exported for archiving or for analysis elsewhere and imported again. Where `Stats`
+//! keeps only what its totals need, a history keeps each puzzle's letters, every word found with
+//! when it was found, every hint bought, and the result.
+//!
+//! With the `json` feature, which is on by default, a history is exported as newline-delimited
+//! JSON, with one object per game, in order of puzzle id, so that tools that read a line at a
+//! time can stream it:
+//!
+//! ```text
+//! {"puzzle":"i-cglorw","letters":"cglorw","center":"i","started_at":1715000000000,
+//!  "plays":[{"word":"coil","time":5200}],"hints":[{"kind":"length","word":"will","cost":1}],
+//!  "score":1,"unaided_score":1,"max_score":16,"answer_count":3,"rank":"Good","finished":false}
+//! ```
+//!
+//! (shown over three lines here, but one in the export). A play's `time` is in milliseconds from
+//! the start of the game's clock, and `started_at` is in milliseconds since the Unix epoch; both
+//! are `null` for a game that didn't keep time. A hint's `kind` is `letter`, `length`, or
+//! `pangram_start`. The `rank` is only for readers, and is worked out again from the scores on
+//! import.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use crate::hints::HintKind;
+use crate::rank::Rank;
+use crate::spellingbee::SpellingBeeGame;
+
+/// A word found in a game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct PlayRecord {
+    /// The word.
+    pub word: String,
+
+    /// The milliseconds from the start of the game's clock the word was found at, if it kept time.
+    pub time: Option<u64>,
+}
+
+/// A hint bought in a game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HintRecord {
+    /// The kind of hint.
+    pub kind: HintKind,
+
+    /// The answer the hint was about.
+    pub word: String,
+
+    /// The points it cost.
+    pub cost: usize,
+}
+
+/// Everything kept of one game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameHistory {
+    /// The puzzle's id.
+    pub puzzle_id: String,
+
+    /// The optional letters.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: String,
+
+    /// When the game's clock started, in milliseconds since the Unix epoch, if it did.
+    pub started_at: Option<u64>,
+
+    /// The words found, in the order they were found.
+    pub plays: Vec<PlayRecord>,
+
+    /// The hints bought, in the order they were bought.
+    pub hints: Vec<HintRecord>,
+
+    /// The score, less hint costs.
+    pub score: usize,
+
+    /// The score from the words found before the answers were revealed, less hint costs.
+    pub unaided_score: usize,
+
+    /// The maximum score.
+    pub max_score: usize,
+
+    /// The number of answers.
+    pub answer_count: usize,
+
+    /// Whether the game was finished.
+    pub finished: bool,
+}
+
+impl GameHistory {
+    /// Returns the rank the game reached, from the words found before the answers were revealed.
+    pub fn rank(&self) -> Rank {
+        Rank::for_score(self.unaided_score, self.max_score)
+    }
+
+    /// Returns the game as a line of an export.
+    #[cfg(feature = "json")]
+    fn to_exported(&self) -> ExportedGame {
+        let hints = self.hints.iter().map(|h| ExportedHint {
+            kind: hint_kind_name(h.kind).to_string(),
+            word: h.word.clone(),
+            cost: h.cost,
+        }).collect();
+        ExportedGame {
+            puzzle: self.puzzle_id.clone(),
+            letters: self.optional_letters.clone(),
+            center: self.required_letter.clone(),
+            started_at: self.started_at,
+            plays: self.plays.clone(),
+            hints,
+            score: self.score,
+            unaided_score: self.unaided_score,
+            max_score: self.max_score,
+            answer_count: self.answer_count,
+            rank: self.rank().name().to_string(),
+            finished: self.finished,
+        }
+    }
+
+    /// Reads a game from a line of an export, returning `None` if a hint's kind isn't one.
+    #[cfg(feature = "json")]
+    fn from_exported(game: ExportedGame) -> Option<GameHistory> {
+        let hints = game.hints.into_iter().map(|hint| Some(HintRecord {
+            kind: *HintKind::ALL.iter().find(|&&k| hint_kind_name(k) == hint.kind)?,
+            word: hint.word,
+            cost: hint.cost,
+        })).collect::<Option<_>>()?;
+        Some(GameHistory {
+            puzzle_id: game.puzzle,
+            optional_letters: game.letters,
+            required_letter: game.center,
+            started_at: game.started_at,
+            plays: game.plays,
+            hints,
+            score: game.score,
+            unaided_score: game.unaided_score,
+            max_score: game.max_score,
+            answer_count: game.answer_count,
+            finished: game.finished,
+        })
+    }
+}
+
+/// A game as a line of an export, in the format described in the module documentation.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct ExportedGame {
+    /// The puzzle's id.
+    puzzle: String,
+
+    /// The optional letters.
+    letters: String,
+
+    /// The required letter.
+    center: String,
+
+    /// When the game's clock started, in milliseconds since the Unix epoch, if it kept time.
+    started_at: Option<u64>,
+
+    /// Every word found, in the order they were found.
+    plays: Vec<PlayRecord>,
+
+    /// Every hint bought, in the order they were bought.
+    hints: Vec<ExportedHint>,
+
+    /// The score, less hint costs.
+    score: usize,
+
+    /// The score from the words found before the answers were revealed, less hint costs.
+    unaided_score: usize,
+
+    /// The score for finding every answer.
+    max_score: usize,
+
+    /// The number of answers.
+    answer_count: usize,
+
+    /// The name of the rank reached, which is only for readers, so it isn't read back.
+    #[serde(skip_deserializing)]
+    rank: String,
+
+    /// Whether the game was finished.
+    finished: bool,
+}
+
+/// A hint bought in a game, as it is in an export.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct ExportedHint {
+    /// The name of the kind of hint, from `hint_kind_name()`.
+    kind: String,
+
+    /// The answer the hint was about.
+    word: String,
+
+    /// The points it cost.
+    cost: usize,
+}
+
+/// Every game a player has played, by puzzle id.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct History {
+    /// The games recorded, by puzzle id.
+    games: BTreeMap<String, GameHistory>,
+}
+
+#[wasm_bindgen]
+impl History {
+    /// Creates a history with no games.
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Records a game of the puzzle with the given id, finished or not, replacing anything
+    /// recorded for the puzzle before.
+    pub fn record(&mut self, puzzle_id: &str, game: &SpellingBeeGame) {
+        let timed = game.started_at().is_some();
+        let plays = game.play_order().iter().zip(game.play_times())
+            .map(|(word, &time)| {
+                PlayRecord { word: word.clone(), time: Some(time).filter(|_| timed) }
+            })
+            .collect();
+        let hints = game.hint_ledger().iter()
+            .map(|h| HintRecord { kind: h.kind, word: h.word.clone(), cost: h.cost })
+            .collect();
+        self.insert(GameHistory {
+            puzzle_id: puzzle_id.to_string(),
+            optional_letters: game.optional_letters(),
+            required_letter: game.required_letter(),
+            started_at: game.started_at(),
+            plays,
+            hints,
+            score: game.score(),
+            unaided_score: game.unaided_score(),
+            max_score: game.max_score(),
+            answer_count: game.total_answer_count(),
+            finished: game.is_finished(),
+        });
+    }
+
+    /// Returns the number of games recorded.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Returns `true` if no games have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Exports the history as newline-delimited JSON, with a line for each game.
+    #[cfg(feature = "json")]
+    pub fn export_ndjson(&self) -> String {
+        self.games.values().map(|game| {
+            let line = serde_json::to_string(&game.to_exported()).expect("games serialize");
+            format!("{}\n", line)
+        }).collect()
+    }
+
+    /// Imports a history exported by `export_ndjson()`, returning `None` if any line that isn't
+    /// blank isn't a valid game. A puzzle that appears more than once keeps its last game.
+    #[cfg(feature = "json")]
+    pub fn import_ndjson(text: &str) -> Option<History> {
+        let mut history = History::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            history.insert(GameHistory::from_exported(serde_json::from_str(line).ok()?)?);
+        }
+        Some(history)
+    }
+}
+
+impl History {
+    /// Adds a game, replacing anything recorded for its puzzle before.
+    pub fn insert(&mut self, game: GameHistory) {
+        self.games.insert(game.puzzle_id.clone(), game);
+    }
+
+    /// Returns the game recorded for the puzzle with the given id, if there is one.
+    pub fn game(&self, puzzle_id: &str) -> Option<&GameHistory> {
+        self.games.get(puzzle_id)
+    }
+
+    /// Returns the games recorded, in order of puzzle id.
+    pub fn games(&self) -> impl Iterator<Item = &GameHistory> {
+        self.games.values()
+    }
+}
+
+/// Returns the name a kind of hint has in an export.
+#[cfg(feature = "json")]
+fn hint_kind_name(kind: HintKind) -> &'static str {
+    match kind {
+        HintKind::Letter => "letter",
+        HintKind::Length => "length",
+        HintKind::PangramStart => "pangram_start",
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let mut game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "");
+        game.start_clock(1_000.0);
+        game.play_at("coil", 6_200.0);
+        game.buy_length_hint();
+        let mut history = History::new();
+        history.record("2024-05-06", &game);
+        game.play_at("will", 9_000.0);
+        game.finish_game();
+        history.record("2024-05-07", &game);
+        assert_eq!(history.len(), 2);
+
+        let exported = history.export_ndjson();
+        assert_eq!(exported.lines().count(), 2);
+        assert_eq!(exported.lines().next().unwrap(),
+                   "{\"puzzle\":\"2024-05-06\",\"letters\":\"cglorw\",\"center\":\"i\",\
+                    \"started_at\":1000,\"plays\":[{\"word\":\"coil\",\"time\":5200}],\
+                    \"hints\":[{\"kind\":\"length\",\"word\":\"will\",\"cost\":0}],\
+                    \"score\":1,\"unaided_score\":1,\"max_score\":16,\"answer_count\":3,\
+                    \"rank\":\"Good\",\"finished\":false}");
+        let imported = History::import_ndjson(&exported).unwrap();
+        assert_eq!(imported, history);
+        let finished = imported.game("2024-05-07").unwrap();
+        assert_eq!(finished.plays[1], PlayRecord { word: "will".to_string(), time: Some(8_000) });
+        assert!(finished.finished);
+
+        assert_eq!(History::import_ndjson(&format!("{}\n\n", exported)), Some(history));
+        assert_eq!(History::import_ndjson("{\"puzzle\":\"2024-05-06\"}\n"), None);
+        assert_eq!(History::import_ndjson("not json\n"), None);
+        assert_eq!(History::import_ndjson(""), Some(History::new()));
+    }
+}
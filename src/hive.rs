@@ -0,0 +1,96 @@
+//! This module provides `HiveLayout`, the arrangement of the optional letters around the required
+//! letter in the honeycomb, for the variant where words have to trace a path of adjacent cells.
+
+use std::collections::BTreeSet;
+use crate::utils::letters;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of cells in the ring around the center of the honeycomb.
+pub const RING_SIZE: usize = 6;
+
+/// The six optional letters in order around the ring of the honeycomb. Each ring cell touches the
+/// two cells beside it in the ring and the center cell, which touches every ring cell.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HiveLayout {
+    /// The letters in order around the ring, as grapheme clusters.
+    ring: Vec<String>,
+}
+
+impl HiveLayout {
+    /// Creates a layout from the optional letters in order around the ring. Returns `None` if
+    /// there aren't exactly six distinct letters.
+    pub fn from_ring(ring: &str) -> Option<HiveLayout> {
+        let ring: Vec<String> = letters(ring).into_iter().map(String::from).collect();
+        let distinct: BTreeSet<&String> = ring.iter().collect();
+        if ring.len() != RING_SIZE || distinct.len() != RING_SIZE {
+            return None;
+        }
+
+        Some(HiveLayout { ring })
+    }
+
+    /// Returns the letters in order around the ring.
+    pub fn ring(&self) -> &[String] {
+        &self.ring
+    }
+
+    /// Returns the set of letters in the ring.
+    pub fn letters(&self) -> BTreeSet<String> {
+        self.ring.iter().cloned().collect()
+    }
+
+    /// Returns `true` if the cells holding `a` and `b` touch, with `center` in the middle. A letter
+    /// counts as adjacent to itself, so doubled letters just stay on the same cell.
+    pub fn is_adjacent(&self, a: &str, b: &str, center: &str) -> bool {
+        if a == b || a == center || b == center {
+            return self.has_cell(a, center) && self.has_cell(b, center);
+        }
+
+        match (self.position(a), self.position(b)) {
+            (Some(i), Some(j)) => (i + 1) % RING_SIZE == j || (j + 1) % RING_SIZE == i,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if every pair of consecutive letters in `word` is adjacent, with `center` in
+    /// the middle.
+    pub fn is_valid_path(&self, word: &str, center: &str) -> bool {
+        let letters = letters(word);
+        letters.iter().all(|l| self.has_cell(l, center)) &&
+            letters.windows(2).all(|pair| self.is_adjacent(pair[0], pair[1], center))
+    }
+
+    /// Returns the ring position of the given letter, if it's in the ring.
+    fn position(&self, letter: &str) -> Option<usize> {
+        self.ring.iter().position(|r| r == letter)
+    }
+
+    /// Returns `true` if the letter is in the ring or is the center.
+    fn has_cell(&self, letter: &str, center: &str) -> bool {
+        letter == center || self.position(letter).is_some()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(HiveLayout::from_ring("cwlgr"), None);
+        assert_eq!(HiveLayout::from_ring("cwlgrr"), None);
+
+        let layout = HiveLayout::from_ring("growlc").unwrap();
+        assert!(layout.is_adjacent("w", "l", "i"));
+        assert!(layout.is_adjacent("c", "g", "i"));
+        assert!(layout.is_adjacent("i", "o", "i"));
+        assert!(!layout.is_adjacent("g", "w", "i"));
+        assert!(layout.is_valid_path("will", "i"));
+        assert!(layout.is_valid_path("growl", "i"));
+        assert!(!layout.is_valid_path("cowgirl", "i"));
+        assert!(!layout.is_valid_path("wilt", "i"));
+    }
+}
@@ -0,0 +1,254 @@
+//! This module provides `Messages`, a catalog of the user-facing text produced by the engine (play
+//! result messages, rank names, and post-game report text), keyed by locale, so that non-English
+//! frontends don't have to map every result or rank themselves.
+//!
+//! A few locales are built in. Frontends can add more, or override any message, by loading a
+//! catalog of tab-separated `key`/`text` lines. Messages missing from a locale fall back to
+//! English. In the message for a play result, `{min_length}` stands for the game's minimum word
+//! length.
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use crate::rank::Rank;
+use crate::spellingbee::{PlayResult, SpellingBeeGame};
+
+/// The locale used when a message isn't available in the requested one.
+pub const FALLBACK_LOCALE: &str = "en";
+
+/// The built-in catalogs, as `(locale, key, text)` entries.
+const BUILT_IN: &[(&str, &str, &str)] = &[
+    ("en", "play.valid", "Nice!"),
+    ("en", "play.already_played", "Already played!"),
+    ("en", "play.invalid_word", "That word couldn't be found in the lexicon"),
+    ("en", "play.invalid_length", "Words need to be at least {min_length} letters long"),
+    ("en", "play.invalid_letters", "Words must contain the center letter"),
+    ("en", "play.invalid_path", "Letters must be next to each other in the hive"),
+    ("en", "play.invalid_character", "That's not a letter"),
+    ("en", "play.accepted_unlisted", "Accepted, but it doesn't count"),
+    ("en", "play.finished", "The game is over"),
+    ("en", "report.title", "Spelling Bee"),
+    ("en", "report.found", "Found"),
+    ("en", "report.total", "Total"),
+    ("en", "report.words", "Words"),
+    ("en", "report.points", "Points"),
+    ("en", "report.pangrams", "Pangrams"),
+    ("en", "report.best_streak", "Best streak"),
+    ("en", "report.word", "Word"),
+    ("en", "report.found_words", "Found words"),
+    ("en", "report.missed_words", "Missed words"),
+    ("en", "report.none", "None."),
+    ("en", "rank.beginner", "Beginner"),
+    ("en", "rank.good_start", "Good Start"),
+    ("en", "rank.moving_up", "Moving Up"),
+    ("en", "rank.good", "Good"),
+    ("en", "rank.solid", "Solid"),
+    ("en", "rank.nice", "Nice"),
+    ("en", "rank.great", "Great"),
+    ("en", "rank.amazing", "Amazing"),
+    ("en", "rank.genius", "Genius"),
+    ("en", "rank.queen_bee", "Queen Bee"),
+
+    ("es", "play.valid", "¡Bien!"),
+    ("es", "play.already_played", "¡Ya la has jugado!"),
+    ("es", "play.invalid_word", "Esa palabra no está en el léxico"),
+    ("es", "play.invalid_length", "Las palabras deben tener al menos {min_length} letras"),
+    ("es", "play.invalid_letters", "Las palabras deben contener la letra central"),
+    ("es", "play.invalid_path", "Las letras deben estar juntas en la colmena"),
+    ("es", "play.invalid_character", "Eso no es una letra"),
+    ("es", "play.accepted_unlisted", "Aceptada, pero no cuenta"),
+    ("es", "play.finished", "La partida ha terminado"),
+    ("es", "report.title", "Spelling Bee"),
+    ("es", "report.found", "Encontradas"),
+    ("es", "report.total", "Total"),
+    ("es", "report.words", "Palabras"),
+    ("es", "report.points", "Puntos"),
+    ("es", "report.pangrams", "Pangramas"),
+    ("es", "report.best_streak", "Mejor racha"),
+    ("es", "report.word", "Palabra"),
+    ("es", "report.found_words", "Palabras encontradas"),
+    ("es", "report.missed_words", "Palabras que faltaron"),
+    ("es", "report.none", "Ninguna."),
+    ("es", "rank.beginner", "Principiante"),
+    ("es", "rank.good_start", "Buen comienzo"),
+    ("es", "rank.moving_up", "Avanzando"),
+    ("es", "rank.good", "Bien"),
+    ("es", "rank.solid", "Sólido"),
+    ("es", "rank.nice", "Muy bien"),
+    ("es", "rank.great", "Genial"),
+    ("es", "rank.amazing", "Increíble"),
+    ("es", "rank.genius", "Genio"),
+    ("es", "rank.queen_bee", "Abeja reina"),
+
+    ("de", "play.valid", "Gut!"),
+    ("de", "play.already_played", "Schon gespielt!"),
+    ("de", "play.invalid_word", "Dieses Wort steht nicht im Wörterbuch"),
+    ("de", "play.invalid_length", "Wörter müssen mindestens {min_length} Buchstaben lang sein"),
+    ("de", "play.invalid_letters", "Wörter müssen den mittleren Buchstaben enthalten"),
+    ("de", "play.invalid_path", "Buchstaben müssen in der Wabe nebeneinander liegen"),
+    ("de", "play.invalid_character", "Das ist kein Buchstabe"),
+    ("de", "play.accepted_unlisted", "Akzeptiert, zählt aber nicht"),
+    ("de", "play.finished", "Das Spiel ist vorbei"),
+    ("de", "report.title", "Spelling Bee"),
+    ("de", "report.found", "Gefunden"),
+    ("de", "report.total", "Gesamt"),
+    ("de", "report.words", "Wörter"),
+    ("de", "report.points", "Punkte"),
+    ("de", "report.pangrams", "Pangramme"),
+    ("de", "report.best_streak", "Beste Serie"),
+    ("de", "report.word", "Wort"),
+    ("de", "report.found_words", "Gefundene Wörter"),
+    ("de", "report.missed_words", "Verpasste Wörter"),
+    ("de", "report.none", "Keine."),
+    ("de", "rank.beginner", "Anfänger"),
+    ("de", "rank.good_start", "Guter Start"),
+    ("de", "rank.moving_up", "Aufwärts"),
+    ("de", "rank.good", "Gut"),
+    ("de", "rank.solid", "Solide"),
+    ("de", "rank.nice", "Schön"),
+    ("de", "rank.great", "Großartig"),
+    ("de", "rank.amazing", "Erstaunlich"),
+    ("de", "rank.genius", "Genie"),
+    ("de", "rank.queen_bee", "Bienenkönigin"),
+
+    ("fr", "play.valid", "Bravo !"),
+    ("fr", "play.already_played", "Déjà jouée !"),
+    ("fr", "play.invalid_word", "Ce mot n'est pas dans le lexique"),
+    ("fr", "play.invalid_length", "Les mots doivent avoir au moins {min_length} lettres"),
+    ("fr", "play.invalid_letters", "Les mots doivent contenir la lettre centrale"),
+    ("fr", "play.invalid_path", "Les lettres doivent se toucher dans la ruche"),
+    ("fr", "play.invalid_character", "Ce n'est pas une lettre"),
+    ("fr", "play.accepted_unlisted", "Accepté, mais ça ne compte pas"),
+    ("fr", "play.finished", "La partie est terminée"),
+    ("fr", "report.title", "Spelling Bee"),
+    ("fr", "report.found", "Trouvés"),
+    ("fr", "report.total", "Total"),
+    ("fr", "report.words", "Mots"),
+    ("fr", "report.points", "Points"),
+    ("fr", "report.pangrams", "Pangrammes"),
+    ("fr", "report.best_streak", "Meilleure série"),
+    ("fr", "report.word", "Mot"),
+    ("fr", "report.found_words", "Mots trouvés"),
+    ("fr", "report.missed_words", "Mots manqués"),
+    ("fr", "report.none", "Aucun."),
+    ("fr", "rank.beginner", "Débutant"),
+    ("fr", "rank.good_start", "Bon début"),
+    ("fr", "rank.moving_up", "En progrès"),
+    ("fr", "rank.good", "Bien"),
+    ("fr", "rank.solid", "Solide"),
+    ("fr", "rank.nice", "Joli"),
+    ("fr", "rank.great", "Super"),
+    ("fr", "rank.amazing", "Incroyable"),
+    ("fr", "rank.genius", "Génie"),
+    ("fr", "rank.queen_bee", "Reine des abeilles"),
+];
+
+/// Returns the catalog key for the message describing a play result.
+pub fn play_result_key(result: PlayResult) -> &'static str {
+    match result {
+        PlayResult::Valid => "play.valid",
+        PlayResult::AlreadyPlayed => "play.already_played",
+        PlayResult::InvalidWord => "play.invalid_word",
+        PlayResult::InvalidLength => "play.invalid_length",
+        PlayResult::InvalidLetters => "play.invalid_letters",
+        PlayResult::InvalidPath => "play.invalid_path",
+        PlayResult::InvalidCharacter => "play.invalid_character",
+        PlayResult::AcceptedUnlisted => "play.accepted_unlisted",
+        PlayResult::Finished => "play.finished",
+    }
+}
+
+/// Returns the catalog key for the name of a rank.
+pub fn rank_key(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Beginner => "rank.beginner",
+        Rank::GoodStart => "rank.good_start",
+        Rank::MovingUp => "rank.moving_up",
+        Rank::Good => "rank.good",
+        Rank::Solid => "rank.solid",
+        Rank::Nice => "rank.nice",
+        Rank::Great => "rank.great",
+        Rank::Amazing => "rank.amazing",
+        Rank::Genius => "rank.genius",
+        Rank::QueenBee => "rank.queen_bee",
+    }
+}
+
+/// The engine's messages in a single locale, with English as a fallback.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Messages {
+    /// The locale, such as `en` or `pt-BR`.
+    locale: String,
+
+    /// The messages in the locale, by key.
+    messages: BTreeMap<String, String>,
+
+    /// The English messages, by key.
+    fallback: BTreeMap<String, String>,
+}
+
+#[wasm_bindgen]
+impl Messages {
+    /// Creates a catalog for the given locale, starting from its built-in messages if it has
+    /// any. A locale with a region, like `es-MX`, also uses its language's built-in messages.
+    pub fn new(locale: &str) -> Messages {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        let built_in = |l: &str| -> BTreeMap<String, String> {
+            BUILT_IN.iter()
+                .filter(|(entry_locale, _, _)| *entry_locale == l)
+                .map(|(_, key, text)| (key.to_string(), text.to_string()))
+                .collect()
+        };
+
+        let mut messages = built_in(&language.to_lowercase());
+        messages.extend(built_in(locale));
+
+        Messages {
+            locale: locale.to_string(),
+            messages,
+            fallback: built_in(FALLBACK_LOCALE),
+        }
+    }
+
+    /// Returns the locale.
+    pub fn locale(&self) -> String {
+        self.locale.clone()
+    }
+
+    /// Adds or replaces messages from a catalog with one tab-separated key and text per line.
+    /// Blank lines and lines starting with `#` are skipped. Returns the number of messages
+    /// loaded.
+    pub fn load(&mut self, catalog: &str) -> usize {
+        let mut count = 0;
+        for line in catalog.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, text)) = line.split_once('\t') {
+                self.messages.insert(key.trim().to_string(), text.to_string());
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns the message with the given key, falling back to English and then to the key
+    /// itself.
+    pub fn get(&self, key: &str) -> String {
+        self.messages.get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// Returns the message describing the result of a play in the given game.
+    pub fn play_result(&self, result: PlayResult, game: &SpellingBeeGame) -> String {
+        self.get(play_result_key(result))
+            .replace("{min_length}", &game.config().min_length().to_string())
+    }
+
+    /// Returns the name of a rank.
+    pub fn rank(&self, rank: Rank) -> String {
+        self.get(rank_key(rank))
+    }
+}
@@ -0,0 +1,108 @@
+//! This module provides `LexiconHandle`, a word list parsed once and shared between games.
+//! Parsing the main word list is most of the cost of building a game, so a frontend that builds
+//! several, like an archive browser or a what-if explorer, should parse the lists into a handle
+//! and build each game from it with `SpellingBeeGame::from_lexicon()`.
+
+use wasm_bindgen::prelude::*;
+use crate::dawg::Dawg;
+use crate::diagnostics::string_bytes;
+use crate::i18n::FALLBACK_LOCALE;
+use crate::spellingbee::parse_lexicon;
+
+/// A parsed word list, ready to build games from.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LexiconHandle {
+    /// The locale whose case rules the words were lowercased with.
+    locale: String,
+
+    /// The words, with the swears removed.
+    words: Vec<String>,
+
+    /// Whether the words came from `spellingbee::FALLBACK_WORDS` because no usable main word
+    /// list was given.
+    fallback_dictionary: bool,
+}
+
+#[wasm_bindgen]
+impl LexiconHandle {
+    /// Parses the same main word list and swears buffers taken by `SpellingBeeGame::new()`.
+    pub fn new(main_words: &str, swears: &str) -> LexiconHandle {
+        LexiconHandle::new_in(FALLBACK_LOCALE, main_words, swears)
+    }
+
+    /// Parses the word lists like `new()`, lowercasing them with the case rules of the given
+    /// locale (see `SpellingBeeGame::new_in()`). Games built from the handle use the same
+    /// locale.
+    pub fn new_in(locale: &str, main_words: &str, swears: &str) -> LexiconHandle {
+        let (lex, fallback_dictionary) = parse_lexicon(locale, main_words, swears);
+        LexiconHandle {
+            locale: locale.to_string(),
+            words: lex.into_iter().collect(),
+            fallback_dictionary,
+        }
+    }
+
+    /// Loads the words from a dictionary compiled by the `spellingbee-dawg` tool (see `dawg`).
+    /// Returns `None` if the bytes aren't a compiled dictionary.
+    pub fn from_dawg(dawg: &[u8]) -> Option<LexiconHandle> {
+        Some(LexiconHandle {
+            locale: FALLBACK_LOCALE.to_string(),
+            words: Dawg::from_bytes(dawg)?.words(),
+            fallback_dictionary: false,
+        })
+    }
+
+    /// Returns the locale whose case rules the words were lowercased with.
+    pub fn locale(&self) -> String {
+        self.locale.clone()
+    }
+
+    /// Returns the number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns `true` if no usable main word list was given, so that the words come from the
+    /// small built-in list instead (see `SpellingBeeGame::uses_fallback_dictionary()`).
+    pub fn uses_fallback_dictionary(&self) -> bool {
+        self.fallback_dictionary
+    }
+
+    /// Returns the estimated bytes taken up by the words (see `diagnostics::string_bytes()`).
+    pub fn heap_bytes(&self) -> usize {
+        string_bytes(&self.words)
+    }
+}
+
+impl LexiconHandle {
+    /// Returns the words, with the swears removed.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_lexicon_handle() {
+        let words = "Will\ncowgirl\ncoil\nroll\nmother\n";
+        let lexicon = LexiconHandle::new(words, "roll\n");
+        assert_eq!(lexicon.len(), 4);
+        assert!(!lexicon.uses_fallback_dictionary());
+
+        let game = SpellingBeeGame::from_lexicon("cglorw", "i", &lexicon);
+        assert_eq!(game, SpellingBeeGame::new("cglorw", "i", words, "roll\n"));
+        let other = SpellingBeeGame::from_lexicon("aehmrt", "o", &lexicon);
+        assert_eq!(other.answers().len(), 1);
+        assert!(LexiconHandle::new("", "").uses_fallback_dictionary());
+    }
+}
@@ -1,8 +1,70 @@
 use console_error_panic_hook;
 use std::panic;
 
+#[macro_use]
 mod utils;
 mod spellingbee;
+pub mod search;
+pub mod hive;
+pub mod royale;
+pub mod team;
+pub mod p2p;
+pub mod oplog;
+pub mod report;
+pub mod printable;
+pub mod i18n;
+pub mod translit;
+pub mod worddata;
+pub mod accuracy;
+pub mod puzzle;
+pub mod cache;
+#[cfg(target_arch = "wasm32")]
+pub mod tasks;
+pub mod config;
+pub mod rank;
+pub mod pack;
+pub mod verify;
+pub mod schedule;
+pub mod hints;
+pub mod adaptive;
+pub mod practice;
+pub mod diagnostics;
+pub mod save;
+pub mod generate;
+pub mod scoring;
+pub mod lexicon;
+pub mod dawg;
+pub mod achievements;
+pub mod stats;
+pub mod history;
+pub mod code;
+pub mod alphabet;
+pub mod definitions;
+pub mod source;
+pub mod loader;
+pub mod answers;
+pub mod archive;
+pub mod sync;
+pub mod protocol;
+pub mod bot;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "nyt-fetch")]
+pub mod nyt;
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub mod server;
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub mod lobby;
+#[cfg(all(feature = "http-api", not(target_arch = "wasm32")))]
+pub mod api;
+#[cfg(all(feature = "http-api", not(target_arch = "wasm32")))]
+pub mod storage;
+#[cfg(all(feature = "http-api", not(target_arch = "wasm32")))]
+pub mod session;
+#[cfg(all(feature = "http-api", not(target_arch = "wasm32")))]
+pub mod graphql;
+
+pub use spellingbee::{GameError, MergeReport, PlayOutcome, PlayResult, SpellingBeeGame};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
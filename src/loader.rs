@@ -0,0 +1,135 @@
+//! This module provides `WordlistLoader`, which takes the main word list in chunks as it streams
+//! in, so that a frontend on a slow connection can feed it the response body piece by piece and
+//! build the game the moment the last piece arrives, instead of first waiting for the whole list
+//! as one string.
+//!
+//! Chunks can be text, or raw bytes of UTF-8 straight from the network, which can end partway
+//! through a letter; the rest of the letter is taken from the next chunk.
+
+use std::str;
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::SpellingBeeGame;
+
+/// A main word list being loaded in chunks.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WordlistLoader {
+    /// The complete lines loaded so far.
+    text: String,
+
+    /// The start of a line whose end hasn't arrived yet.
+    partial_line: String,
+
+    /// The start of a letter whose other bytes haven't arrived yet.
+    partial_bytes: Vec<u8>,
+
+    /// The number of complete lines loaded so far.
+    lines: usize,
+}
+
+#[wasm_bindgen]
+impl WordlistLoader {
+    /// Creates a loader with nothing loaded.
+    pub fn new() -> WordlistLoader {
+        WordlistLoader::default()
+    }
+
+    /// Adds the next chunk of the word list as text.
+    pub fn push_chunk(&mut self, chunk: &str) {
+        if !self.partial_bytes.is_empty() {
+            // A letter cut off by a byte chunk never got its end.
+            let bytes = std::mem::take(&mut self.partial_bytes);
+            self.push_text(&String::from_utf8_lossy(&bytes));
+        }
+        self.push_text(chunk);
+    }
+
+    /// Adds the next chunk of the word list as bytes of UTF-8. Bytes that aren't valid UTF-8
+    /// are replaced with U+FFFD, like `String::from_utf8_lossy()`.
+    pub fn push_bytes(&mut self, chunk: &[u8]) {
+        self.partial_bytes.extend_from_slice(chunk);
+        let bytes = std::mem::take(&mut self.partial_bytes);
+        let mut rest = &bytes[..];
+        loop {
+            match str::from_utf8(rest) {
+                Ok(text) => {
+                    self.push_text(text);
+                    break;
+                }
+                Err(e) => {
+                    let (valid, tail) = rest.split_at(e.valid_up_to());
+                    self.push_text(&String::from_utf8_lossy(valid));
+                    match e.error_len() {
+                        Some(len) => {
+                            self.push_text(&String::from_utf8_lossy(&tail[..len]));
+                            rest = &tail[len..];
+                        }
+                        // The letter might still be finished by the next chunk.
+                        None => {
+                            self.partial_bytes = tail.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the number of complete lines loaded so far, for showing progress.
+    pub fn lines_loaded(&self) -> usize {
+        self.lines
+    }
+
+    /// Finishes loading and creates a game from the word list like `SpellingBeeGame::new()`.
+    pub fn finish(self, optional_letters: &str, required_letter: &str,
+                  swears: &str) -> SpellingBeeGame
+    {
+        SpellingBeeGame::new(optional_letters, required_letter, &self.into_text(), swears)
+    }
+}
+
+impl WordlistLoader {
+    /// Finishes loading and returns the whole word list, with anything left over from a cut-off
+    /// letter replaced with U+FFFD.
+    pub fn into_text(mut self) -> String {
+        self.push_chunk("");
+        self.text + &self.partial_line
+    }
+
+    /// Adds text to the lines loaded so far, keeping back any line that hasn't ended.
+    fn push_text(&mut self, text: &str) {
+        self.partial_line.push_str(text);
+        if let Some(end) = self.partial_line.rfind('\n') {
+            let rest = self.partial_line.split_off(end + 1);
+            self.lines += self.partial_line.matches('\n').count();
+            self.text.push_str(&self.partial_line);
+            self.partial_line = rest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loader() {
+        let mut loader = WordlistLoader::new();
+        loader.push_chunk("will\ncow");
+        assert_eq!(loader.lines_loaded(), 1);
+        loader.push_chunk("girl\ncoil\n");
+        let bytes = "café\nwill".as_bytes();
+        loader.push_bytes(&bytes[..4]);
+        loader.push_bytes(&bytes[4..]);
+        assert_eq!(loader.lines_loaded(), 4);
+        assert_eq!(loader.clone().into_text(), "will\ncowgirl\ncoil\ncafé\nwill");
+
+        let game = loader.finish("cglorw", "i", "");
+        let expected = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\ncafé\n", "");
+        assert_eq!(game, expected);
+
+        let mut cut = WordlistLoader::new();
+        cut.push_bytes(&[b'a', 0xc3]);
+        assert_eq!(cut.into_text(), "a\u{fffd}");
+    }
+}
@@ -0,0 +1,293 @@
+//! This module provides `Lobbies`, which handles the setup for multiplayer games: creating a lobby
+//! with a short join code, letting players join and mark themselves ready, and starting a versus
+//! (`Royale`) or co-op (`TeamGame`) session once everyone is ready. The room server (see the
+//! `server` module) hosts its lobbies with it, so it is only built natively, with the `server`
+//! feature.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use crate::royale::Royale;
+use crate::spellingbee::SpellingBeeGame;
+use crate::team::TeamGame;
+use crate::utils::Rng;
+
+/// The number of letters in a join code.
+pub const JOIN_CODE_LENGTH: usize = 5;
+
+/// The letters used in join codes, leaving out ones that are easy to mix up.
+const JOIN_CODE_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// The kind of multiplayer game a lobby will start.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LobbyMode {
+    /// A Bee Royale, with the given round length in milliseconds and number of players knocked
+    /// out each round.
+    Versus { round_length: u64, eliminations_per_round: usize },
+
+    /// A team game with one shared word list.
+    Coop,
+}
+
+/// A multiplayer game that has been started from a lobby.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Session {
+    /// A versus game.
+    Versus(Royale),
+
+    /// A co-op game.
+    Coop(Box<TeamGame>),
+}
+
+/// The ways a lobby action can fail.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LobbyError {
+    /// There's no lobby with the given join code.
+    UnknownLobby,
+
+    /// The player isn't in the lobby.
+    UnknownPlayer,
+
+    /// The lobby's game has already started.
+    AlreadyStarted,
+
+    /// Someone in the lobby isn't ready yet.
+    NotReady,
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            LobbyError::UnknownLobby => "no lobby has that join code",
+            LobbyError::UnknownPlayer => "that player isn't in the lobby",
+            LobbyError::AlreadyStarted => "the lobby's game has already started",
+            LobbyError::NotReady => "not every player is ready",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl Error for LobbyError {}
+
+/// A lobby for a single multiplayer game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lobby {
+    /// The puzzle everyone will play.
+    game: SpellingBeeGame,
+
+    /// The kind of game to start.
+    mode: LobbyMode,
+
+    /// The player who created the lobby.
+    host: String,
+
+    /// Every player in the lobby, with whether they're ready.
+    players: BTreeMap<String, bool>,
+
+    /// The game, once it has started.
+    session: Option<Session>,
+}
+
+impl Lobby {
+    /// Returns the player who created the lobby.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the kind of game the lobby will start.
+    pub fn mode(&self) -> LobbyMode {
+        self.mode
+    }
+
+    /// Returns the players in the lobby, in sorted order.
+    pub fn players(&self) -> Vec<&str> {
+        self.players.keys().map(|p| p.as_str()).collect()
+    }
+
+    /// Returns the players who are ready.
+    pub fn ready_players(&self) -> BTreeSet<&str> {
+        self.players.iter().filter(|(_, &r)| r).map(|(p, _)| p.as_str()).collect()
+    }
+
+    /// Returns `true` if every player is ready.
+    pub fn all_ready(&self) -> bool {
+        self.players.values().all(|&r| r)
+    }
+
+    /// Returns the game, if it has started.
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_ref()
+    }
+
+    /// Returns the game, if it has started, so players can make moves in it.
+    pub fn session_mut(&mut self) -> Option<&mut Session> {
+        self.session.as_mut()
+    }
+}
+
+/// Every open lobby, by join code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lobbies {
+    /// The lobbies, by join code.
+    lobbies: BTreeMap<String, Lobby>,
+
+    /// The generator for join codes.
+    rng: Rng,
+}
+
+impl Lobbies {
+    /// Creates an empty set of lobbies, with join codes generated from the given seed.
+    pub fn new(seed: u64) -> Lobbies {
+        Lobbies { lobbies: BTreeMap::new(), rng: Rng::new(seed) }
+    }
+
+    /// Opens a new lobby for the given puzzle and mode, with the host already in it but not ready,
+    /// and returns its join code.
+    pub fn create(&mut self, host: &str, game: SpellingBeeGame, mode: LobbyMode) -> String {
+        let code = loop {
+            let code: String = (0..JOIN_CODE_LENGTH).map(|_| {
+                JOIN_CODE_LETTERS[self.rng.below(JOIN_CODE_LETTERS.len())] as char
+            }).collect();
+            if !self.lobbies.contains_key(&code) {
+                break code;
+            }
+        };
+
+        let mut players = BTreeMap::new();
+        players.insert(host.to_string(), false);
+        self.lobbies.insert(code.clone(), Lobby {
+            game,
+            mode,
+            host: host.to_string(),
+            players,
+            session: None,
+        });
+        code
+    }
+
+    /// Returns the lobby with the given join code.
+    pub fn get(&self, code: &str) -> Option<&Lobby> {
+        self.lobbies.get(&code.to_uppercase())
+    }
+
+    /// Returns the lobby with the given join code, so players can make moves in its game.
+    pub fn get_mut(&mut self, code: &str) -> Option<&mut Lobby> {
+        self.lobbies.get_mut(&code.to_uppercase())
+    }
+
+    /// Adds a player to a lobby that hasn't started yet. Joining again does nothing.
+    pub fn join(&mut self, code: &str, player: &str) -> Result<(), LobbyError> {
+        let lobby = self.waiting_lobby(code)?;
+        lobby.players.entry(player.to_string()).or_insert(false);
+        Ok(())
+    }
+
+    /// Removes a player from a lobby that hasn't started yet. If the lobby ends up empty, it's
+    /// closed.
+    pub fn leave(&mut self, code: &str, player: &str) -> Result<(), LobbyError> {
+        let lobby = self.waiting_lobby(code)?;
+        if lobby.players.remove(player).is_none() {
+            return Err(LobbyError::UnknownPlayer);
+        }
+
+        if lobby.host == player {
+            if let Some(next) = lobby.players.keys().next() {
+                lobby.host = next.clone();
+            }
+        }
+        if lobby.players.is_empty() {
+            self.close(code);
+        }
+        Ok(())
+    }
+
+    /// Marks a player as ready or not in a lobby that hasn't started yet.
+    pub fn set_ready(&mut self, code: &str, player: &str, ready: bool) -> Result<(), LobbyError> {
+        let lobby = self.waiting_lobby(code)?;
+        match lobby.players.get_mut(player) {
+            Some(r) => {
+                *r = ready;
+                Ok(())
+            }
+            None => Err(LobbyError::UnknownPlayer),
+        }
+    }
+
+    /// Starts the lobby's game at time `now` (in milliseconds), once every player is ready.
+    pub fn start(&mut self, code: &str, now: u64) -> Result<&mut Session, LobbyError> {
+        let lobby = self.waiting_lobby(code)?;
+        if !lobby.all_ready() {
+            return Err(LobbyError::NotReady);
+        }
+
+        let session = match lobby.mode {
+            LobbyMode::Versus { round_length, eliminations_per_round } => {
+                Session::Versus(Royale::new(&lobby.game, lobby.players.keys().cloned(),
+                                            round_length, eliminations_per_round, now))
+            }
+            LobbyMode::Coop => Session::Coop(Box::new(TeamGame::new(lobby.game.clone()))),
+        };
+        Ok(lobby.session.get_or_insert(session))
+    }
+
+    /// Closes a lobby, returning it.
+    pub fn close(&mut self, code: &str) -> Option<Lobby> {
+        self.lobbies.remove(&code.to_uppercase())
+    }
+
+    /// Returns the lobby with the given join code, if it hasn't started yet.
+    fn waiting_lobby(&mut self, code: &str) -> Result<&mut Lobby, LobbyError> {
+        match self.get_mut(code) {
+            None => Err(LobbyError::UnknownLobby),
+            Some(lobby) if lobby.session.is_some() => Err(LobbyError::AlreadyStarted),
+            Some(lobby) => Ok(lobby),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spellingbee::PlayResult;
+
+    #[test]
+    fn test_lobby() {
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let mut lobbies = Lobbies::new(7);
+        let code = lobbies.create("ana", game.clone(), LobbyMode::Coop);
+        assert_eq!(code.len(), JOIN_CODE_LENGTH);
+        assert_eq!(lobbies.join("ZZZZZ", "ben"), Err(LobbyError::UnknownLobby));
+        lobbies.join(&code.to_lowercase(), "ben").unwrap();
+        lobbies.join(&code, "cy").unwrap();
+        assert_eq!(lobbies.get(&code).unwrap().players(), ["ana", "ben", "cy"]);
+
+        lobbies.set_ready(&code, "ana", true).unwrap();
+        lobbies.set_ready(&code, "ben", true).unwrap();
+        assert_eq!(lobbies.set_ready(&code, "dee", true), Err(LobbyError::UnknownPlayer));
+        assert_eq!(lobbies.start(&code, 0).err(), Some(LobbyError::NotReady));
+
+        // The host leaving hands the lobby to the next player, and leaves everyone else ready.
+        lobbies.leave(&code, "ana").unwrap();
+        lobbies.leave(&code, "cy").unwrap();
+        let lobby = lobbies.get(&code).unwrap();
+        assert_eq!((lobby.host(), lobby.all_ready()), ("ben", true));
+        match lobbies.start(&code, 0).unwrap() {
+            Session::Coop(team) => assert_eq!(team.play("ben", "will"), PlayResult::Valid),
+            Session::Versus(_) => panic!("a co-op lobby started a versus game"),
+        }
+        assert_eq!(lobbies.join(&code, "dee"), Err(LobbyError::AlreadyStarted));
+        assert_eq!(lobbies.start(&code, 0).err(), Some(LobbyError::AlreadyStarted));
+
+        let versus = LobbyMode::Versus { round_length: 1_000, eliminations_per_round: 1 };
+        let code = lobbies.create("ana", game, versus);
+        lobbies.set_ready(&code, "ana", true).unwrap();
+        assert!(matches!(lobbies.start(&code, 0), Ok(Session::Versus(_))));
+        assert!(lobbies.close(&code).is_some());
+        assert_eq!(lobbies.get(&code), None);
+
+        let code = lobbies.create("ana", SpellingBeeGame::from_answers("cglorw", "i", ""),
+                                  LobbyMode::Coop);
+        lobbies.leave(&code, "ana").unwrap();
+        assert_eq!(lobbies.get(&code), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! This module provides `OfficialPuzzle`, today's puzzle from the New York Times, so that the game
+//! can be played with the real letters and the real answer list rather than with answers found
+//! by filtering our own lexicon. It is only built with the `nyt-fetch` feature.
+//!
+//! The puzzle page embeds the puzzle as `window.gameData`, a JSON object whose `today` field has
+//! the letters, answers, and pangrams. `OfficialPuzzle::parse()` reads it from the page, and
+//! `fetch_today()`, natively, or `fetch_nyt_game()`, in WebAssembly, downloads the page first.
+//! Browsers won't let a page on another site read the puzzle page, so frontends should pass the
+//! URL of a proxy that serves it instead of `NYT_URL`.
+
+use std::error::Error;
+use std::fmt;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+use serde::Deserialize;
+use crate::spellingbee::SpellingBeeGame;
+
+/// The address of the puzzle page.
+pub const NYT_URL: &str = "https://www.nytimes.com/puzzles/spelling-bee";
+
+/// What comes just before the puzzle in the page.
+const GAME_DATA: &str = "window.gameData";
+
+/// The part of `window.gameData` that has the puzzles.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+struct GameData {
+    /// Today's puzzle.
+    today: PuzzleData,
+}
+
+/// A puzzle as `window.gameData` has it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleData {
+    /// The day the puzzle is for, as `YYYY-MM-DD`.
+    print_date: String,
+
+    /// The required letter.
+    center_letter: String,
+
+    /// The optional letters, one to a string.
+    outer_letters: Vec<String>,
+
+    /// The official answers.
+    answers: Vec<String>,
+
+    /// The answers that are pangrams.
+    pangrams: Vec<String>,
+}
+
+/// A puzzle as published by the New York Times.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OfficialPuzzle {
+    /// The day the puzzle is for, as `YYYY-MM-DD`.
+    pub date: String,
+
+    /// The required letter.
+    pub center_letter: String,
+
+    /// The optional letters, in the order the page lists them.
+    pub outer_letters: String,
+
+    /// The official answers, in the order the page lists them.
+    pub answers: Vec<String>,
+
+    /// The answers that are pangrams.
+    pub pangrams: Vec<String>,
+}
+
+impl OfficialPuzzle {
+    /// Reads today's puzzle from the HTML of the puzzle page.
+    pub fn parse(page: &str) -> Result<OfficialPuzzle, FetchError> {
+        let start = page.find(GAME_DATA).ok_or(FetchError::NoGameData)? + GAME_DATA.len();
+        let rest = page[start..].trim_start().strip_prefix('=').ok_or(FetchError::NoGameData)?;
+        // The page carries on after the object, so only the first value is read.
+        let today = serde_json::Deserializer::from_str(rest).into_iter::<GameData>().next()
+            .and_then(Result::ok)
+            .ok_or(FetchError::BadGameData)?
+            .today;
+        Ok(OfficialPuzzle {
+            date: today.print_date,
+            center_letter: today.center_letter,
+            outer_letters: today.outer_letters.concat(),
+            answers: today.answers,
+            pangrams: today.pangrams,
+        })
+    }
+
+    /// Creates a game with the puzzle's letters whose answers are exactly the official ones,
+    /// like `SpellingBeeGame::from_answers()`.
+    pub fn game(&self) -> SpellingBeeGame {
+        SpellingBeeGame::from_answers(&self.outer_letters, &self.center_letter,
+                                      &self.answers.join("\n"))
+    }
+}
+
+/// The ways fetching the official puzzle can fail.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FetchError {
+    /// The page couldn't be downloaded. Holds what went wrong.
+    Network(String),
+
+    /// The page doesn't have the puzzle in it.
+    NoGameData,
+
+    /// The puzzle in the page isn't in the shape it used to be.
+    BadGameData,
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Network(e) => write!(f, "couldn't download the puzzle: {}", e),
+            FetchError::NoGameData => write!(f, "the page doesn't have a puzzle in it"),
+            FetchError::BadGameData => write!(f, "the puzzle in the page couldn't be read"),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+/// Downloads today's puzzle from the given puzzle page, usually `NYT_URL`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_today(url: &str) -> Result<OfficialPuzzle, FetchError> {
+    let response = ureq::get(url).call().map_err(|e| FetchError::Network(e.to_string()))?;
+    let page = response.into_string().map_err(|e| FetchError::Network(e.to_string()))?;
+    OfficialPuzzle::parse(&page)
+}
+
+/// Downloads today's puzzle from the given puzzle page, or `NYT_URL` if there isn't one, and
+/// resolves to a game with its letters and official answers. Rejects with a message saying what
+/// went wrong if it can't.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub async fn fetch_nyt_game(url: Option<String>) -> Result<SpellingBeeGame, JsValue> {
+    let puzzle = match fetch_page(url.as_deref().unwrap_or(NYT_URL)).await {
+        Ok(page) => OfficialPuzzle::parse(&page),
+        Err(e) => Err(FetchError::Network(e.as_string().unwrap_or_else(|| format!("{:?}", e)))),
+    };
+    puzzle.map(|puzzle| puzzle.game()).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Downloads a page with the browser's `fetch()`.
+#[cfg(target_arch = "wasm32")]
+async fn fetch_page(url: &str) -> Result<String, JsValue> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("there's no window"))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url)).await?
+        .dyn_into()?;
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!("the server answered {}", response.status())));
+    }
+    JsFuture::from(response.text()?).await?
+        .as_string()
+        .ok_or_else(|| JsValue::from_str("the page isn't text"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let page = r#"<script type="text/javascript">window.gameData = {"today":{
+            "printDate":"2026-10-14","centerLetter":"i","outerLetters":["c","g","l","o","r","w"],
+            "pangrams":["cowgirl"],"answers":["cowgirl","coil","will"],"editor":"Sam Ezersky"},
+            "yesterday":{}}</script>"#;
+        let puzzle = OfficialPuzzle::parse(page).unwrap();
+        assert_eq!((puzzle.date.as_str(), puzzle.outer_letters.as_str()), ("2026-10-14", "cglorw"));
+        assert_eq!(puzzle.pangrams, vec!["cowgirl"]);
+
+        let game = puzzle.game();
+        assert_eq!(game.total_answer_count(), 3);
+        assert!(game.is_answer("coil") && game.is_pangram("cowgirl"));
+
+        assert_eq!(OfficialPuzzle::parse("<html></html>"), Err(FetchError::NoGameData));
+        let missing = "window.gameData = {\"today\":{\"centerLetter\":\"i\"}}";
+        assert_eq!(OfficialPuzzle::parse(missing), Err(FetchError::BadGameData));
+    }
+}
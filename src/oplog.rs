@@ -0,0 +1,260 @@
+//! This module provides `OpLog`, an append-only log of everything a player does in a game, so that
+//! play can happen offline and be synced later.
+//!
+//! A log is never edited, only added to: undoing a word is itself an op. The state of a game is
+//! whatever replaying its log against the fresh puzzle gives, so two logs with the same ops always
+//! give the same game, and logs recorded on different devices can simply be concatenated.
+//!
+//! Plays can be recorded with the time they were made, so that replaying the log gives the game
+//! the same clock, and what it claims about how long a game took can be checked (see
+//! `verify::timing()`).
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::{SpellingBeeGame, PlayResult};
+
+/// A single thing a player did.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Played a word.
+    Play(String),
+
+    /// Played a word at the given time, in milliseconds since the Unix epoch.
+    PlayAt(String, u64),
+
+    /// Took back the most recent word that's still played, if any.
+    Undo,
+
+    /// Asked for a hint of the given kind.
+    Hint(String),
+
+    /// Gave up. Anything after this is ignored by `replay()`.
+    GiveUp,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Op::Play(word) => write!(f, "play\t{}", word),
+            Op::PlayAt(word, at) => write!(f, "play\t{}\t{}", word, at),
+            Op::Undo => write!(f, "undo"),
+            Op::Hint(kind) => write!(f, "hint\t{}", kind),
+            Op::GiveUp => write!(f, "giveup"),
+        }
+    }
+}
+
+impl Op {
+    /// Parses an op from the format written by `Display`.
+    pub fn parse(line: &str) -> Option<Op> {
+        let mut fields = line.split('\t');
+        match (fields.next()?, fields.next(), fields.next(), fields.next()) {
+            ("play", Some(word), None, None) => Some(Op::Play(word.to_string())),
+            ("play", Some(word), Some(at), None) => {
+                Some(Op::PlayAt(word.to_string(), at.parse().ok()?))
+            }
+            ("undo", None, None, None) => Some(Op::Undo),
+            ("hint", Some(kind), None, None) => Some(Op::Hint(kind.to_string())),
+            ("giveup", None, None, None) => Some(Op::GiveUp),
+            _ => None,
+        }
+    }
+}
+
+/// The result of replaying a log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    /// The game with every word still played after undos.
+    pub game: SpellingBeeGame,
+
+    /// The kinds of hints asked for, in order.
+    pub hints: Vec<String>,
+
+    /// Whether the player gave up.
+    pub given_up: bool,
+}
+
+/// An append-only log of ops.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct OpLog {
+    /// The ops, oldest first.
+    ops: Vec<Op>,
+}
+
+#[wasm_bindgen]
+impl OpLog {
+    /// Creates an empty log.
+    pub fn new() -> OpLog {
+        OpLog::default()
+    }
+
+    /// Records playing a word.
+    pub fn play(&mut self, word: &str) {
+        self.ops.push(Op::Play(word.to_string()));
+    }
+
+    /// Records playing a word at the given time, in milliseconds since the Unix epoch, as from
+    /// `Date.now()`.
+    pub fn play_at(&mut self, word: &str, now: f64) {
+        self.ops.push(Op::PlayAt(word.to_string(), now as u64));
+    }
+
+    /// Records taking back the most recent word.
+    pub fn undo(&mut self) {
+        self.ops.push(Op::Undo);
+    }
+
+    /// Records asking for a hint of the given kind.
+    pub fn hint(&mut self, kind: &str) {
+        self.ops.push(Op::Hint(kind.to_string()));
+    }
+
+    /// Records giving up.
+    pub fn give_up(&mut self) {
+        self.ops.push(Op::GiveUp);
+    }
+
+    /// Returns the number of ops in the log.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns `true` if the log has no ops.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Adds every op in another log to the end of this one.
+    pub fn append(&mut self, other: &OpLog) {
+        self.ops.extend(other.ops.iter().cloned());
+    }
+
+    /// Returns the ops after the first `n`, as a log: what a server that has already seen `n` ops
+    /// is missing.
+    pub fn since(&self, n: usize) -> OpLog {
+        OpLog { ops: self.ops.iter().skip(n).cloned().collect() }
+    }
+
+    /// Encodes the log as text, one op per line.
+    pub fn encode(&self) -> String {
+        self.ops.iter().map(|op| format!("{}\n", op)).collect()
+    }
+
+    /// Decodes a log written by `encode()`, returning `None` if any line isn't a valid op.
+    pub fn decode(text: &str) -> Option<OpLog> {
+        let ops = text.lines().map(Op::parse).collect::<Option<Vec<Op>>>()?;
+        Some(OpLog { ops })
+    }
+
+    /// Replays the log against a fresh copy of the puzzle, returning just the resulting game.
+    pub fn replay_game(&self, puzzle: &SpellingBeeGame) -> SpellingBeeGame {
+        self.replay(puzzle).game
+    }
+}
+
+impl OpLog {
+    /// Returns the ops, oldest first.
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Adds an op to the end of the log.
+    pub fn push(&mut self, op: Op) {
+        self.ops.push(op);
+    }
+
+    /// Replays the log against a fresh copy of the puzzle. Words are kept if they were valid when
+    /// played and weren't later undone; nothing after giving up counts. Words played with a time
+    /// are played at that time, and the rest at the time of the replay.
+    pub fn replay(&self, puzzle: &SpellingBeeGame) -> Replay {
+        let play = |game: &mut SpellingBeeGame, word: &str, at: Option<u64>| match at {
+            Some(at) => game.play_at(word, at as f64),
+            None => game.play(word),
+        };
+        let mut played: Vec<(&str, Option<u64>)> = Vec::new();
+        let mut hints = Vec::new();
+        let mut given_up = false;
+
+        // The game can't take a word back, so an undo rebuilds it from the words still played.
+        let mut game = puzzle.clone();
+        for op in &self.ops {
+            match op {
+                Op::Play(word) => {
+                    if play(&mut game, word, None) == PlayResult::Valid {
+                        played.push((word, None));
+                    }
+                }
+                Op::PlayAt(word, at) => {
+                    if play(&mut game, word, Some(*at)) == PlayResult::Valid {
+                        played.push((word, Some(*at)));
+                    }
+                }
+                Op::Undo => {
+                    if played.pop().is_some() {
+                        // The rebuilt game keeps the clock the first play started.
+                        let started_at = game.started_at();
+                        game = puzzle.clone();
+                        if let Some(start) = started_at {
+                            game.start_clock(start as f64);
+                        }
+                        for &(word, at) in &played {
+                            play(&mut game, word, at);
+                        }
+                    }
+                }
+                Op::Hint(kind) => hints.push(kind.clone()),
+                Op::GiveUp => {
+                    given_up = true;
+                    break;
+                }
+            }
+        }
+
+        Replay { game, hints, given_up }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let mut log = OpLog::new();
+        log.play("will");
+        log.play_at("coil", 5_000.0);
+        log.undo();
+        log.hint("length");
+        log.give_up();
+        let encoded = log.encode();
+        assert_eq!(encoded, "play\twill\nplay\tcoil\t5000\nundo\nhint\tlength\ngiveup\n");
+        assert_eq!(OpLog::decode(&encoded), Some(log.clone()));
+        assert_eq!(OpLog::decode("play\twill\nfly\n"), None);
+        assert_eq!(OpLog::decode("play\tcoil\tsoon\n"), None);
+        assert_eq!(log.since(3).ops(), [Op::Hint("length".to_string()), Op::GiveUp]);
+    }
+
+    #[test]
+    fn test_replay() {
+        let puzzle = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let mut log = OpLog::new();
+        log.play_at("will", 1_000.0);
+        log.play_at("wilco", 2_000.0);
+        log.play_at("coil", 3_000.0);
+        log.undo();
+        log.play_at("cowgirl", 4_000.0);
+        log.hint("letter");
+        let mut later = OpLog::new();
+        later.give_up();
+        later.play("coil");
+        log.append(&later);
+
+        let replay = log.replay(&puzzle);
+        assert_eq!(replay.game.play_order(), ["will", "cowgirl"]);
+        assert_eq!(replay.game.play_times(), [0, 3_000]);
+        assert_eq!(replay.game.started_at(), Some(1_000));
+        assert_eq!(replay.hints, ["letter"]);
+        assert!(replay.given_up);
+    }
+}
@@ -0,0 +1,174 @@
+//! This module provides `PeerSync`, which lets two or more browsers co-op a puzzle over direct
+//! peer-to-peer data channels (such as WebRTC) without a relay server.
+//!
+//! Every accepted word becomes an op tagged with the id of the peer that played it and that peer's
+//! sequence number, counting from 1. Each peer applies another peer's ops strictly in sequence
+//! order, holding on to any that arrive early, and ignores ops it has already seen, so messages
+//! can be duplicated, reordered, or resent without harm. Since applying an op just plays its word,
+//! and playing an already-found word does nothing, every peer that has seen the same ops ends up
+//! with the same words and score no matter what order they arrived in.
+//!
+//! Messages are plain text, one op or clock entry per line with tab-separated fields, so they can
+//! go straight over a string data channel. Peer ids may not contain tabs or newlines.
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::{SpellingBeeGame, PlayResult};
+
+/// One peer's copy of a shared game, along with every op it has seen.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSync {
+    /// This peer's id.
+    peer: String,
+
+    /// The game, with every applied op played.
+    game: SpellingBeeGame,
+
+    /// For each peer, the words of its ops in sequence order, so that the op with sequence
+    /// number `n` is at index `n - 1`.
+    applied: BTreeMap<String, Vec<String>>,
+
+    /// Ops that arrived before some earlier op from the same peer, by peer and sequence number.
+    pending: BTreeMap<(String, usize), String>,
+}
+
+#[wasm_bindgen]
+impl PeerSync {
+    /// Starts syncing the given game as the peer with the given id. Every peer should start from
+    /// the same puzzle.
+    pub fn new(game: SpellingBeeGame, peer: &str) -> PeerSync {
+        PeerSync {
+            peer: peer.to_string(),
+            game,
+            applied: BTreeMap::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Plays a word locally, recording an op for the other peers if it's accepted.
+    pub fn play(&mut self, word: &str) -> PlayResult {
+        let result = self.game.play(word);
+        if result == PlayResult::Valid {
+            self.applied.entry(self.peer.clone()).or_default().push(word.to_string());
+        }
+        result
+    }
+
+    /// Returns the current score.
+    pub fn score(&self) -> usize {
+        self.game.score()
+    }
+
+    /// Returns a copy of the shared game.
+    pub fn game(&self) -> SpellingBeeGame {
+        self.game.clone()
+    }
+
+    /// Returns this peer's clock: the highest sequence number applied from each peer. Send this to
+    /// another peer so it can work out which ops to send back with `ops_since()`.
+    pub fn clock(&self) -> String {
+        self.applied.iter()
+            .map(|(peer, ops)| format!("{}\t{}\n", peer, ops.len()))
+            .collect()
+    }
+
+    /// Returns a message with every applied op that a peer with the given clock hasn't seen.
+    /// Passing an empty clock returns every op.
+    pub fn ops_since(&self, clock: &str) -> String {
+        let seen: BTreeMap<&str, usize> = clock.lines().filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some((fields.next()?, fields.next()?.parse().ok()?))
+        }).collect();
+
+        let mut message = String::new();
+        for (peer, ops) in &self.applied {
+            let from = seen.get(peer.as_str()).copied().unwrap_or(0);
+            for (i, word) in ops.iter().enumerate().skip(from) {
+                message.push_str(&format!("{}\t{}\t{}\n", peer, i + 1, word));
+            }
+        }
+        message
+    }
+
+    /// Applies every op in a message from another peer, skipping ones already seen and holding on
+    /// to ones that arrived early. Malformed lines are ignored. Returns the number of ops applied.
+    pub fn receive(&mut self, message: &str) -> usize {
+        let mut count = 0;
+        for line in message.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let op = match (fields.next(), fields.next().and_then(|s| s.parse().ok()),
+                            fields.next()) {
+                (Some(peer), Some(seq), Some(word)) if seq > 0 => (peer, seq, word),
+                _ => continue,
+            };
+            count += self.receive_op(op.0, op.1, op.2);
+        }
+        count
+    }
+
+    /// Returns the number of ops that arrived early and are waiting on earlier ones.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl PeerSync {
+    /// Returns the shared game.
+    pub fn game_ref(&self) -> &SpellingBeeGame {
+        &self.game
+    }
+
+    /// Applies one op, along with any pending ops from the same peer that it unblocks. Returns
+    /// the number of ops applied.
+    fn receive_op(&mut self, peer: &str, seq: usize, word: &str) -> usize {
+        let next = self.applied.get(peer).map_or(0, |ops| ops.len()) + 1;
+        if seq < next {
+            return 0;
+        } else if seq > next {
+            self.pending.insert((peer.to_string(), seq), word.to_string());
+            return 0;
+        }
+
+        self.game.play(word);
+        let ops = self.applied.entry(peer.to_string()).or_default();
+        ops.push(word.to_string());
+
+        let mut count = 1;
+        while let Some(word) = self.pending.remove(&(peer.to_string(), ops.len() + 1)) {
+            self.game.play(&word);
+            ops.push(word);
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_sync() {
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let mut ana = PeerSync::new(game.clone(), "ana");
+        let mut ben = PeerSync::new(game, "ben");
+        assert_eq!(ana.play("will"), PlayResult::Valid);
+        assert_eq!(ana.play("wilco"), PlayResult::InvalidWord);
+        assert_eq!(ana.play("coil"), PlayResult::Valid);
+        assert_eq!(ben.play("cowgirl"), PlayResult::Valid);
+        assert_eq!(ana.ops_since(""), "ana\t1\twill\nana\t2\tcoil\n");
+
+        // Ops that arrive out of order wait for the ones before them.
+        assert_eq!(ben.receive("ana\t2\tcoil\n"), 0);
+        assert_eq!(ben.pending_count(), 1);
+        assert_eq!(ben.receive("ana\t1\twill\nana\t1\twill\nnot an op\n"), 2);
+        assert_eq!(ben.pending_count(), 0);
+        assert_eq!(ben.clock(), "ana\t2\nben\t1\n");
+
+        assert_eq!(ana.receive(&ben.ops_since(&ana.clock())), 1);
+        assert_eq!(ana.ops_since(&ben.clock()), "");
+        assert_eq!((ana.score(), ben.score()), (16, 16));
+        assert_eq!(ana.game_ref().played_words(), ben.game_ref().played_words());
+    }
+}
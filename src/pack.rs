@@ -0,0 +1,286 @@
+//! This module provides `PuzzlePack`, a named set of puzzles, such as a themed week, that can be
+//! distributed as one file, along with the player's progress through it.
+//!
+//! A pack is text with one record per line and tab-separated fields (shown as spaces below). The
+//! first record names the pack, and each one after that is a puzzle with an id, a date, its
+//! optional and required letters, and optionally a comma-separated list of modifiers for the
+//! frontend, like `layout=hpreat`:
+//!
+//! ```text
+//! pack    Birds of a Feather
+//! puzzle  birds-1  2024-05-06  cglorw  i
+//! puzzle  birds-2  2024-05-07  aehnrt  p  layout=hpreat,hard
+//! ```
+//!
+//! Blank lines and lines starting with `#` are skipped.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use crate::rank::Rank;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::{is_date, is_letter, letters};
+
+/// The number of optional letters in a puzzle.
+const OPTIONAL_LETTERS: usize = 6;
+
+/// A puzzle in a pack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackPuzzle {
+    /// The id, unique within the pack.
+    pub id: String,
+
+    /// The date the puzzle is for, as `YYYY-MM-DD`.
+    pub date: String,
+
+    /// The optional letters.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: String,
+
+    /// The modifiers, in the order given.
+    pub modifiers: Vec<String>,
+}
+
+impl PackPuzzle {
+    /// Returns the value of a `key=value` modifier, if the puzzle has one with the given key.
+    pub fn modifier(&self, key: &str) -> Option<&str> {
+        self.modifiers.iter().find_map(|m| {
+            m.split_once('=').filter(|(k, _)| *k == key).map(|(_, value)| value)
+        })
+    }
+
+    /// Returns `true` if the puzzle has the given modifier, either by itself or as the key of a
+    /// `key=value` modifier.
+    pub fn has_modifier(&self, name: &str) -> bool {
+        self.modifiers.iter()
+            .any(|m| m == name || m.split_once('=').is_some_and(|(key, _)| key == name))
+    }
+}
+
+/// A player's progress on one puzzle in a pack.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzleProgress {
+    /// The score.
+    pub score: usize,
+
+    /// The maximum possible score.
+    pub max_score: usize,
+
+    /// The number of answers found.
+    pub words_found: usize,
+
+    /// The number of answers.
+    pub answer_count: usize,
+
+    /// The rank reached.
+    pub rank: Rank,
+}
+
+/// The ways loading a pack can fail. Line numbers start from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PackError {
+    /// The first record isn't a `pack` record with a name.
+    MissingHeader,
+
+    /// The line isn't a valid record.
+    BadRecord(usize),
+
+    /// The puzzle on the line doesn't have six distinct optional letters and a different
+    /// required letter.
+    BadLetters(usize),
+
+    /// The puzzle on the line doesn't have a `YYYY-MM-DD` date.
+    BadDate(usize),
+
+    /// More than one puzzle has the given id.
+    DuplicateId(String),
+
+    /// The pack has no puzzles.
+    Empty,
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::MissingHeader => write!(f, "the pack doesn't start with a name"),
+            PackError::BadRecord(line) => write!(f, "line {} isn't a valid record", line),
+            PackError::BadLetters(line) => write!(f, "the puzzle on line {} has bad letters", line),
+            PackError::BadDate(line) => write!(f, "the puzzle on line {} has a bad date", line),
+            PackError::DuplicateId(id) => write!(f, "more than one puzzle has the id {}", id),
+            PackError::Empty => write!(f, "the pack has no puzzles"),
+        }
+    }
+}
+
+impl Error for PackError {}
+
+/// A named set of puzzles and the progress made on them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzlePack {
+    /// The name.
+    name: String,
+
+    /// The puzzles, in the order given.
+    puzzles: Vec<PackPuzzle>,
+
+    /// The progress on each puzzle that's been started, by id.
+    progress: BTreeMap<String, PuzzleProgress>,
+}
+
+impl PuzzlePack {
+    /// Loads and validates a pack in the format described in the module documentation.
+    pub fn load(text: &str) -> Result<PuzzlePack, PackError> {
+        let mut records = text.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'));
+
+        let name = match records.next() {
+            Some((_, line)) => match line.split_once('\t') {
+                Some(("pack", name)) if !name.trim().is_empty() => name.trim().to_string(),
+                _ => return Err(PackError::MissingHeader),
+            },
+            None => return Err(PackError::MissingHeader),
+        };
+
+        let mut puzzles = Vec::new();
+        let mut ids = BTreeSet::new();
+        for (line_number, line) in records {
+            let puzzle = parse_puzzle(line_number, line)?;
+            if !ids.insert(puzzle.id.clone()) {
+                return Err(PackError::DuplicateId(puzzle.id));
+            }
+            puzzles.push(puzzle);
+        }
+
+        if puzzles.is_empty() {
+            return Err(PackError::Empty);
+        }
+
+        Ok(PuzzlePack { name, puzzles, progress: BTreeMap::new() })
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the puzzles, in the order given.
+    pub fn puzzles(&self) -> &[PackPuzzle] {
+        &self.puzzles
+    }
+
+    /// Returns the puzzle with the given id, if the pack has it.
+    pub fn puzzle(&self, id: &str) -> Option<&PackPuzzle> {
+        self.puzzles.iter().find(|p| p.id == id)
+    }
+
+    /// Records the current state of a game as the progress on the puzzle with the given id,
+    /// replacing any progress recorded before. Returns `false`, recording nothing, if the pack
+    /// has no puzzle with that id.
+    pub fn record(&mut self, id: &str, game: &SpellingBeeGame) -> bool {
+        if self.puzzle(id).is_none() {
+            return false;
+        }
+
+        let def = game.puzzle_def();
+        self.progress.insert(id.to_string(), PuzzleProgress {
+            score: game.score(),
+            max_score: def.max_score(),
+            words_found: game.words_found(),
+            answer_count: def.answer_count(),
+            rank: game.rank(),
+        });
+        true
+    }
+
+    /// Returns the progress on the puzzle with the given id, if any has been recorded.
+    pub fn progress(&self, id: &str) -> Option<&PuzzleProgress> {
+        self.progress.get(id)
+    }
+
+    /// Returns the number of puzzles that have been started.
+    pub fn started_count(&self) -> usize {
+        self.progress.len()
+    }
+
+    /// Returns the number of puzzles in which every answer has been found.
+    pub fn completed_count(&self) -> usize {
+        self.progress.values().filter(|p| p.words_found == p.answer_count).count()
+    }
+
+    /// Returns the total score across the pack's puzzles.
+    pub fn total_score(&self) -> usize {
+        self.progress.values().map(|p| p.score).sum()
+    }
+}
+
+/// Parses a `puzzle` record.
+fn parse_puzzle(line_number: usize, line: &str) -> Result<PackPuzzle, PackError> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let (id, date, optional_letters, required_letter, modifiers) = match fields.as_slice() {
+        ["puzzle", id, date, optional, required] => (id, date, optional, required, ""),
+        ["puzzle", id, date, optional, required, modifiers] => {
+            (id, date, optional, required, *modifiers)
+        }
+        _ => return Err(PackError::BadRecord(line_number)),
+    };
+
+    if id.trim().is_empty() {
+        return Err(PackError::BadRecord(line_number));
+    }
+    if !is_date(date) {
+        return Err(PackError::BadDate(line_number));
+    }
+
+    let optional: BTreeSet<&str> = letters(optional_letters).into_iter().collect();
+    if letters(optional_letters).len() != OPTIONAL_LETTERS || optional.len() != OPTIONAL_LETTERS ||
+        letters(required_letter).len() != 1 || optional.contains(required_letter) ||
+        !optional.iter().chain(&[*required_letter]).all(|l| is_letter(l))
+    {
+        return Err(PackError::BadLetters(line_number));
+    }
+
+    Ok(PackPuzzle {
+        id: id.trim().to_string(),
+        date: date.to_string(),
+        optional_letters: optional_letters.to_string(),
+        required_letter: required_letter.to_string(),
+        modifiers: modifiers.split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(String::from)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let text = "# a test pack\npack\tTest\npuzzle\tone\t2024-05-06\tcglorw\ti\n\n\
+                    puzzle\ttwo\t2024-05-07\taehnrt\tp\tlayout=hpreat,hard\n";
+        let mut pack = PuzzlePack::load(text).unwrap();
+        assert_eq!(pack.name(), "Test");
+        assert_eq!(pack.puzzles().len(), 2);
+        assert_eq!(pack.puzzle("two").unwrap().modifier("layout"), Some("hpreat"));
+        assert!(pack.puzzle("two").unwrap().has_modifier("hard"));
+
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\n");
+        game.play("will");
+        assert!(pack.record("one", &game));
+        assert!(!pack.record("three", &game));
+        assert_eq!((pack.started_count(), pack.completed_count(), pack.total_score()), (1, 0, 1));
+
+        assert_eq!(PuzzlePack::load("puzzle\tone\t2024-05-06\tcglorw\ti\n"),
+                   Err(PackError::MissingHeader));
+        assert_eq!(PuzzlePack::load("pack\tTest\npuzzle\tone\t2024-13-06\tcglorw\ti\n"),
+                   Err(PackError::BadDate(2)));
+        assert_eq!(PuzzlePack::load("pack\tTest\npuzzle\tone\t2024-05-06\tcglorr\ti\n"),
+                   Err(PackError::BadLetters(2)));
+    }
+}
@@ -0,0 +1,159 @@
+//! This module provides `Curriculum`, a sequence of practice puzzles from easiest to hardest, and
+//! `PracticeProfile`, a player's place in it. New players start on small puzzles with short,
+//! common answers and move on to the next one each time they reach the target rank, instead of
+//! facing whatever the daily puzzle happens to be.
+
+use std::collections::HashSet;
+use crate::generate::game;
+use crate::rank::Rank;
+use crate::search::{Candidate, Interest, PuzzleSearch, SearchConfig};
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::letter_count;
+
+/// The rank a player has to reach on a practice puzzle to move on, unless the profile says
+/// otherwise.
+pub const DEFAULT_TARGET: Rank = Rank::Amazing;
+
+/// Returns how hard a puzzle is to play through. Every answer counts one point, plus one for each
+/// letter beyond `min_length`, and answers that aren't in `common_words` count double. If
+/// `common_words` is empty, no answer counts as uncommon.
+pub fn difficulty(candidate: &Candidate, common_words: &HashSet<String>, min_length: usize)
+    -> usize
+{
+    candidate.answers.iter().map(|word| {
+        let points = 1 + letter_count(word).saturating_sub(min_length);
+        if common_words.is_empty() || common_words.contains(word) {
+            points
+        } else {
+            2 * points
+        }
+    }).sum()
+}
+
+/// Practice puzzles in order from easiest to hardest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curriculum {
+    /// The puzzles, easiest first.
+    puzzles: Vec<Candidate>,
+}
+
+impl Curriculum {
+    /// Searches for puzzles like `PuzzleSearch::search()` and orders them by `difficulty()`,
+    /// using the common words in `interest`. Puzzles of the same difficulty stay in the order the
+    /// search found them.
+    pub fn new(search: &PuzzleSearch, interest: &Interest, config: &SearchConfig) -> Curriculum {
+        Curriculum::from_puzzles(search.search(interest, config), &interest.common_words,
+                                 search.min_length())
+    }
+
+    /// Orders the given puzzles by `difficulty()` into a curriculum, for answers at least
+    /// `min_length` letters long.
+    pub fn from_puzzles(mut puzzles: Vec<Candidate>, common_words: &HashSet<String>,
+                        min_length: usize) -> Curriculum
+    {
+        puzzles.sort_by_key(|p| difficulty(p, common_words, min_length));
+        Curriculum { puzzles }
+    }
+
+    /// Returns the puzzles, easiest first.
+    pub fn puzzles(&self) -> &[Candidate] {
+        &self.puzzles
+    }
+
+    /// Returns the number of puzzles.
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /// Returns `true` if there are no puzzles.
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    /// Builds a game for the puzzle at the given level, counting from 0, if there is one.
+    pub fn game(&self, level: usize) -> Option<SpellingBeeGame> {
+        self.puzzles.get(level).map(game)
+    }
+}
+
+/// A player's place in a curriculum. Frontends keep one per player profile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PracticeProfile {
+    /// The level of the puzzle the player is on, counting from 0.
+    pub level: usize,
+
+    /// The rank the player has to reach to move on.
+    pub target: Rank,
+}
+
+impl Default for PracticeProfile {
+    fn default() -> PracticeProfile {
+        PracticeProfile { level: 0, target: DEFAULT_TARGET }
+    }
+}
+
+impl PracticeProfile {
+    /// Creates a profile at the start of the curriculum.
+    pub fn new() -> PracticeProfile {
+        PracticeProfile::default()
+    }
+
+    /// Returns the puzzle the player is on, or `None` if they've finished the curriculum.
+    pub fn current<'a>(&self, curriculum: &'a Curriculum) -> Option<&'a Candidate> {
+        curriculum.puzzles.get(self.level)
+    }
+
+    /// Returns `true` if the player has finished every puzzle in the curriculum.
+    pub fn is_finished(&self, curriculum: &Curriculum) -> bool {
+        self.level >= curriculum.len()
+    }
+
+    /// Records a game of the puzzle the player is on, moving on to the next level if it reached
+    /// the target rank. Returns `true` if the player moved on. Games of any other puzzle are
+    /// ignored.
+    pub fn record(&mut self, curriculum: &Curriculum, game: &SpellingBeeGame) -> bool {
+        let current = match self.current(curriculum) {
+            Some(puzzle) => puzzle,
+            None => return false,
+        };
+
+        if game.optional_letters() != current.optional_letters ||
+            game.required_letter() != current.required_letter.to_string() ||
+            game.rank() < self.target
+        {
+            return false;
+        }
+
+        self.level += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curriculum() {
+        let search = PuzzleSearch::from_words([
+            "cowgirl", "will", "crow", "grill", "growl", "wilco", "clog", "glow", "logic", "coil",
+            "roil", "girl", "zebra", "bread", "beard", "dare", "read", "bare",
+        ].iter().map(|w| w.to_string()));
+        let config = SearchConfig { min_answers: 1, ..SearchConfig::default() };
+        let curriculum = Curriculum::new(&search, &Interest::default(), &config);
+        assert!(!curriculum.is_empty());
+        let none = HashSet::new();
+        assert!(curriculum.puzzles().windows(2)
+                .all(|w| difficulty(&w[0], &none, 4) <= difficulty(&w[1], &none, 4)));
+
+        let mut profile = PracticeProfile::new();
+        let mut game = curriculum.game(0).unwrap();
+        assert!(!profile.record(&curriculum, &game));
+        for word in curriculum.puzzles()[0].answers.clone() {
+            game.play(&word);
+        }
+        assert!(profile.record(&curriculum, &game));
+        assert_eq!(profile.level, 1);
+        assert!(!profile.record(&curriculum, &game));
+    }
+}
@@ -0,0 +1,151 @@
+//! This module provides `Sheet`, a printable version of a puzzle for playing on paper: the hive
+//! letters, blank answer lines grouped by length, and a hint grid of answer counts by starting
+//! letter and length. Sheets render as plain text or as simple HTML.
+
+use std::collections::BTreeSet;
+use crate::hints::HintGrid;
+use crate::utils::letters;
+
+/// The number of blank answer lines printed per row.
+const BLANKS_PER_ROW: usize = 6;
+
+/// A printable puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sheet {
+    /// The optional letters, in sorted order.
+    optional_letters: Vec<String>,
+
+    /// The required letter.
+    required_letter: String,
+
+    /// The number of answers by starting letter and length.
+    grid: HintGrid,
+
+    /// The number of pangrams.
+    pangrams: usize,
+}
+
+impl Sheet {
+    /// Creates a sheet for the puzzle with the given letters and answers.
+    pub fn new<I, S>(optional_letters: &BTreeSet<String>, required_letter: &str,
+                     answers: I) -> Sheet
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let answers: Vec<S> = answers.into_iter().collect();
+        let mut pangrams = 0;
+        for word in &answers {
+            let distinct: BTreeSet<&str> = letters(word.as_ref()).into_iter().collect();
+            if distinct.contains(required_letter) &&
+                optional_letters.iter().all(|l| distinct.contains(l.as_str()))
+            {
+                pangrams += 1;
+            }
+        }
+
+        Sheet {
+            optional_letters: optional_letters.iter().cloned().collect(),
+            required_letter: required_letter.to_string(),
+            grid: HintGrid::new(answers.iter().map(|w| w.as_ref())),
+            pangrams,
+        }
+    }
+
+    /// Returns the optional letters in uppercase, with the required letter in the middle, in the
+    /// order they're printed across the hive's three rows.
+    fn hive_rows(&self) -> [Vec<String>; 3] {
+        let at = |i: usize| {
+            self.optional_letters.get(i).map_or(" ".to_string(), |l| l.to_uppercase())
+        };
+        let center = self.required_letter.to_uppercase();
+        [vec![at(0), at(1)], vec![at(2), center, at(3)], vec![at(4), at(5)]]
+    }
+
+    /// Renders the sheet as plain text.
+    pub fn to_text(&self) -> String {
+        let [top, middle, bottom] = self.hive_rows();
+        let mut text = String::from("SPELLING BEE\n\n");
+        text.push_str(&format!("    {}   {}\n", top[0], top[1]));
+        text.push_str(&format!("  {}  [{}]  {}\n", middle[0], middle[1], middle[2]));
+        text.push_str(&format!("    {}   {}\n\n", bottom[0], bottom[1]));
+        text.push_str(&format!("Words: {}  Pangrams: {}\n", self.grid.total(), self.pangrams));
+
+        for (len, n) in self.grid.length_counts() {
+            text.push_str(&format!("\n{} letters\n", len));
+            let blank = "_".repeat(len);
+            let blanks = vec![blank.as_str(); n];
+            for row in blanks.chunks(BLANKS_PER_ROW) {
+                text.push_str(&row.join("  "));
+                text.push('\n');
+            }
+        }
+
+        text.push_str("\nHint grid\n\n");
+        text.push_str(&self.grid.to_text());
+        text
+    }
+
+    /// Renders the sheet as a standalone HTML fragment, with no styling beyond table borders.
+    pub fn to_html(&self) -> String {
+        let [top, middle, bottom] = self.hive_rows();
+        let mut html = String::from("<div class=\"spelling-bee-sheet\">\n<h1>Spelling Bee</h1>\n");
+        html.push_str("<pre class=\"hive\">\n");
+        html.push_str(&format!("    {}   {}\n", top[0], top[1]));
+        html.push_str(&format!("  {}  <b>{}</b>  {}\n", middle[0], middle[1], middle[2]));
+        html.push_str(&format!("    {}   {}\n", bottom[0], bottom[1]));
+        html.push_str("</pre>\n");
+        html.push_str(&format!("<p>Words: {} &middot; Pangrams: {}</p>\n", self.grid.total(),
+                               self.pangrams));
+
+        for (len, n) in self.grid.length_counts() {
+            html.push_str(&format!("<h2>{} letters</h2>\n<p>", len));
+            let blank = "_".repeat(len);
+            html.push_str(&vec![blank.as_str(); n].join(" &nbsp; "));
+            html.push_str("</p>\n");
+        }
+
+        let lengths = self.grid.lengths();
+        html.push_str("<h2>Hint grid</h2>\n<table border=\"1\">\n<tr><th></th>");
+        for len in &lengths {
+            html.push_str(&format!("<th>{}</th>", len));
+        }
+        html.push_str("<th>&Sigma;</th></tr>\n");
+        for (first, row) in self.grid.rows() {
+            html.push_str(&format!("<tr><th>{}</th>", first.to_uppercase()));
+            for len in &lengths {
+                match row.get(len) {
+                    Some(n) => html.push_str(&format!("<td>{}</td>", n)),
+                    None => html.push_str("<td>-</td>"),
+                }
+            }
+            html.push_str(&format!("<td>{}</td></tr>\n", row.values().sum::<usize>()));
+        }
+        html.push_str("<tr><th>&Sigma;</th>");
+        for n in self.grid.length_counts().values() {
+            html.push_str(&format!("<td>{}</td>", n));
+        }
+        html.push_str(&format!("<td>{}</td></tr>\n</table>\n</div>\n", self.grid.total()));
+        html
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text() {
+        let answers: Vec<String> = ["will", "cowgirl", "coil", "logic", "girl"].iter()
+            .map(|w| w.to_string())
+            .collect();
+        let letters = ["c", "g", "l", "o", "r", "w"].iter().map(|l| l.to_string()).collect();
+        let sheet = Sheet::new(&letters, "i", &answers);
+        let text = sheet.to_text();
+
+        assert!(text.contains("  L  [I]  O\n"));
+        assert!(text.contains("Words: 5  Pangrams: 1\n"));
+        assert!(text.contains("\n4 letters\n____  ____  ____\n"));
+        assert!(text.contains("C:   1   -   1   2\n"));
+        assert!(text.ends_with("Σ:   3   1   1   5\n"));
+    }
+}
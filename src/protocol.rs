@@ -0,0 +1,450 @@
+//! This module provides the messages of the room protocol, spoken between the room server run
+//! with `spellingbee --serve` (see the `server` module) and its clients, so that a WebAssembly
+//! client encodes and decodes messages exactly as the server does.
+//!
+//! Messages are plain text with tab-separated fields, one message per WebSocket text frame.
+//! Player ids, join codes and words may not contain tabs or newlines. A client first opens a
+//! lobby, co-op or versus (giving the round length in milliseconds and the number of players
+//! knocked out each round), or joins one by its join code, then marks itself ready, then plays
+//! words once the game has started. It can leave a lobby whose game hasn't started:
+//!
+//! ```text
+//! create  alice   coop
+//! create  alice   versus   60000  1
+//! join    QXRTA   bob
+//! ready
+//! unready
+//! leave
+//! play    cowgirl
+//! ```
+//!
+//! Whenever someone joins, leaves, or changes whether they're ready, everyone in the lobby is
+//! sent its join code, mode, and host, followed by a `member` message for each player, so clients
+//! should forget the members they knew of on a `lobby` message. Once every player is ready the
+//! game starts and everyone is sent the puzzle, its letters and maximum score. Each accepted play
+//! is sent to everyone in the lobby as a `found` message with who found it, the points it earned,
+//! and the new score, which is the team's in co-op and the player's own in versus, and a play
+//! that isn't accepted is sent back to just the player who made it as a `rejected` message with
+//! the catalog key of the reason (see `i18n::play_result_key()`). In versus, everyone is told who
+//! is knocked out at the end of each round, and who won once only one player is left:
+//!
+//! ```text
+//! lobby       QXRTA    versus   alice
+//! member      alice    ready
+//! member      bob      waiting
+//! puzzle      cglorw   i        34
+//! found       alice    cowgirl  14  14
+//! rejected    cow      play.invalid_length
+//! eliminated  bob      1
+//! winner      alice
+//! error       join before playing
+//! ```
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// A message from a client to the server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientMessage {
+    /// Opens a co-op lobby and joins it as the given player.
+    CreateCoop(String),
+
+    /// Opens a versus lobby and joins it as the given player, with the given round length in
+    /// milliseconds and number of players knocked out each round.
+    CreateVersus { player: String, round_length: u64, eliminations_per_round: usize },
+
+    /// Joins the lobby with the given join code as the given player.
+    Join { code: String, player: String },
+
+    /// Marks the client's player as ready to start, or not.
+    Ready(bool),
+
+    /// Leaves the lobby, if its game hasn't started.
+    Leave,
+
+    /// Plays a word.
+    Play(String),
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::CreateCoop(player) => write!(f, "create\t{}\tcoop", player),
+            ClientMessage::CreateVersus { player, round_length, eliminations_per_round } => {
+                write!(f, "create\t{}\tversus\t{}\t{}", player, round_length,
+                       eliminations_per_round)
+            }
+            ClientMessage::Join { code, player } => write!(f, "join\t{}\t{}", code, player),
+            ClientMessage::Ready(true) => write!(f, "ready"),
+            ClientMessage::Ready(false) => write!(f, "unready"),
+            ClientMessage::Leave => write!(f, "leave"),
+            ClientMessage::Play(word) => write!(f, "play\t{}", word),
+        }
+    }
+}
+
+impl ClientMessage {
+    /// Parses a message from the format written by `Display`. Returns `None` if a player id, join
+    /// code or word has a tab or newline in it.
+    pub fn parse(text: &str) -> Option<ClientMessage> {
+        if text.contains(['\n', '\r']) {
+            return None;
+        }
+        let fields: Vec<&str> = text.split('\t').collect();
+        match fields.as_slice() {
+            ["create", player, "coop"] if !player.is_empty() => {
+                Some(ClientMessage::CreateCoop(player.to_string()))
+            }
+            ["create", player, "versus", round_length, eliminations_per_round]
+                if !player.is_empty() => Some(ClientMessage::CreateVersus {
+                    player: player.to_string(),
+                    round_length: round_length.parse().ok()?,
+                    eliminations_per_round: eliminations_per_round.parse().ok()?,
+                }),
+            ["join", code, player] if !code.is_empty() && !player.is_empty() => {
+                Some(ClientMessage::Join { code: code.to_string(), player: player.to_string() })
+            }
+            ["ready"] => Some(ClientMessage::Ready(true)),
+            ["unready"] => Some(ClientMessage::Ready(false)),
+            ["leave"] => Some(ClientMessage::Leave),
+            ["play", word] => Some(ClientMessage::Play(word.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// A message from the server to a client.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServerMessage {
+    /// The client's lobby: its join code, its mode (`coop` or `versus`), and its host.
+    Lobby { code: String, mode: String, host: String },
+
+    /// A player in the client's lobby, and whether they're ready.
+    Member { player: String, ready: bool },
+
+    /// The game has started on this puzzle: the optional letters, the required letter, and the
+    /// maximum score.
+    Puzzle { optional_letters: String, required_letter: String, max_score: usize },
+
+    /// A player found a word, earning the given points and bringing the team, or in versus the
+    /// player, to the given score.
+    Found { player: String, word: String, points: usize, score: usize },
+
+    /// The client's play wasn't accepted, for the reason with the given catalog key.
+    Rejected { word: String, reason: String },
+
+    /// A player was knocked out of a versus game at the end of the given round.
+    Eliminated { player: String, round: usize },
+
+    /// A player won a versus game, being the only one left.
+    Winner(String),
+
+    /// The client's message couldn't be handled.
+    Error(String),
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Lobby { code, mode, host } => {
+                write!(f, "lobby\t{}\t{}\t{}", code, mode, host)
+            }
+            ServerMessage::Member { player, ready } => {
+                write!(f, "member\t{}\t{}", player, if *ready { "ready" } else { "waiting" })
+            }
+            ServerMessage::Puzzle { optional_letters, required_letter, max_score } => {
+                write!(f, "puzzle\t{}\t{}\t{}", optional_letters, required_letter, max_score)
+            }
+            ServerMessage::Found { player, word, points, score } => {
+                write!(f, "found\t{}\t{}\t{}\t{}", player, word, points, score)
+            }
+            ServerMessage::Rejected { word, reason } => write!(f, "rejected\t{}\t{}", word, reason),
+            ServerMessage::Eliminated { player, round } => {
+                write!(f, "eliminated\t{}\t{}", player, round)
+            }
+            ServerMessage::Winner(player) => write!(f, "winner\t{}", player),
+            ServerMessage::Error(message) => write!(f, "error\t{}", message),
+        }
+    }
+}
+
+impl ServerMessage {
+    /// Parses a message from the format written by `Display`.
+    pub fn parse(text: &str) -> Option<ServerMessage> {
+        let fields: Vec<&str> = text.split('\t').collect();
+        match fields.as_slice() {
+            ["lobby", code, mode, host] => Some(ServerMessage::Lobby {
+                code: code.to_string(),
+                mode: mode.to_string(),
+                host: host.to_string(),
+            }),
+            ["member", player, ready] => Some(ServerMessage::Member {
+                player: player.to_string(),
+                ready: match *ready {
+                    "ready" => true,
+                    "waiting" => false,
+                    _ => return None,
+                },
+            }),
+            ["puzzle", optional_letters, required_letter, max_score] => Some(ServerMessage::Puzzle {
+                optional_letters: optional_letters.to_string(),
+                required_letter: required_letter.to_string(),
+                max_score: max_score.parse().ok()?,
+            }),
+            ["found", player, word, points, score] => Some(ServerMessage::Found {
+                player: player.to_string(),
+                word: word.to_string(),
+                points: points.parse().ok()?,
+                score: score.parse().ok()?,
+            }),
+            ["rejected", word, reason] => Some(ServerMessage::Rejected {
+                word: word.to_string(),
+                reason: reason.to_string(),
+            }),
+            ["eliminated", player, round] => Some(ServerMessage::Eliminated {
+                player: player.to_string(),
+                round: round.parse().ok()?,
+            }),
+            ["winner", player] => Some(ServerMessage::Winner(player.to_string())),
+            ["error", message] => Some(ServerMessage::Error(message.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes a message opening a co-op lobby and joining it as the given player.
+#[wasm_bindgen]
+pub fn encode_create_coop(player: &str) -> String {
+    ClientMessage::CreateCoop(player.to_string()).to_string()
+}
+
+/// Encodes a message opening a versus lobby and joining it as the given player, with the given
+/// round length in milliseconds and number of players knocked out each round.
+#[wasm_bindgen]
+pub fn encode_create_versus(player: &str, round_length: f64,
+                            eliminations_per_round: usize) -> String {
+    ClientMessage::CreateVersus {
+        player: player.to_string(),
+        round_length: round_length.max(0.0) as u64,
+        eliminations_per_round,
+    }.to_string()
+}
+
+/// Encodes a message joining the lobby with the given join code as the given player.
+#[wasm_bindgen]
+pub fn encode_join(code: &str, player: &str) -> String {
+    ClientMessage::Join { code: code.to_string(), player: player.to_string() }.to_string()
+}
+
+/// Encodes a message marking the client's player as ready to start, or not.
+#[wasm_bindgen]
+pub fn encode_ready(ready: bool) -> String {
+    ClientMessage::Ready(ready).to_string()
+}
+
+/// Encodes a message leaving the lobby.
+#[wasm_bindgen]
+pub fn encode_leave() -> String {
+    ClientMessage::Leave.to_string()
+}
+
+/// Encodes a message playing the given word.
+#[wasm_bindgen]
+pub fn encode_play(word: &str) -> String {
+    ClientMessage::Play(word.to_string()).to_string()
+}
+
+/// Decodes a message from the server, returning `None` if it isn't valid.
+#[wasm_bindgen]
+pub fn decode_server_message(text: &str) -> Option<RoomEvent> {
+    ServerMessage::parse(text).map(|message| RoomEvent { message })
+}
+
+/// A decoded message from the server, for JavaScript, from `decode_server_message()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoomEvent {
+    /// The message.
+    message: ServerMessage,
+}
+
+#[wasm_bindgen]
+impl RoomEvent {
+    /// Returns what kind of message it is: `lobby`, `member`, `puzzle`, `found`, `rejected`,
+    /// `eliminated`, `winner`, or `error`.
+    pub fn kind(&self) -> String {
+        match self.message {
+            ServerMessage::Lobby { .. } => "lobby",
+            ServerMessage::Member { .. } => "member",
+            ServerMessage::Puzzle { .. } => "puzzle",
+            ServerMessage::Found { .. } => "found",
+            ServerMessage::Rejected { .. } => "rejected",
+            ServerMessage::Eliminated { .. } => "eliminated",
+            ServerMessage::Winner(_) => "winner",
+            ServerMessage::Error(_) => "error",
+        }.to_string()
+    }
+
+    /// Returns the join code of a `lobby` message.
+    pub fn code(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Lobby { code, .. } => Some(code.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the mode of a `lobby` message, `coop` or `versus`.
+    pub fn mode(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Lobby { mode, .. } => Some(mode.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the host of a `lobby` message.
+    pub fn host(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Lobby { host, .. } => Some(host.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the player of a `member` message is ready.
+    pub fn ready(&self) -> Option<bool> {
+        match self.message {
+            ServerMessage::Member { ready, .. } => Some(ready),
+            _ => None,
+        }
+    }
+
+    /// Returns the optional letters of a `puzzle` message.
+    pub fn optional_letters(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Puzzle { optional_letters, .. } => Some(optional_letters.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the required letter of a `puzzle` message.
+    pub fn required_letter(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Puzzle { required_letter, .. } => Some(required_letter.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the maximum score of a `puzzle` message.
+    pub fn max_score(&self) -> Option<usize> {
+        match self.message {
+            ServerMessage::Puzzle { max_score, .. } => Some(max_score),
+            _ => None,
+        }
+    }
+
+    /// Returns the player of a `member`, `found`, `eliminated`, or `winner` message.
+    pub fn player(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Member { player, .. } | ServerMessage::Found { player, .. } |
+            ServerMessage::Eliminated { player, .. } | ServerMessage::Winner(player) => {
+                Some(player.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the word of a `found` or `rejected` message.
+    pub fn word(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Found { word, .. } | ServerMessage::Rejected { word, .. } => {
+                Some(word.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the points the word of a `found` message earned.
+    pub fn points(&self) -> Option<usize> {
+        match self.message {
+            ServerMessage::Found { points, .. } => Some(points),
+            _ => None,
+        }
+    }
+
+    /// Returns the score after a `found` message: the team's in co-op, or the player's in versus.
+    pub fn score(&self) -> Option<usize> {
+        match self.message {
+            ServerMessage::Found { score, .. } => Some(score),
+            _ => None,
+        }
+    }
+
+    /// Returns the round at the end of which the player of an `eliminated` message was knocked
+    /// out.
+    pub fn round(&self) -> Option<usize> {
+        match self.message {
+            ServerMessage::Eliminated { round, .. } => Some(round),
+            _ => None,
+        }
+    }
+
+    /// Returns the catalog key of the reason of a `rejected` message, or the text of an `error`
+    /// message.
+    pub fn reason(&self) -> Option<String> {
+        match &self.message {
+            ServerMessage::Rejected { reason, .. } | ServerMessage::Error(reason) => {
+                Some(reason.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RoomEvent {
+    /// Returns the message.
+    pub fn message(&self) -> &ServerMessage {
+        &self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(encode_play("cowgirl"), "play\tcowgirl");
+        assert_eq!(encode_create_versus("alice", 60_000.0, 1), "create\talice\tversus\t60000\t1");
+        assert_eq!(ClientMessage::parse(&encode_join("QXRTA", "alice")),
+                   Some(ClientMessage::Join { code: "QXRTA".to_string(),
+                                              player: "alice".to_string() }));
+        assert_eq!(ClientMessage::parse(&encode_create_coop("alice")),
+                   Some(ClientMessage::CreateCoop("alice".to_string())));
+        assert_eq!(ClientMessage::parse(&encode_ready(false)), Some(ClientMessage::Ready(false)));
+        assert_eq!(ClientMessage::parse(&encode_leave()), Some(ClientMessage::Leave));
+        assert_eq!(ClientMessage::parse("join\tQXRTA\t"), None);
+        assert_eq!(ClientMessage::parse("create\talice\tversus\tlong\t1"), None);
+        assert_eq!(ClientMessage::parse("play\tcow\ngirl"), None);
+        assert_eq!(ClientMessage::parse("play\tcow\tgirl"), None);
+
+        let found = ServerMessage::Found {
+            player: "alice".to_string(),
+            word: "cowgirl".to_string(),
+            points: 14,
+            score: 14,
+        };
+        assert_eq!(found.to_string(), "found\talice\tcowgirl\t14\t14");
+        let event = decode_server_message(&found.to_string()).unwrap();
+        assert_eq!((event.kind().as_str(), event.points()), ("found", Some(14)));
+        assert_eq!(event.reason(), None);
+        assert!(decode_server_message("found\talice\tcowgirl\tmany\t14").is_none());
+
+        let member = decode_server_message("member\tbob\twaiting").unwrap();
+        assert_eq!((member.kind().as_str(), member.ready()), ("member", Some(false)));
+        assert_eq!(member.player().as_deref(), Some("bob"));
+        let lobby = ServerMessage::parse("lobby\tQXRTA\tversus\talice").unwrap();
+        assert_eq!(lobby.to_string(), "lobby\tQXRTA\tversus\talice");
+        let eliminated = decode_server_message("eliminated\tbob\t2").unwrap();
+        assert_eq!((eliminated.player().as_deref(), eliminated.round()), (Some("bob"), Some(2)));
+        assert!(decode_server_message("member\tbob\tmaybe").is_none());
+    }
+}
@@ -0,0 +1,109 @@
+//! This module provides `PuzzleDef`, the definition of a puzzle without any progress in it: its
+//! letters, an id, and how many answers and points it has. A definition is a few dozen bytes, so
+//! archives and servers can list and cache puzzles without shipping whole games.
+
+use wasm_bindgen::prelude::*;
+
+/// The letters and totals of a puzzle, from `SpellingBeeGame::puzzle_def()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzleDef {
+    /// The id, which shouldn't contain tabs or newlines.
+    id: String,
+
+    /// The optional letters, in sorted order.
+    optional_letters: String,
+
+    /// The required letter.
+    required_letter: String,
+
+    /// The number of answers.
+    answer_count: usize,
+
+    /// The maximum possible score.
+    max_score: usize,
+}
+
+#[wasm_bindgen]
+impl PuzzleDef {
+    /// Returns the id.
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Returns the optional letters, in sorted order.
+    pub fn optional_letters(&self) -> String {
+        self.optional_letters.clone()
+    }
+
+    /// Returns the required letter.
+    pub fn required_letter(&self) -> String {
+        self.required_letter.clone()
+    }
+
+    /// Returns the number of answers.
+    pub fn answer_count(&self) -> usize {
+        self.answer_count
+    }
+
+    /// Returns the maximum possible score.
+    pub fn max_score(&self) -> usize {
+        self.max_score
+    }
+
+    /// Encodes the definition as a single line of tab-separated fields: the id, optional letters,
+    /// required letter, answer count, and maximum score.
+    pub fn encode(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}", self.id, self.optional_letters, self.required_letter,
+                self.answer_count, self.max_score)
+    }
+
+    /// Decodes a definition written by `encode()`, returning `None` if it isn't valid.
+    pub fn decode(text: &str) -> Option<PuzzleDef> {
+        let fields: Vec<&str> = text.trim_end_matches('\n').split('\t').collect();
+        match fields.as_slice() {
+            [id, optional_letters, required_letter, answer_count, max_score] => Some(PuzzleDef {
+                id: id.to_string(),
+                optional_letters: optional_letters.to_string(),
+                required_letter: required_letter.to_string(),
+                answer_count: answer_count.parse().ok()?,
+                max_score: max_score.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl PuzzleDef {
+    /// Creates a definition from its parts.
+    pub fn new(id: &str, optional_letters: &str, required_letter: &str, answer_count: usize,
+               max_score: usize) -> PuzzleDef
+    {
+        PuzzleDef {
+            id: id.to_string(),
+            optional_letters: optional_letters.to_string(),
+            required_letter: required_letter.to_string(),
+            answer_count,
+            max_score,
+        }
+    }
+
+    /// Returns the default id for a puzzle: the required letter, a dash, and the optional
+    /// letters, like `i-cglorw`.
+    pub fn default_id(optional_letters: &str, required_letter: &str) -> String {
+        format!("{}-{}", required_letter, optional_letters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let def = PuzzleDef::new(&PuzzleDef::default_id("cglorw", "i"), "cglorw", "i", 5, 22);
+        assert_eq!(def.encode(), "i-cglorw\tcglorw\ti\t5\t22");
+        assert_eq!(PuzzleDef::decode(&def.encode()), Some(def));
+        assert_eq!(PuzzleDef::decode("i-cglorw\tcglorw\ti\t5"), None);
+    }
+}
@@ -0,0 +1,112 @@
+//! This module provides `Rank`, the title a player earns for reaching a share of a puzzle's maximum
+//! score, using the same thresholds as the official game.
+
+use wasm_bindgen::prelude::*;
+
+/// A player's rank, from lowest to highest. Ranks compare in that order, so frontends can check
+/// progress with `<` and `>` instead of matching names.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    /// Any score.
+    Beginner,
+    /// 2% of the maximum score.
+    GoodStart,
+    /// 5% of the maximum score.
+    MovingUp,
+    /// 8% of the maximum score.
+    Good,
+    /// 15% of the maximum score.
+    Solid,
+    /// 25% of the maximum score.
+    Nice,
+    /// 40% of the maximum score.
+    Great,
+    /// 50% of the maximum score.
+    Amazing,
+    /// 70% of the maximum score.
+    Genius,
+    /// The maximum score.
+    QueenBee,
+}
+
+impl Rank {
+    /// Every rank, from lowest to highest.
+    pub const ALL: [Rank; 10] = [
+        Rank::Beginner, Rank::GoodStart, Rank::MovingUp, Rank::Good, Rank::Solid, Rank::Nice,
+        Rank::Great, Rank::Amazing, Rank::Genius, Rank::QueenBee,
+    ];
+
+    /// Returns the percentage of the maximum score needed to reach the rank.
+    pub fn percent(self) -> usize {
+        match self {
+            Rank::Beginner => 0,
+            Rank::GoodStart => 2,
+            Rank::MovingUp => 5,
+            Rank::Good => 8,
+            Rank::Solid => 15,
+            Rank::Nice => 25,
+            Rank::Great => 40,
+            Rank::Amazing => 50,
+            Rank::Genius => 70,
+            Rank::QueenBee => 100,
+        }
+    }
+
+    /// Returns the score needed to reach the rank in a puzzle with the given maximum score,
+    /// rounded to the nearest point.
+    pub fn threshold(self, max_score: usize) -> usize {
+        (self.percent() * max_score + 50) / 100
+    }
+
+    /// Returns the rank for a score in a puzzle with the given maximum score. Puzzles with no
+    /// points to score leave every player a beginner.
+    pub fn for_score(score: usize, max_score: usize) -> Rank {
+        if max_score == 0 {
+            return Rank::Beginner;
+        }
+
+        Rank::ALL.iter()
+            .rev()
+            .copied()
+            .find(|rank| score >= rank.threshold(max_score))
+            .unwrap_or(Rank::Beginner)
+    }
+
+    /// Returns the next rank up, or `None` for `QueenBee`.
+    pub fn next(self) -> Option<Rank> {
+        Rank::ALL.iter().copied().find(|&rank| rank > self)
+    }
+
+    /// Returns the rank's name in English.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Beginner => "Beginner",
+            Rank::GoodStart => "Good Start",
+            Rank::MovingUp => "Moving Up",
+            Rank::Good => "Good",
+            Rank::Solid => "Solid",
+            Rank::Nice => "Nice",
+            Rank::Great => "Great",
+            Rank::Amazing => "Amazing",
+            Rank::Genius => "Genius",
+            Rank::QueenBee => "Queen Bee",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_score() {
+        assert_eq!(Rank::Genius.threshold(143), 100);
+        assert_eq!(Rank::for_score(99, 143), Rank::Amazing);
+        assert_eq!(Rank::for_score(100, 143), Rank::Genius);
+        assert_eq!(Rank::for_score(143, 143), Rank::QueenBee);
+        assert_eq!(Rank::for_score(0, 0), Rank::Beginner);
+        assert!(Rank::Solid < Rank::Nice);
+        assert_eq!(Rank::Genius.next(), Some(Rank::QueenBee));
+    }
+}
@@ -0,0 +1,210 @@
+//! This module provides `Report`, the post-game summary of a `SpellingBeeGame`: which words were
+//! found and missed and how the score compares to the maximum, along with a Markdown renderer for
+//! pasting it into forums or journals and a spoiler-free text summary for sharing in group chats.
+
+use std::collections::BTreeMap;
+use crate::i18n::{Messages, FALLBACK_LOCALE};
+use crate::rank::Rank;
+use crate::utils::letter_count;
+
+/// The square for a found answer in a share grid.
+const FOUND_SQUARE: &str = "🟡";
+
+/// The square for a missed answer in a share grid.
+const MISSED_SQUARE: &str = "⬜";
+
+/// A single answer in a report.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReportWord {
+    /// The word.
+    pub word: String,
+
+    /// The points the word is worth.
+    pub points: usize,
+
+    /// Whether the word is a pangram.
+    pub pangram: bool,
+}
+
+/// The summary of a game, from `SpellingBeeGame::final_report()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Report {
+    /// The optional letters, in sorted order.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: String,
+
+    /// The score.
+    pub score: usize,
+
+    /// The maximum possible score.
+    pub max_score: usize,
+
+    /// The longest streak of valid plays.
+    pub best_streak: usize,
+
+    /// The answers that were found, in alphabetical order.
+    pub found: Vec<ReportWord>,
+
+    /// The answers that weren't found, in alphabetical order.
+    pub missed: Vec<ReportWord>,
+}
+
+impl Report {
+    /// Returns the number of pangrams found.
+    pub fn pangrams_found(&self) -> usize {
+        self.found.iter().filter(|w| w.pangram).count()
+    }
+
+    /// Returns the total number of pangrams.
+    pub fn pangram_count(&self) -> usize {
+        self.pangrams_found() + self.missed.iter().filter(|w| w.pangram).count()
+    }
+
+    /// Returns the rank the score reached.
+    pub fn rank(&self) -> Rank {
+        Rank::for_score(self.score, self.max_score)
+    }
+
+    /// Renders a summary for sharing in English, without any words, so it doesn't spoil the
+    /// puzzle: the letters, the rank, and the points, words, and pangrams found out of the
+    /// totals. With `grid`, a row follows for each answer length, with a square for each answer
+    /// of that length, yellow if it was found.
+    pub fn to_share_text(&self, grid: bool) -> String {
+        self.to_share_text_in(&Messages::new(FALLBACK_LOCALE), grid)
+    }
+
+    /// Renders a summary for sharing like `to_share_text()`, using the given messages.
+    pub fn to_share_text_in(&self, messages: &Messages, grid: bool) -> String {
+        let m = |key: &str| messages.get(key);
+        let mut text = format!("{} {} {}\n{}\n", m("report.title"),
+                               self.required_letter.to_uppercase(),
+                               self.optional_letters.to_uppercase(), messages.rank(self.rank()));
+        text.push_str(&format!("{}: {}/{}\n", m("report.points"), self.score, self.max_score));
+        text.push_str(&format!("{}: {}/{}\n", m("report.words"), self.found.len(),
+                               self.found.len() + self.missed.len()));
+        text.push_str(&format!("{}: {}/{}\n", m("report.pangrams"), self.pangrams_found(),
+                               self.pangram_count()));
+        if !grid {
+            return text;
+        }
+
+        // The found and missed answers of each length.
+        let mut lengths: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for w in &self.found {
+            lengths.entry(letter_count(&w.word)).or_default().0 += 1;
+        }
+        for w in &self.missed {
+            lengths.entry(letter_count(&w.word)).or_default().1 += 1;
+        }
+        text.push('\n');
+        for (length, (found, missed)) in lengths {
+            text.push_str(&format!("{} {}{}\n", length, FOUND_SQUARE.repeat(found),
+                                   MISSED_SQUARE.repeat(missed)));
+        }
+        text
+    }
+
+    /// Renders the report as Markdown in English: a stats table, then tables of the found and
+    /// missed words with pangrams in bold.
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_in(&Messages::new(FALLBACK_LOCALE))
+    }
+
+    /// Renders the report as Markdown like `to_markdown()`, using the given messages.
+    pub fn to_markdown_in(&self, messages: &Messages) -> String {
+        let m = |key: &str| messages.get(key);
+        let mut md = format!("# {}: **{}** {}\n\n", m("report.title"),
+                             self.required_letter.to_uppercase(),
+                             self.optional_letters.to_uppercase());
+
+        md.push_str(&format!("| | {} | {} |\n|---|--:|--:|\n", m("report.found"),
+                             m("report.total")));
+        md.push_str(&format!("| {} | {} | {} |\n", m("report.words"), self.found.len(),
+                             self.found.len() + self.missed.len()));
+        md.push_str(&format!("| {} | {} | {} |\n", m("report.points"), self.score,
+                             self.max_score));
+        md.push_str(&format!("| {} | {} | {} |\n", m("report.pangrams"), self.pangrams_found(),
+                             self.pangram_count()));
+        md.push_str(&format!("\n{}: {}\n", m("report.best_streak"), self.best_streak));
+
+        md.push_str(&format!("\n## {}\n\n", m("report.found_words")));
+        md.push_str(&word_table(&self.found, messages));
+        md.push_str(&format!("\n## {}\n\n", m("report.missed_words")));
+        md.push_str(&word_table(&self.missed, messages));
+        md
+    }
+}
+
+/// Renders a Markdown table of words and their points, with pangrams in bold.
+fn word_table(words: &[ReportWord], messages: &Messages) -> String {
+    if words.is_empty() {
+        return format!("_{}_\n", messages.get("report.none"));
+    }
+
+    let mut table = format!("| {} | {} |\n|---|--:|\n", messages.get("report.word"),
+                            messages.get("report.points"));
+    for w in words {
+        if w.pangram {
+            table.push_str(&format!("| **{}** | {} |\n", w.word, w.points));
+        } else {
+            table.push_str(&format!("| {} | {} |\n", w.word, w.points));
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let word = |word: &str, points, pangram| ReportWord {
+            word: word.to_string(),
+            points,
+            pangram,
+        };
+        let report = Report {
+            optional_letters: "cglorw".to_string(),
+            required_letter: "i".to_string(),
+            score: 15,
+            max_score: 20,
+            best_streak: 2,
+            found: vec![word("cowgirl", 14, true), word("will", 1, false)],
+            missed: vec![word("logic", 5, false)],
+        };
+
+        assert_eq!(report.pangram_count(), 1);
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Spelling Bee: **I** CGLORW\n"));
+        assert!(md.contains("| Words | 2 | 3 |\n"));
+        assert!(md.contains("| **cowgirl** | 14 |\n"));
+        assert!(md.ends_with("## Missed words\n\n| Word | Points |\n|---|--:|\n| logic | 5 |\n"));
+
+        let md = report.to_markdown_in(&Messages::new("de-AT"));
+        assert!(md.contains("| Wörter | 2 | 3 |\n"));
+
+        assert_eq!(report.to_share_text(false),
+                   "Spelling Bee I CGLORW\nGenius\nPoints: 15/20\nWords: 2/3\nPangrams: 1/1\n");
+        assert!(report.to_share_text(true).ends_with("\n\n4 🟡\n5 ⬜\n7 🟡\n"));
+        assert!(report.to_share_text_in(&Messages::new("fr"), false)
+                .starts_with("Spelling Bee I CGLORW\nGénie\nPoints: 15/20\n"));
+    }
+
+    #[test]
+    fn test_best_streak() {
+        let report = Report {
+            optional_letters: "cglorw".to_string(),
+            required_letter: "i".to_string(),
+            score: 1,
+            max_score: 20,
+            best_streak: 3,
+            found: vec![],
+            missed: vec![],
+        };
+        assert!(report.to_markdown().contains("\nBest streak: 3\n"));
+        assert!(report.to_markdown_in(&Messages::new("de")).contains("\nBeste Serie: 3\n"));
+    }
+}
@@ -0,0 +1,228 @@
+//! This module provides `Royale`, an elimination mode where many players race through the same
+//! puzzle in timed rounds and the lowest scorers are knocked out at the end of each round.
+//!
+//! `Royale` only tracks state: it doesn't keep time itself, so callers pass in the current time
+//! (in milliseconds, as from `Date.now()`) whenever something happens, and rounds end once a play
+//! or a call to `advance()` arrives after the deadline.
+
+use std::collections::BTreeMap;
+use crate::spellingbee::{SpellingBeeGame, PlayResult};
+
+/// One player's progress in a royale.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Player {
+    /// The player's own copy of the puzzle.
+    game: SpellingBeeGame,
+
+    /// The time of the player's last valid play, used to break ties: reaching a score first is
+    /// better.
+    last_scored_at: Option<u64>,
+
+    /// The round in which the player was knocked out, if they have been.
+    eliminated_in: Option<usize>,
+}
+
+/// The outcome of trying to play a word in a royale.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RoyalePlay {
+    /// The word was played, with the given result.
+    Played(PlayResult),
+
+    /// The player has been eliminated and can't play.
+    Eliminated,
+
+    /// The royale is over.
+    Finished,
+
+    /// There's no player with the given id.
+    UnknownPlayer,
+}
+
+/// A player's position in the standings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Standing {
+    /// The player's id.
+    pub player: String,
+
+    /// The player's score.
+    pub score: usize,
+
+    /// The round in which the player was knocked out, or `None` if they're still in.
+    pub eliminated_in: Option<usize>,
+}
+
+/// A game of Bee Royale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Royale {
+    /// Every player, by id.
+    players: BTreeMap<String, Player>,
+
+    /// The length of each round, in milliseconds.
+    round_length: u64,
+
+    /// The number of players knocked out at the end of each round.
+    eliminations_per_round: usize,
+
+    /// The current round, starting from 1.
+    round: usize,
+
+    /// When the current round ends.
+    round_ends_at: u64,
+}
+
+impl Royale {
+    /// Starts a royale at time `now` in which every given player plays their own copy of `game`,
+    /// with its clock started at `now`. Each round lasts `round_length` milliseconds, after which
+    /// the `eliminations_per_round` lowest scorers are knocked out (always leaving at least one
+    /// player).
+    pub fn new<I: IntoIterator<Item = String>>(game: &SpellingBeeGame, players: I,
+                                               round_length: u64,
+                                               eliminations_per_round: usize,
+                                               now: u64) -> Royale
+    {
+        let mut game = game.clone();
+        game.start_clock(now as f64);
+        let players = players.into_iter().map(|id| {
+            (id, Player { game: game.clone(), last_scored_at: None, eliminated_in: None })
+        }).collect();
+
+        Royale {
+            players,
+            round_length,
+            eliminations_per_round: eliminations_per_round.max(1),
+            round: 1,
+            round_ends_at: now.saturating_add(round_length),
+        }
+    }
+
+    /// Returns the current round, starting from 1.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Returns the time when the current round ends.
+    pub fn round_ends_at(&self) -> u64 {
+        self.round_ends_at
+    }
+
+    /// Returns `true` once only one player is left.
+    pub fn is_finished(&self) -> bool {
+        self.remaining() <= 1
+    }
+
+    /// Returns the number of players who haven't been knocked out.
+    pub fn remaining(&self) -> usize {
+        self.players.values().filter(|p| p.eliminated_in.is_none()).count()
+    }
+
+    /// Returns the given player's copy of the puzzle, timed by the royale's clock, or `None` if
+    /// there's no player with that id.
+    pub fn game(&self, player: &str) -> Option<&SpellingBeeGame> {
+        self.players.get(player).map(|p| &p.game)
+    }
+
+    /// Plays a word for the given player at time `now`, first ending any rounds whose deadlines
+    /// have passed.
+    pub fn play(&mut self, player: &str, word: &str, now: u64) -> RoyalePlay {
+        self.advance(now);
+        if self.is_finished() {
+            return RoyalePlay::Finished;
+        }
+
+        match self.players.get_mut(player) {
+            None => RoyalePlay::UnknownPlayer,
+            Some(p) if p.eliminated_in.is_some() => RoyalePlay::Eliminated,
+            Some(p) => {
+                let result = p.game.play_at(word, now as f64);
+                if result == PlayResult::Valid {
+                    p.last_scored_at = Some(now);
+                }
+                RoyalePlay::Played(result)
+            }
+        }
+    }
+
+    /// Ends every round whose deadline is at or before `now`, returning the ids of the players
+    /// knocked out, in the order they were eliminated.
+    pub fn advance(&mut self, now: u64) -> Vec<String> {
+        let mut eliminated = Vec::new();
+        while !self.is_finished() && now >= self.round_ends_at {
+            eliminated.extend(self.end_round());
+        }
+        eliminated
+    }
+
+    /// Knocks out the lowest scorers of the current round and starts the next one.
+    fn end_round(&mut self) -> Vec<String> {
+        let standings = self.standings();
+        let cut = self.eliminations_per_round.min(self.remaining().saturating_sub(1));
+        let out: Vec<String> = standings.into_iter()
+            .filter(|s| s.eliminated_in.is_none())
+            .rev()
+            .take(cut)
+            .map(|s| s.player)
+            .collect();
+
+        for id in &out {
+            if let Some(p) = self.players.get_mut(id) {
+                p.eliminated_in = Some(self.round);
+            }
+        }
+
+        self.round += 1;
+        self.round_ends_at = self.round_ends_at.saturating_add(self.round_length);
+        out
+    }
+
+    /// Returns the standings, best first: players still in by score, then eliminated players by
+    /// how long they lasted. Ties in score go to whoever reached it earlier.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut players: Vec<(&String, &Player)> = self.players.iter().collect();
+        players.sort_by_key(|(id, p)| {
+            (std::cmp::Reverse(p.eliminated_in.unwrap_or(usize::MAX)),
+             std::cmp::Reverse(p.game.score()),
+             p.last_scored_at.unwrap_or(u64::MAX),
+             *id)
+        });
+
+        players.into_iter().map(|(id, p)| Standing {
+            player: id.clone(),
+            score: p.game.score(),
+            eliminated_in: p.eliminated_in,
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_royale() {
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let players = ["ana", "ben", "cy"].iter().map(|p| p.to_string());
+        let mut royale = Royale::new(&game, players, 1_000, 1, 50);
+        assert_eq!(royale.play("ana", "cowgirl", 100), RoyalePlay::Played(PlayResult::Valid));
+        assert_eq!(royale.play("ben", "will", 200), RoyalePlay::Played(PlayResult::Valid));
+        assert_eq!(royale.play("cy", "coil", 300), RoyalePlay::Played(PlayResult::Valid));
+        assert_eq!(royale.play("dee", "coil", 400), RoyalePlay::UnknownPlayer);
+        let cy = royale.game("cy").unwrap();
+        assert_eq!((cy.started_at(), cy.play_times()), (Some(50), &[250][..]));
+        assert!(royale.game("dee").is_none());
+
+        // Ben and Cy tie, but Ben scored first.
+        assert_eq!(royale.advance(1_050), ["cy"]);
+        assert_eq!((royale.round(), royale.round_ends_at(), royale.remaining()), (2, 2_050, 2));
+        assert_eq!(royale.play("cy", "will", 1_100), RoyalePlay::Eliminated);
+        assert_eq!(royale.play("ben", "will", 1_200),
+                   RoyalePlay::Played(PlayResult::AlreadyPlayed));
+
+        assert_eq!(royale.play("ben", "coil", 2_500), RoyalePlay::Finished);
+        assert!(royale.is_finished());
+        let standings = royale.standings();
+        let standings: Vec<(&str, usize, Option<usize>)> = standings.iter()
+            .map(|s| (s.player.as_str(), s.score, s.eliminated_in))
+            .collect();
+        assert_eq!(standings, [("ana", 14, None), ("ben", 1, Some(2)), ("cy", 1, Some(1))]);
+    }
+}
@@ -0,0 +1,229 @@
+//! This module provides the compact binary format for saved games, from
+//! `SpellingBeeGame::save_bytes()`. A save only has the puzzle and the progress made on it, not the
+//! answers, so it stays small however big the dictionary is; the answers are rebuilt from the word
+//! lists when the save is loaded.
+//!
+//! A save starts with a version byte, currently `SAVE_VERSION`. Then come the locale, the optional
+//! letters, the required letter, and the hive layout (empty if there isn't one) as strings, the
+//! score, the streak, and the best streak as numbers, the time the clock started plus one (zero if
+//! it hasn't), and the number of words found followed by the words in the order they were played,
+//! each followed by the milliseconds from the start it was found at, and then the number of hints
+//! bought followed by each one's kind (its place in `HintKind::ALL`), answer, and cost, and last
+//! the number of words found when the answers were revealed plus one (zero if they haven't been)
+//! and whether the game is finished (one if it is, zero if not). Numbers are unsigned LEB128
+//! varints, and strings are their length in bytes as a number followed by their UTF-8.
+//!
+//! Older versions are the same with less at the end. Version 1 saves, from before games kept
+//! time, don't have the start or anything after it, and load as games whose clock hasn't started,
+//! version 2 saves, from before hints could be bought, end with the words, and version 3 saves,
+//! from before answers were saved as revealed, end with the hints.
+
+use std::convert::TryFrom;
+use crate::hints::{HintKind, HintPurchase};
+
+/// The version of the save format written by `encode()`.
+pub const SAVE_VERSION: u8 = 4;
+
+/// The contents of a save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SaveState {
+    /// The locale the game lowercases with.
+    pub locale: String,
+
+    /// The optional letters, in sorted order.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: String,
+
+    /// The optional letters in order around the hive, or an empty string if words don't have to
+    /// trace a path.
+    pub layout: String,
+
+    /// The score.
+    pub score: usize,
+
+    /// The current streak of valid plays.
+    pub streak: usize,
+
+    /// The longest streak of valid plays.
+    pub best_streak: usize,
+
+    /// The time the clock started, in milliseconds since the Unix epoch, if it has.
+    pub started_at: Option<u64>,
+
+    /// The words found, in the order they were played.
+    pub played: Vec<String>,
+
+    /// The milliseconds from the start each word was found at, in the same order.
+    pub play_times: Vec<u64>,
+
+    /// The hints bought, in the order they were bought.
+    pub hints: Vec<HintPurchase>,
+
+    /// The number of words that had been found when the answers were revealed, if they have been.
+    pub revealed_after: Option<usize>,
+
+    /// Whether the game has been finished, so that no more words can be played.
+    pub finished: bool,
+}
+
+impl SaveState {
+    /// Encodes the save in the format described in the module documentation.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![SAVE_VERSION];
+        for text in &[&self.locale, &self.optional_letters, &self.required_letter, &self.layout] {
+            write_str(&mut bytes, text);
+        }
+        for &n in &[self.score, self.streak, self.best_streak] {
+            write_number(&mut bytes, n);
+        }
+        write_u64(&mut bytes, self.started_at.map_or(0, |t| t + 1));
+        write_number(&mut bytes, self.played.len());
+        for (word, &time) in self.played.iter().zip(&self.play_times) {
+            write_str(&mut bytes, word);
+            write_u64(&mut bytes, time);
+        }
+        write_number(&mut bytes, self.hints.len());
+        for hint in &self.hints {
+            let kind = HintKind::ALL.iter().position(|&k| k == hint.kind).expect("every kind");
+            write_number(&mut bytes, kind);
+            write_str(&mut bytes, &hint.word);
+            write_number(&mut bytes, hint.cost);
+        }
+        write_number(&mut bytes, self.revealed_after.map_or(0, |n| n + 1));
+        write_number(&mut bytes, usize::from(self.finished));
+        bytes
+    }
+
+    /// Decodes a save written by `encode()`. Returns `None` if the bytes aren't a save of a
+    /// version this build understands.
+    pub fn decode(bytes: &[u8]) -> Option<SaveState> {
+        let (&version, mut rest) = bytes.split_first()?;
+        if version == 0 || version > SAVE_VERSION {
+            return None;
+        }
+
+        let rest = &mut rest;
+        let mut save = SaveState {
+            locale: read_str(rest)?,
+            optional_letters: read_str(rest)?,
+            required_letter: read_str(rest)?,
+            layout: read_str(rest)?,
+            score: read_number(rest)?,
+            streak: read_number(rest)?,
+            best_streak: read_number(rest)?,
+            started_at: None,
+            played: Vec::new(),
+            play_times: Vec::new(),
+            hints: Vec::new(),
+            revealed_after: None,
+            finished: false,
+        };
+        if version > 1 {
+            save.started_at = read_u64(rest)?.checked_sub(1);
+        }
+        let count = read_number(rest)?;
+        for _ in 0..count {
+            save.played.push(read_str(rest)?);
+            save.play_times.push(if version > 1 { read_u64(rest)? } else { 0 });
+        }
+        let count = if version > 2 { read_number(rest)? } else { 0 };
+        for _ in 0..count {
+            save.hints.push(HintPurchase {
+                kind: *HintKind::ALL.get(read_number(rest)?)?,
+                word: read_str(rest)?,
+                cost: read_number(rest)?,
+            });
+        }
+        if version > 3 {
+            save.revealed_after = read_number(rest)?.checked_sub(1);
+            save.finished = match read_number(rest)? {
+                0 => false,
+                1 => true,
+                _ => return None,
+            };
+        }
+
+        if rest.is_empty() { Some(save) } else { None }
+    }
+}
+
+/// Appends a number as an unsigned LEB128 varint.
+pub(crate) fn write_number(bytes: &mut Vec<u8>, n: usize) {
+    write_u64(bytes, n as u64);
+}
+
+/// Appends a number that may not fit in a `usize`, like a time, as an unsigned LEB128 varint.
+fn write_u64(bytes: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        bytes.push((n & 0x7f) as u8 | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+/// Appends a string as its length followed by its UTF-8.
+pub(crate) fn write_str(bytes: &mut Vec<u8>, text: &str) {
+    write_number(bytes, text.len());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+/// Reads an unsigned LEB128 varint from the front of the bytes, advancing past it.
+pub(crate) fn read_number(bytes: &mut &[u8]) -> Option<usize> {
+    usize::try_from(read_u64(bytes)?).ok()
+}
+
+/// Reads a number written by `write_u64()` from the front of the bytes, advancing past it.
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+    let mut n: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        n |= u64::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(n);
+        }
+        shift += 7;
+    }
+}
+
+/// Reads a string written by `write_str()` from the front of the bytes, advancing past it.
+pub(crate) fn read_str(bytes: &mut &[u8]) -> Option<String> {
+    let len = read_number(bytes)?;
+    if len > bytes.len() {
+        return None;
+    }
+    let (text, rest) = bytes.split_at(len);
+    *bytes = rest;
+    String::from_utf8(text.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::LexiconHandle;
+    use crate::spellingbee::SpellingBeeGame;
+
+    #[test]
+    fn test_save() {
+        let words = "will\ncowgirl\ncoil\nroll\n";
+        let mut game = SpellingBeeGame::new("cglorw", "i", words, "");
+        game.play("cowgirl");
+        game.play("will");
+        game.play("wilk");
+        let bytes = game.save_bytes();
+        // The time the clock started takes up six bytes of it.
+        assert!(bytes.len() < 48);
+        assert_eq!(SpellingBeeGame::load_bytes(&bytes, words, ""), Some(game.clone()));
+
+        assert_eq!(SpellingBeeGame::load_bytes(&bytes, "will\ncoil\n", ""), None);
+        assert_eq!(SpellingBeeGame::load_bytes(&bytes[..bytes.len() - 1], words, ""), None);
+        assert_eq!(SpellingBeeGame::load_bytes(&[], words, ""), None);
+
+        let lexicon = LexiconHandle::new(words, "");
+        assert_eq!(SpellingBeeGame::load_bytes_from_lexicon(&bytes, &lexicon), Some(game));
+        let turkish = LexiconHandle::new_in("tr", words, "");
+        assert_eq!(SpellingBeeGame::load_bytes_from_lexicon(&bytes, &turkish), None);
+    }
+}
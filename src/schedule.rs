@@ -0,0 +1,107 @@
+//! This module works out when the next daily puzzle comes out, so that every frontend shows the
+//! same countdown. Times are in milliseconds since the Unix epoch, like JavaScript's `Date.now()`,
+//! and time zones are fixed offsets from UTC.
+//!
+//! Daily puzzles are identified by the date they come out, as `YYYY-MM-DD`, in the time zone they
+//! come out in.
+
+use wasm_bindgen::prelude::*;
+
+/// The number of milliseconds in a minute.
+const MINUTE: i64 = 60 * 1000;
+
+/// The number of milliseconds in a day.
+const DAY: i64 = 24 * 60 * MINUTE;
+
+/// When the next daily puzzle comes out, from `next_puzzle_at()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NextPuzzle {
+    /// The time the puzzle comes out.
+    at: i64,
+
+    /// The puzzle's id.
+    id: String,
+}
+
+#[wasm_bindgen]
+impl NextPuzzle {
+    /// Returns the time the puzzle comes out, in milliseconds since the Unix epoch.
+    pub fn at(&self) -> f64 {
+        self.at as f64
+    }
+
+    /// Returns the puzzle's id: the date it comes out.
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Returns the number of milliseconds from `now` until the puzzle comes out.
+    pub fn wait(&self, now: f64) -> f64 {
+        (self.at - now as i64).max(0) as f64
+    }
+}
+
+/// Returns when the next daily puzzle comes out after `now`, if puzzles come out at midnight in
+/// the time zone `utc_offset` minutes ahead of UTC (such as -300 for US Eastern Standard Time).
+/// Time zones with daylight saving time should pass the offset in effect at the next midnight.
+#[wasm_bindgen]
+pub fn next_puzzle_at(now: f64, utc_offset: i32) -> NextPuzzle {
+    next_puzzle_at_release(now, utc_offset, 0)
+}
+
+/// Returns when the next daily puzzle comes out like `next_puzzle_at()`, if puzzles come out
+/// `release_minute` minutes after midnight, such as 180 for 3 AM.
+#[wasm_bindgen]
+pub fn next_puzzle_at_release(now: f64, utc_offset: i32, release_minute: u32) -> NextPuzzle {
+    let offset = i64::from(utc_offset) * MINUTE;
+    let release = (i64::from(release_minute) * MINUTE).rem_euclid(DAY);
+    let local_now = now as i64 + offset;
+
+    let mut day = local_now.div_euclid(DAY);
+    if day * DAY + release <= local_now {
+        day += 1;
+    }
+
+    NextPuzzle {
+        at: day * DAY + release - offset,
+        id: civil_date(day),
+    }
+}
+
+/// Returns the date `days` days after 1970-01-01 as `YYYY-MM-DD`, using Howard Hinnant's
+/// `civil_from_days` algorithm.
+pub fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 -
+                       day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_puzzle() {
+        // 2024-03-01 01:30 UTC, which is 2024-02-29 20:30 in UTC-5.
+        let now = 1_709_256_600_000.0;
+        let next = next_puzzle_at(now, -300);
+        assert_eq!(next.id(), "2024-03-01");
+        assert_eq!(next.wait(now), 3.5 * 60.0 * 60.0 * 1000.0);
+
+        let next = next_puzzle_at_release(now, 0, 180);
+        assert_eq!(next.id(), "2024-03-01");
+        assert_eq!(next.wait(now), 1.5 * 60.0 * 60.0 * 1000.0);
+
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(-1), "1969-12-31");
+    }
+}
@@ -0,0 +1,203 @@
+//! This module provides `ScoringRules`, the points each answer is worth and how short an answer
+//! can be, so that groups can play with house rules like two points for five-letter words, a
+//! doubled pangram bonus, or three-letter words for younger players. The default rules are the
+//! official ones: answers have at least four letters, a four-letter word is worth one point, a
+//! longer word one point per letter, and a pangram seven more. Perfect pangrams, which use each
+//! letter exactly once, can be worth an extra bonus on top, which is zero in the official rules.
+//! So can rare words: with a rarity bonus, answers that occur fewer times than some count in the
+//! game's frequency data (see `SpellingBeeGame::load_frequencies()`) earn extra points.
+//!
+//! The maximum score, and so the rank thresholds, come from the same rules as the score itself.
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::spellingbee::{MIN_LENGTH, PANGRAM_BONUS};
+
+/// The points answers are worth and the shortest answers allowed, passed to
+/// `SpellingBeeGame::new_with_scoring()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScoringRules {
+    /// The fewest letters an answer can have.
+    min_length: usize,
+
+    /// The points for a word of the minimum length.
+    minimum_length_points: usize,
+
+    /// The points for each letter of a longer word.
+    points_per_letter: usize,
+
+    /// The extra points for a pangram.
+    pangram_bonus: usize,
+
+    /// The extra points for a perfect pangram, on top of `pangram_bonus`.
+    perfect_pangram_bonus: usize,
+
+    /// The points for words of particular lengths, instead of the usual ones.
+    length_points: BTreeMap<usize, usize>,
+
+    /// The extra points for a rare word.
+    rarity_bonus: usize,
+
+    /// The frequency count below which a word is rare.
+    rare_frequency: u32,
+}
+
+impl Default for ScoringRules {
+    fn default() -> ScoringRules {
+        ScoringRules {
+            min_length: MIN_LENGTH,
+            minimum_length_points: 1,
+            points_per_letter: 1,
+            pangram_bonus: PANGRAM_BONUS,
+            perfect_pangram_bonus: 0,
+            length_points: BTreeMap::new(),
+            rarity_bonus: 0,
+            rare_frequency: 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ScoringRules {
+    /// Creates the official rules, to be changed with the setters.
+    pub fn new() -> ScoringRules {
+        ScoringRules::default()
+    }
+
+    /// Returns the fewest letters an answer can have.
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Sets the fewest letters an answer can have. Lexicons rarely list words shorter than
+    /// three letters, so lower minimums add few answers.
+    pub fn set_min_length(&mut self, length: usize) {
+        self.min_length = length;
+    }
+
+    /// Returns the points for a word of the minimum length.
+    pub fn minimum_length_points(&self) -> usize {
+        self.minimum_length_points
+    }
+
+    /// Sets the points for a word of the minimum length.
+    pub fn set_minimum_length_points(&mut self, points: usize) {
+        self.minimum_length_points = points;
+    }
+
+    /// Returns the points for each letter of a word longer than the minimum.
+    pub fn points_per_letter(&self) -> usize {
+        self.points_per_letter
+    }
+
+    /// Sets the points for each letter of a word longer than the minimum.
+    pub fn set_points_per_letter(&mut self, points: usize) {
+        self.points_per_letter = points;
+    }
+
+    /// Returns the extra points for a pangram.
+    pub fn pangram_bonus(&self) -> usize {
+        self.pangram_bonus
+    }
+
+    /// Sets the extra points for a pangram.
+    pub fn set_pangram_bonus(&mut self, points: usize) {
+        self.pangram_bonus = points;
+    }
+
+    /// Returns the extra points for a perfect pangram, on top of the pangram bonus.
+    pub fn perfect_pangram_bonus(&self) -> usize {
+        self.perfect_pangram_bonus
+    }
+
+    /// Sets the extra points for a perfect pangram, on top of the pangram bonus.
+    pub fn set_perfect_pangram_bonus(&mut self, points: usize) {
+        self.perfect_pangram_bonus = points;
+    }
+
+    /// Returns the extra points for a rare word.
+    pub fn rarity_bonus(&self) -> usize {
+        self.rarity_bonus
+    }
+
+    /// Sets the extra points for a rare word, one whose frequency count is below the rare
+    /// frequency. Words without frequency data never count as rare.
+    pub fn set_rarity_bonus(&mut self, points: usize) {
+        self.rarity_bonus = points;
+    }
+
+    /// Returns the frequency count below which a word is rare.
+    pub fn rare_frequency(&self) -> u32 {
+        self.rare_frequency
+    }
+
+    /// Sets the frequency count below which a word is rare.
+    pub fn set_rare_frequency(&mut self, count: u32) {
+        self.rare_frequency = count;
+    }
+
+    /// Makes words of the given length worth the given points, before any pangram bonus, instead
+    /// of the usual points for their length.
+    pub fn set_length_points(&mut self, length: usize, points: usize) {
+        self.length_points.insert(length, points);
+    }
+
+    /// Returns the points for a word with the given number of letters, including the bonus if
+    /// it's a pangram but not the perfect pangram bonus, which `SpellingBeeGame` adds for perfect
+    /// pangrams. Words shorter than the minimum length are worth nothing.
+    pub fn points(&self, length: usize, pangram: bool) -> usize {
+        if length < self.min_length {
+            return 0;
+        }
+
+        let base = match self.length_points.get(&length) {
+            Some(&points) => points,
+            None if length == self.min_length => self.minimum_length_points,
+            None => length * self.points_per_letter,
+        };
+        if pangram { base + self.pangram_bonus } else { base }
+    }
+}
+
+impl ScoringRules {
+    /// Returns the extra points for a word with the given frequency count, if it has one: the
+    /// rarity bonus if the count is below the rare frequency, and otherwise nothing.
+    pub fn rarity_points(&self, frequency: Option<u64>) -> usize {
+        match frequency {
+            Some(count) if count < u64::from(self.rare_frequency) => self.rarity_bonus,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring_rules() {
+        let official = ScoringRules::new();
+        assert_eq!((official.points(3, false), official.points(4, false)), (0, 1));
+        assert_eq!((official.points(5, false), official.points(7, true)), (5, 14));
+
+        let mut house = ScoringRules::new();
+        house.set_length_points(5, 2);
+        house.set_pangram_bonus(2 * PANGRAM_BONUS);
+        assert_eq!((house.points(5, false), house.points(6, false)), (2, 6));
+        assert_eq!(house.points(7, true), 21);
+
+        let mut kids = ScoringRules::new();
+        kids.set_min_length(3);
+        assert_eq!((kids.points(3, false), kids.points(4, false)), (1, 4));
+
+        let mut rare = ScoringRules::new();
+        rare.set_rarity_bonus(3);
+        rare.set_rare_frequency(100);
+        assert_eq!((rare.rarity_points(Some(99)), rare.rarity_points(Some(100))), (3, 0));
+        assert_eq!(rare.rarity_points(None), 0);
+    }
+}
@@ -0,0 +1,514 @@
+//! This module provides `PuzzleSearch`, a beam search over letter sets that looks for
+//! "interesting" puzzles according to a configurable `Interest` function.
+//!
+//! Every puzzle needs at least one pangram, so the search only ever visits letter sets that are
+//! the letters of some seven-letter-set word. From a handful of random starting sets, it
+//! repeatedly tries moving the required letter and swapping single optional letters, keeping the
+//! most interesting puzzles it has seen.
+//!
+//! Puzzles that don't meet the constraints in `SearchConfig` are never returned, but they stay in
+//! the beam with their interest lowered by how far they are from meeting them, so the search can
+//! pass through them to puzzles that do.
+
+use std::collections::{BTreeMap, HashSet};
+use lexi::{VecLexicon, wordlist};
+use crate::scoring::ScoringRules;
+use crate::spellingbee::GameError;
+use crate::utils::{letter_bit, letter_count, letter_mask, Rng};
+
+/// The number of distinct letters in a puzzle.
+const PUZZLE_LETTERS: u32 = 7;
+
+/// The length an answer needs to count as long for `SearchConfig::require_long_non_pangram`.
+pub const LONG_WORD_LENGTH: usize = 7;
+
+/// The mask with every letter from 'a' to 'z' set.
+const ALL_LETTERS: u32 = (1 << 26) - 1;
+
+/// How much a puzzle's interest is lowered in the beam for each answer it has fewer than
+/// `SearchConfig::min_answers` or more than `SearchConfig::max_answers`.
+const ANSWER_COUNT_PENALTY: f64 = 0.1;
+
+/// How much a puzzle's interest is lowered in the beam for not having a long answer that isn't a
+/// pangram, when `SearchConfig::require_long_non_pangram` asks for one.
+const LONG_NON_PANGRAM_PENALTY: f64 = 1.0;
+
+/// A puzzle found by the search, along with its answers and how interesting it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The six optional letters, in sorted order.
+    pub optional_letters: String,
+
+    /// The required letter.
+    pub required_letter: char,
+
+    /// The valid answers, in alphabetical order.
+    pub answers: Vec<String>,
+
+    /// The number of answers that use every letter.
+    pub pangrams: usize,
+
+    /// The value of the interest function for this puzzle.
+    pub interest: f64,
+
+    /// The mask of all seven letters.
+    letters: u32,
+}
+
+impl Candidate {
+    /// Returns `true` if some answer of at least `LONG_WORD_LENGTH` letters isn't a pangram.
+    pub fn has_long_non_pangram(&self) -> bool {
+        self.answers.iter().any(|w| letter_count(w) >= LONG_WORD_LENGTH &&
+                                letter_mask(w) != Some(self.letters))
+    }
+}
+
+/// The terms of the interest function used to rank puzzles. Each term is multiplied by its
+/// weight and the results are summed, so a weight of 0 turns that term off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interest {
+    /// Reward for spreading answers evenly over the range of word lengths, measured as an entropy
+    /// between 0 and 1.
+    pub balance: f64,
+
+    /// Reward for each pangram beyond the first.
+    pub extra_pangrams: f64,
+
+    /// Penalty for the fraction of answers that aren't in `common_words`.
+    pub obscurity: f64,
+
+    /// The words considered common. If this is empty, no answer counts as obscure.
+    pub common_words: HashSet<String>,
+}
+
+impl Default for Interest {
+    fn default() -> Interest {
+        Interest {
+            balance: 1.0,
+            extra_pangrams: 0.5,
+            obscurity: 1.0,
+            common_words: HashSet::new(),
+        }
+    }
+}
+
+impl Interest {
+    /// Computes how interesting a puzzle with the given answers and pangram count is.
+    pub fn score(&self, answers: &[String], pangrams: usize) -> f64 {
+        let extra_pangrams = pangrams.saturating_sub(1) as f64;
+
+        let obscurity = if self.common_words.is_empty() || answers.is_empty() {
+            0.0
+        } else {
+            let obscure = answers.iter().filter(|w| !self.common_words.contains(*w)).count();
+            obscure as f64 / answers.len() as f64
+        };
+
+        self.balance * length_balance(answers) + self.extra_pangrams * extra_pangrams -
+            self.obscurity * obscurity
+    }
+}
+
+/// Returns the entropy of the distribution of answer lengths, normalized by the number of lengths
+/// between the shortest and longest answer so that gaps count against the puzzle. Returns 0 if
+/// every answer has the same length.
+fn length_balance(answers: &[String]) -> f64 {
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    for word in answers {
+        *counts.entry(letter_count(word)).or_insert(0) += 1;
+    }
+
+    let span = match (counts.keys().next(), counts.keys().next_back()) {
+        (Some(shortest), Some(longest)) if longest > shortest => longest - shortest + 1,
+        _ => return 0.0,
+    };
+
+    let total = answers.len() as f64;
+    let entropy: f64 = counts.values().map(|&n| {
+        let p = n as f64 / total;
+        -p * p.ln()
+    }).sum();
+
+    entropy / (span as f64).ln()
+}
+
+/// Settings that control how much of the puzzle space the search explores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchConfig {
+    /// The number of puzzles kept at each step of the search.
+    pub beam_width: usize,
+
+    /// The maximum number of steps to take.
+    pub steps: usize,
+
+    /// The number of puzzles to return.
+    pub top_n: usize,
+
+    /// The fewest answers a returned puzzle may have.
+    pub min_answers: usize,
+
+    /// The most answers a returned puzzle may have.
+    pub max_answers: usize,
+
+    /// Whether a returned puzzle must have a long answer that isn't a pangram, so that the
+    /// pangrams aren't the only long words to find.
+    pub require_long_non_pangram: bool,
+
+    /// The seed used to pick the starting letter sets.
+    pub seed: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> SearchConfig {
+        SearchConfig {
+            beam_width: 8,
+            steps: 20,
+            top_n: 10,
+            min_answers: 20,
+            max_answers: 80,
+            require_long_non_pangram: false,
+            seed: 0,
+        }
+    }
+}
+
+/// A word list prepared for searching over puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleSearch {
+    /// Every word that could be an answer to some puzzle, with its letter mask.
+    words: Vec<(u32, String)>,
+
+    /// The distinct letter masks of words with exactly seven letters, in sorted order. These are
+    /// exactly the letter sets whose puzzles have a pangram.
+    pangram_sets: Vec<u32>,
+
+    /// The fewest letters an answer can have.
+    min_length: usize,
+}
+
+impl PuzzleSearch {
+    /// Creates a new search from the same main word list and swears buffers taken by
+    /// `SpellingBeeGame::new()`. Fails with `GameError::BadWordList` if they can't be parsed.
+    pub fn new(main_words: &str, swears: &str) -> Result<PuzzleSearch, GameError> {
+        let lex: VecLexicon = wordlist::parse_strings(main_words, swears)
+            .map_err(|_| GameError::BadWordList)?
+            .into();
+        Ok(PuzzleSearch::from_words(lex))
+    }
+
+    /// Creates a new search over the given words. Words that are too short, have more than seven
+    /// distinct letters, or have anything other than lowercase ASCII letters are ignored.
+    pub fn from_words<I: IntoIterator<Item = String>>(words: I) -> PuzzleSearch {
+        PuzzleSearch::from_words_with_scoring(words, &ScoringRules::default())
+    }
+
+    /// Creates a new search over the given words like `from_words()`, for games scored by the
+    /// given rules, so that answers can be as short as the rules' minimum length.
+    pub fn from_words_with_scoring<I>(words: I, scoring: &ScoringRules) -> PuzzleSearch
+        where I: IntoIterator<Item = String>
+    {
+        let min_length = scoring.min_length();
+        let words: Vec<(u32, String)> = words.into_iter()
+            .filter(|w| letter_count(w) >= min_length)
+            .filter_map(|w| letter_mask(&w).map(|mask| (mask, w)))
+            .filter(|(mask, _)| mask.count_ones() <= PUZZLE_LETTERS)
+            .collect();
+
+        let mut pangram_sets: Vec<u32> = words.iter()
+            .map(|(mask, _)| *mask)
+            .filter(|mask| mask.count_ones() == PUZZLE_LETTERS)
+            .collect();
+        pangram_sets.sort_unstable();
+        pangram_sets.dedup();
+
+        PuzzleSearch { words, pangram_sets, min_length }
+    }
+
+    /// Returns the fewest letters an answer can have.
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Returns the letter sets whose puzzles have a pangram, each as its seven letters in
+    /// alphabetical order.
+    pub fn pangram_letters(&self) -> impl Iterator<Item = String> + '_ {
+        self.pangram_sets.iter().map(|&mask| mask_letters(mask))
+    }
+
+    /// Evaluates the puzzle with the given letters, which must include the required letter.
+    /// Returns `None` if the letters aren't seven distinct lowercase ASCII letters or don't include
+    /// the required letter.
+    pub fn evaluate(&self, letters: &str, required_letter: char,
+                    interest: &Interest) -> Option<Candidate>
+    {
+        let mask = letter_mask(letters)?;
+        if mask.count_ones() != PUZZLE_LETTERS || !required_letter.is_ascii_lowercase() ||
+            mask & letter_bit(required_letter) == 0
+        {
+            return None;
+        }
+
+        self.candidate(mask, letter_bit(required_letter), interest)
+    }
+
+    /// Searches for the most interesting puzzles, returning up to `config.top_n` of them that meet
+    /// the constraints in `config`, most interesting first.
+    pub fn search(&self, interest: &Interest, config: &SearchConfig) -> Vec<Candidate> {
+        let mut run = self.start(interest, config);
+        while run.step() {}
+        run.finish()
+    }
+
+    /// Starts a search like `search()` that runs a step at a time, so that callers can do other
+    /// work in between steps.
+    pub fn start<'a>(&'a self, interest: &'a Interest, config: &'a SearchConfig) -> SearchRun<'a> {
+        let mut run = SearchRun {
+            search: self,
+            interest,
+            config,
+            seen: HashSet::new(),
+            found: Vec::new(),
+            beam: Vec::new(),
+            steps: 0,
+            done: self.pangram_sets.is_empty(),
+        };
+        if run.done {
+            return run;
+        }
+
+        let mut rng = Rng::new(config.seed);
+        let starts: Vec<(u32, u32)> = (0..config.beam_width).map(|_| {
+            let letters = self.pangram_sets[rng.below(self.pangram_sets.len())];
+            let bits = mask_bits(letters);
+            (letters, bits[rng.below(bits.len())])
+        }).collect();
+
+        run.beam = self.visit(starts, interest, config, &mut run.seen, &mut run.found);
+        run
+    }
+
+    /// Evaluates every puzzle in `states` that hasn't been seen before, recording the ones that
+    /// meet the constraints in `config` in `found`, and returns them all.
+    fn visit(&self, states: Vec<(u32, u32)>, interest: &Interest, config: &SearchConfig,
+             seen: &mut HashSet<(u32, u32)>, found: &mut Vec<Candidate>) -> Vec<Candidate>
+    {
+        let visited: Vec<Candidate> = states.into_iter()
+            .filter(|state| seen.insert(*state))
+            .filter_map(|(letters, required)| self.candidate(letters, required, interest))
+            .collect();
+
+        found.extend(visited.iter().filter(|c| constraint_penalty(c, config) == 0.0).cloned());
+        visited
+    }
+
+    /// Returns the puzzles one move away from the given one: either the same letters with a
+    /// different required letter, or one optional letter swapped out such that the puzzle still
+    /// has a pangram.
+    fn neighbors(&self, letters: u32, required: u32) -> Vec<(u32, u32)> {
+        let mut neighbors = Vec::new();
+        for old in mask_bits(letters).into_iter().filter(|&bit| bit != required) {
+            neighbors.push((letters, old));
+            for new in mask_bits(!letters & ALL_LETTERS) {
+                let swapped = (letters & !old) | new;
+                if self.pangram_sets.binary_search(&swapped).is_ok() {
+                    neighbors.push((swapped, required));
+                }
+            }
+        }
+
+        neighbors
+    }
+
+    /// Finds the answers to the puzzle with the given letter mask and required letter bit and
+    /// scores it. Returns `None` if the required letter bit isn't exactly one letter.
+    fn candidate(&self, letters: u32, required: u32, interest: &Interest) -> Option<Candidate> {
+        let required_letter = match mask_letters(required).chars().collect::<Vec<_>>()[..] {
+            [letter] => letter,
+            _ => return None,
+        };
+        let mut answers: Vec<String> = Vec::new();
+        let mut pangrams = 0;
+        for (mask, word) in &self.words {
+            if mask & !letters == 0 && mask & required != 0 {
+                if *mask == letters {
+                    pangrams += 1;
+                }
+                answers.push(word.clone());
+            }
+        }
+        answers.sort();
+
+        Some(Candidate {
+            optional_letters: mask_letters(letters & !required),
+            required_letter,
+            interest: interest.score(&answers, pangrams),
+            answers,
+            pangrams,
+            letters,
+        })
+    }
+}
+
+/// A search in progress, from `PuzzleSearch::start()`.
+#[derive(Debug, Clone)]
+pub struct SearchRun<'a> {
+    /// The word list being searched.
+    search: &'a PuzzleSearch,
+
+    /// What makes a puzzle interesting.
+    interest: &'a Interest,
+
+    /// The search settings.
+    config: &'a SearchConfig,
+
+    /// The puzzles evaluated so far, as letter masks and required letter bits.
+    seen: HashSet<(u32, u32)>,
+
+    /// The puzzles found so far that meet the constraints.
+    found: Vec<Candidate>,
+
+    /// The puzzles kept from the last step.
+    beam: Vec<Candidate>,
+
+    /// The number of steps taken.
+    steps: usize,
+
+    /// Whether the search has stopped.
+    done: bool,
+}
+
+impl SearchRun<'_> {
+    /// Takes a step of the search, moving the beam to the most interesting neighboring puzzles.
+    /// Returns `false` once the search has nothing left to do.
+    pub fn step(&mut self) -> bool {
+        if self.done || self.steps >= self.config.steps {
+            self.done = true;
+            return false;
+        }
+
+        let search = self.search;
+        let neighbors: Vec<(u32, u32)> = self.beam.iter()
+            .flat_map(|c| search.neighbors(c.letters, letter_bit(c.required_letter)))
+            .collect();
+        let mut next = search.visit(neighbors, self.interest, self.config, &mut self.seen,
+                                    &mut self.found);
+        self.steps += 1;
+        if next.is_empty() {
+            self.done = true;
+            return false;
+        }
+
+        next.append(&mut self.beam);
+        let config = self.config;
+        next.sort_by(|a, b| {
+            let (a, b) = (a.interest - constraint_penalty(a, config),
+                          b.interest - constraint_penalty(b, config));
+            b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        next.truncate(self.config.beam_width);
+        self.beam = next;
+        true
+    }
+
+    /// Returns `true` if the search has stopped.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Returns the most interesting puzzles found so far, up to `config.top_n` of them, most
+    /// interesting first.
+    pub fn finish(mut self) -> Vec<Candidate> {
+        sort_by_interest(&mut self.found);
+        self.found.truncate(self.config.top_n);
+        self.found
+    }
+}
+
+/// Splits a letter mask into its individual bits, from 'a' to 'z'.
+fn mask_bits(mask: u32) -> Vec<u32> {
+    (0..26).map(|i| 1 << i).filter(|bit| mask & bit != 0).collect()
+}
+
+/// Returns the letters in a mask in alphabetical order.
+fn mask_letters(mask: u32) -> String {
+    ('a'..='z').filter(|&c| mask & letter_bit(c) != 0).collect()
+}
+
+/// Returns how much a puzzle's interest is lowered in the beam for how far it is from meeting the
+/// constraints in `config`, which is 0 if it meets them.
+fn constraint_penalty(candidate: &Candidate, config: &SearchConfig) -> f64 {
+    let answers = candidate.answers.len();
+    let missed = config.min_answers.saturating_sub(answers) +
+        answers.saturating_sub(config.max_answers);
+    let long = config.require_long_non_pangram && !candidate.has_long_non_pangram();
+    ANSWER_COUNT_PENALTY * missed as f64 + if long { LONG_NON_PANGRAM_PENALTY } else { 0.0 }
+}
+
+/// Sorts candidates from most to least interesting.
+fn sort_by_interest(candidates: &mut [Candidate]) {
+    candidates.sort_by(|a, b| b.interest.partial_cmp(&a.interest)
+                       .unwrap_or(std::cmp::Ordering::Equal));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_search() {
+        let search = PuzzleSearch::from_words(words(&[
+            "cowgirl", "will", "crow", "grill", "growl", "wilco", "clog", "glow", "logic",
+            "coil", "roil", "girl", "oil", "zebra",
+        ]));
+        let config = SearchConfig { min_answers: 1, ..SearchConfig::default() };
+        let found = search.search(&Interest::default(), &config);
+
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|c| c.pangrams >= 1));
+        assert!(found.windows(2).all(|w| w[0].interest >= w[1].interest));
+
+        let game = search.evaluate("clwgroi", 'i', &Interest::default()).unwrap();
+        assert_eq!(game.optional_letters, "cglorw");
+        assert_eq!(game.answers, words(&["coil", "cowgirl", "girl", "grill", "logic", "roil",
+                                         "wilco", "will"]));
+        assert_eq!(game.pangrams, 1);
+        assert!(!game.has_long_non_pangram());
+        assert_eq!(search.evaluate("clwgro", 'i', &Interest::default()), None);
+
+        let config = SearchConfig { require_long_non_pangram: true, ..config };
+        assert!(search.search(&Interest::default(), &config).is_empty());
+
+        let mut kids = ScoringRules::new();
+        kids.set_min_length(3);
+        let search = PuzzleSearch::from_words_with_scoring(words(&["cowgirl", "oil"]), &kids);
+        let game = search.evaluate("clwgroi", 'i', &Interest::default()).unwrap();
+        assert_eq!(game.answers, words(&["cowgirl", "oil"]));
+    }
+
+    #[test]
+    fn test_search_through_constraints() {
+        let search = PuzzleSearch::from_words(words(&[
+            "cowgirl", "will", "crow", "grill", "growl", "wilco", "clog", "glow", "logic",
+            "coil", "roil", "girl",
+        ]));
+        // Only the puzzle with I required has exactly eight answers, so a search that starts
+        // anywhere else has to keep puzzles with the wrong number to get there.
+        for seed in 0..10 {
+            let config = SearchConfig {
+                beam_width: 1,
+                min_answers: 8,
+                max_answers: 8,
+                seed,
+                ..SearchConfig::default()
+            };
+            let found = search.search(&Interest::default(), &config);
+            let found: Vec<char> = found.iter().map(|c| c.required_letter).collect();
+            assert_eq!(found, ['i'], "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! This module provides the room server run by `spellingbee --serve`, which hosts co-op and versus
+//! games of one puzzle for everyone connected over WebSockets, so that players on different
+//! devices can solve it together or race each other. It is only built natively, with the `server`
+//! feature.
+//!
+//! `Room` has the rules of the server, without any networking: which lobby each client has joined
+//! as which player, and which messages go to whom (see the `protocol` module for the messages).
+//! Clients open lobbies and join them by code with `Lobbies`, and each lobby's game starts once
+//! every player in it is ready. Co-op plays are credited to players with a `TeamGame`, and versus
+//! games are a `Royale`. `serve()` accepts connections and passes their messages to the room,
+//! each connection on its own task, and ends versus rounds as their deadlines pass.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+use crate::i18n::play_result_key;
+use crate::lobby::{Lobbies, Lobby, LobbyError, LobbyMode, Session};
+use crate::protocol::{ClientMessage, ServerMessage};
+use crate::royale::RoyalePlay;
+use crate::spellingbee::{PlayResult, SpellingBeeGame};
+use crate::utils::now;
+
+/// How often `serve()` checks whether versus rounds have ended.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The lobby a client has joined, and the player it joined as.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Seat {
+    /// The lobby's join code.
+    code: String,
+
+    /// The player.
+    player: String,
+}
+
+/// The lobbies and the clients connected to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    /// The puzzle every lobby plays.
+    game: SpellingBeeGame,
+
+    /// The open lobbies.
+    lobbies: Lobbies,
+
+    /// The seat of each connected client, by client id, or `None` if it hasn't joined a lobby.
+    clients: BTreeMap<u64, Option<Seat>>,
+
+    /// The id of the next client to connect.
+    next_client: u64,
+}
+
+impl Room {
+    /// Creates a room whose lobbies play the given game, with join codes generated from the given
+    /// seed.
+    pub fn new(game: SpellingBeeGame, seed: u64) -> Room {
+        Room { game, lobbies: Lobbies::new(seed), clients: BTreeMap::new(), next_client: 0 }
+    }
+
+    /// Returns the open lobbies.
+    pub fn lobbies(&self) -> &Lobbies {
+        &self.lobbies
+    }
+
+    /// Adds a client that hasn't joined a lobby yet, returning its id.
+    pub fn connect(&mut self) -> u64 {
+        let id = self.next_client;
+        self.next_client += 1;
+        self.clients.insert(id, None);
+        id
+    }
+
+    /// Removes a client, taking its player out of its lobby if the game hasn't started, and
+    /// returns the messages telling the rest of the lobby. The words its player found stay found.
+    pub fn disconnect(&mut self, client: u64) -> Vec<(u64, ServerMessage)> {
+        let messages = self.unseat(client);
+        self.clients.remove(&client);
+        messages
+    }
+
+    /// Handles a message from a client at time `now` (in milliseconds), returning the messages
+    /// to send and the ids of the clients to send them to.
+    pub fn receive(&mut self, client: u64, text: &str, now: u64) -> Vec<(u64, ServerMessage)> {
+        match ClientMessage::parse(text) {
+            None => error(client, "unknown message"),
+            Some(ClientMessage::CreateCoop(player)) => {
+                self.create(client, &player, LobbyMode::Coop)
+            }
+            Some(ClientMessage::CreateVersus { player, round_length, eliminations_per_round }) => {
+                let mode = LobbyMode::Versus { round_length, eliminations_per_round };
+                self.create(client, &player, mode)
+            }
+            Some(ClientMessage::Join { code, player }) => self.join(client, &code, &player),
+            Some(ClientMessage::Ready(ready)) => self.ready(client, ready, now),
+            Some(ClientMessage::Leave) => self.unseat(client),
+            Some(ClientMessage::Play(word)) => self.play(client, &word, now),
+        }
+    }
+
+    /// Ends every versus round whose deadline is at or before `now`, returning the messages
+    /// saying who was knocked out and who won.
+    pub fn tick(&mut self, now: u64) -> Vec<(u64, ServerMessage)> {
+        let codes: BTreeSet<String> = self.clients.values().flatten()
+            .map(|seat| seat.code.clone())
+            .collect();
+        codes.iter().flat_map(|code| self.advance(code, now)).collect()
+    }
+
+    /// Opens a lobby with the client's player as its host, first taking the client out of any
+    /// lobby it was in.
+    fn create(&mut self, client: u64, player: &str, mode: LobbyMode) -> Vec<(u64, ServerMessage)> {
+        let mut messages = self.unseat(client);
+        let code = self.lobbies.create(player, self.game.clone(), mode);
+        self.clients.insert(client, Some(Seat { code: code.clone(), player: player.to_string() }));
+        messages.extend(self.send_lobby(&code));
+        messages
+    }
+
+    /// Adds the client's player to the lobby with the given join code, first taking the client
+    /// out of any lobby it was in.
+    fn join(&mut self, client: u64, code: &str, player: &str) -> Vec<(u64, ServerMessage)> {
+        // Check first, so that a client isn't taken out of its lobby to join one it can't.
+        let code = code.to_uppercase();
+        match self.lobbies.get(&code) {
+            None => return error(client, &LobbyError::UnknownLobby.to_string()),
+            Some(lobby) if lobby.session().is_some() => {
+                return error(client, &LobbyError::AlreadyStarted.to_string());
+            }
+            Some(_) => {}
+        }
+        let mut messages = self.unseat(client);
+        if let Err(e) = self.lobbies.join(&code, player) {
+            messages.extend(error(client, &e.to_string()));
+            return messages;
+        }
+        self.clients.insert(client, Some(Seat { code: code.clone(), player: player.to_string() }));
+        messages.extend(self.send_lobby(&code));
+        messages
+    }
+
+    /// Marks the client's player as ready or not, starting the lobby's game at time `now` if
+    /// everyone is now ready.
+    fn ready(&mut self, client: u64, ready: bool, now: u64) -> Vec<(u64, ServerMessage)> {
+        let Seat { code, player } = match self.seat(client) {
+            Some(seat) => seat,
+            None => return error(client, "join a lobby first"),
+        };
+        if let Err(e) = self.lobbies.set_ready(&code, &player, ready) {
+            return error(client, &e.to_string());
+        }
+        if !self.lobbies.get(&code).is_some_and(Lobby::all_ready) {
+            return self.send_lobby(&code);
+        }
+
+        if let Err(e) = self.lobbies.start(&code, now) {
+            return error(client, &e.to_string());
+        }
+        let puzzle = ServerMessage::Puzzle {
+            optional_letters: self.game.optional_letters(),
+            required_letter: self.game.required_letter(),
+            max_score: self.game.max_score(),
+        };
+        self.send_to(&code, &[puzzle])
+    }
+
+    /// Plays a word for a client's player at time `now`, returning a `found` message with the
+    /// answer it was accepted as for everyone in the lobby if it's accepted, and a `rejected` or
+    /// `error` message for the client if it isn't.
+    fn play(&mut self, client: u64, word: &str, now: u64) -> Vec<(u64, ServerMessage)> {
+        let Seat { code, player } = match self.seat(client) {
+            Some(seat) => seat,
+            None => return error(client, "join before playing"),
+        };
+        let mut messages = self.advance(&code, now);
+        let found = match self.lobbies.get_mut(&code).and_then(Lobby::session_mut) {
+            None => return error(client, "the game hasn't started"),
+            Some(Session::Coop(team)) => {
+                let outcome = team.play_outcome(&player, word);
+                if outcome.result() != PlayResult::Valid {
+                    return rejected(client, word, outcome.result());
+                }
+                ServerMessage::Found {
+                    player: player.clone(),
+                    word: outcome.word(),
+                    points: outcome.points(),
+                    score: team.score(),
+                }
+            }
+            Some(Session::Versus(royale)) => match royale.play(&player, word, now) {
+                RoyalePlay::Played(PlayResult::Valid) => {
+                    let game = royale.game(&player).expect("the player has just played");
+                    let word = game.play_order().last().expect("the word was played").clone();
+                    ServerMessage::Found {
+                        player: player.clone(),
+                        points: game.word_points(&word),
+                        word,
+                        score: game.score(),
+                    }
+                }
+                RoyalePlay::Played(result) => return rejected(client, word, result),
+                RoyalePlay::Eliminated => return error(client, "you've been knocked out"),
+                RoyalePlay::Finished => return error(client, "the game is over"),
+                RoyalePlay::UnknownPlayer => return error(client, "join before playing"),
+            },
+        };
+        messages.extend(self.send_to(&code, &[found]));
+        messages
+    }
+
+    /// Ends the rounds of the lobby's versus game whose deadlines are at or before `now`,
+    /// returning the messages saying who was knocked out and who won.
+    fn advance(&mut self, code: &str, now: u64) -> Vec<(u64, ServerMessage)> {
+        let royale = match self.lobbies.get_mut(code).and_then(Lobby::session_mut) {
+            Some(Session::Versus(royale)) => royale,
+            _ => return Vec::new(),
+        };
+        let out = royale.advance(now);
+        if out.is_empty() {
+            return Vec::new();
+        }
+
+        let standings = royale.standings();
+        let mut news: Vec<ServerMessage> = out.into_iter().map(|player| {
+            let round = standings.iter().find(|s| s.player == player)
+                .and_then(|s| s.eliminated_in)
+                .unwrap_or_default();
+            ServerMessage::Eliminated { player, round }
+        }).collect();
+        if royale.is_finished() {
+            news.push(ServerMessage::Winner(standings[0].player.clone()));
+        }
+        self.send_to(code, &news)
+    }
+
+    /// Takes a client out of its lobby, returning the messages telling the rest of the lobby if
+    /// its player left one whose game hasn't started. A lobby whose game has started is closed
+    /// once no clients are left in it.
+    fn unseat(&mut self, client: u64) -> Vec<(u64, ServerMessage)> {
+        let Seat { code, player } = match self.clients.get_mut(&client).and_then(Option::take) {
+            Some(seat) => seat,
+            None => return Vec::new(),
+        };
+        if self.lobbies.leave(&code, &player).is_ok() {
+            return self.send_lobby(&code);
+        }
+        if self.clients.values().flatten().all(|seat| seat.code != code) {
+            self.lobbies.close(&code);
+        }
+        Vec::new()
+    }
+
+    /// Returns the lobby and player a client has joined as, if it has.
+    fn seat(&self, client: u64) -> Option<Seat> {
+        self.clients.get(&client).cloned().flatten()
+    }
+
+    /// Returns the messages telling everyone in a lobby its join code, mode and host, and who is
+    /// in it, or nothing if it has closed.
+    fn send_lobby(&self, code: &str) -> Vec<(u64, ServerMessage)> {
+        let lobby = match self.lobbies.get(code) {
+            Some(lobby) => lobby,
+            None => return Vec::new(),
+        };
+        let mode = match lobby.mode() {
+            LobbyMode::Coop => "coop",
+            LobbyMode::Versus { .. } => "versus",
+        };
+        let ready = lobby.ready_players();
+        let state: Vec<ServerMessage> = std::iter::once(ServerMessage::Lobby {
+            code: code.to_string(),
+            mode: mode.to_string(),
+            host: lobby.host().to_string(),
+        }).chain(lobby.players().into_iter().map(|player| ServerMessage::Member {
+            player: player.to_string(),
+            ready: ready.contains(player),
+        })).collect();
+        self.send_to(code, &state)
+    }
+
+    /// Returns the messages sending each of the given messages to every client in a lobby.
+    fn send_to(&self, code: &str, messages: &[ServerMessage]) -> Vec<(u64, ServerMessage)> {
+        self.clients.iter()
+            .filter(|(_, seat)| seat.as_ref().is_some_and(|seat| seat.code == code))
+            .flat_map(|(&id, _)| messages.iter().map(move |message| (id, message.clone())))
+            .collect()
+    }
+}
+
+/// Returns an `error` message for a client.
+fn error(client: u64, message: &str) -> Vec<(u64, ServerMessage)> {
+    vec![(client, ServerMessage::Error(message.to_string()))]
+}
+
+/// Returns a `rejected` message for a client's play.
+fn rejected(client: u64, word: &str, result: PlayResult) -> Vec<(u64, ServerMessage)> {
+    let reason = play_result_key(result).to_string();
+    vec![(client, ServerMessage::Rejected { word: word.to_string(), reason })]
+}
+
+/// The room and a way to send messages to each of its clients, shared between connections.
+struct Hub {
+    /// The room.
+    room: Room,
+
+    /// Where to send each client's messages, by client id.
+    senders: BTreeMap<u64, UnboundedSender<String>>,
+}
+
+impl Hub {
+    /// Sends each message to its client.
+    fn send(&self, messages: Vec<(u64, ServerMessage)>) {
+        for (to, message) in messages {
+            if let Some(sender) = self.senders.get(&to) {
+                // A client whose connection has closed is about to be removed anyway.
+                let _ = sender.send(message.to_string());
+            }
+        }
+    }
+}
+
+/// Builds a runtime and serves the game at the given address, like `0.0.0.0:8080`, until
+/// something goes wrong with the listening socket.
+pub fn run(address: &str, game: SpellingBeeGame) -> io::Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    runtime.block_on(async {
+        let listener = TcpListener::bind(address).await?;
+        serve(listener, game).await
+    })
+}
+
+/// Serves lobbies playing the game to every WebSocket client that connects to the listener,
+/// until something goes wrong with the listener.
+pub async fn serve(listener: TcpListener, game: SpellingBeeGame) -> io::Result<()> {
+    let room = Room::new(game, now() as u64);
+    let hub = Arc::new(Mutex::new(Hub { room, senders: BTreeMap::new() }));
+
+    let ticker = Arc::clone(&hub);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let mut hub = lock(&ticker);
+            let messages = hub.room.tick(now() as u64);
+            hub.send(messages);
+        }
+    });
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(connection(stream, Arc::clone(&hub)));
+    }
+}
+
+/// Locks the hub. A connection that panicked while holding the lock leaves the room as it was
+/// after its last complete message, so the room is still used.
+fn lock(hub: &Mutex<Hub>) -> MutexGuard<'_, Hub> {
+    hub.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Handles one client's connection, from the WebSocket handshake until it closes.
+async fn connection(stream: TcpStream, hub: Arc<Mutex<Hub>>) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    let (mut sink, mut source) = socket.split();
+    let (sender, mut outbox) = mpsc::unbounded_channel::<String>();
+    let client = {
+        let mut hub = lock(&hub);
+        let client = hub.room.connect();
+        hub.senders.insert(client, sender);
+        client
+    };
+
+    // Sending happens on its own task, which ends once the client is removed from the hub and
+    // its sender dropped.
+    tokio::spawn(async move {
+        while let Some(text) = outbox.recv().await {
+            if sink.send(Message::text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(message)) = source.next().await {
+        if let (true, Ok(text)) = (message.is_text(), message.to_text()) {
+            let mut hub = lock(&hub);
+            let messages = hub.room.receive(client, text, now() as u64);
+            hub.send(messages);
+        }
+    }
+
+    let mut hub = lock(&hub);
+    hub.senders.remove(&client);
+    let messages = hub.room.disconnect(client);
+    hub.send(messages);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the join code from the first `lobby` message.
+    fn code(messages: &[(u64, ServerMessage)]) -> String {
+        messages.iter().find_map(|(_, m)| match m {
+            ServerMessage::Lobby { code, .. } => Some(code.clone()),
+            _ => None,
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_room() {
+        let game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "");
+        let mut room = Room::new(game, 7);
+        let (alice, bob, carol) = (room.connect(), room.connect(), room.connect());
+
+        let error = ServerMessage::Error("join before playing".to_string());
+        assert_eq!(room.receive(alice, "play\twill", 0), vec![(alice, error)]);
+        let code = code(&room.receive(alice, "create\talice\tcoop", 0));
+        assert_eq!(room.receive(bob, "join\tZZZZZ\tbob", 0)[0].1,
+                   ServerMessage::Error("no lobby has that join code".to_string()));
+        let joined = room.receive(bob, &format!("join\t{}\tbob", code.to_lowercase()), 0);
+        let member = |player: &str, ready| {
+            ServerMessage::Member { player: player.to_string(), ready }
+        };
+        assert_eq!(joined.len(), 6);
+        assert_eq!(joined[..3], [(alice, ServerMessage::Lobby {
+            code: code.clone(),
+            mode: "coop".to_string(),
+            host: "alice".to_string(),
+        }), (alice, member("alice", false)), (alice, member("bob", false))]);
+
+        assert_eq!(room.receive(alice, "play\twill", 0)[0].1,
+                   ServerMessage::Error("the game hasn't started".to_string()));
+        assert_eq!(room.receive(alice, "ready", 0)[1..3],
+                   [(alice, member("alice", true)), (alice, member("bob", false))]);
+        let puzzle = ServerMessage::parse("puzzle\tcglorw\ti\t16").unwrap();
+        assert_eq!(room.receive(bob, "ready", 0), vec![(alice, puzzle.clone()),
+                                                        (bob, puzzle)]);
+        assert_eq!(room.receive(carol, &format!("join\t{}\tcarol", code), 0)[0].1,
+                   ServerMessage::Error("the lobby's game has already started".to_string()));
+
+        let found = ServerMessage::parse("found\talice\tcowgirl\t14\t14").unwrap();
+        assert_eq!(room.receive(alice, "play\tcowgirl", 0), vec![(alice, found.clone()),
+                                                                  (bob, found)]);
+        let rejected = ServerMessage::parse("rejected\tcowgirl\tplay.already_played").unwrap();
+        assert_eq!(room.receive(bob, "play\tcowgirl", 0), vec![(bob, rejected)]);
+        let will = ServerMessage::parse("found\tbob\twill\t1\t15").unwrap();
+        assert_eq!(room.receive(bob, "play\t WILL ", 0), vec![(alice, will.clone()),
+                                                               (bob, will)]);
+        assert_eq!(room.receive(bob, "join\tbob", 0)[0].1,
+                   ServerMessage::Error("unknown message".to_string()));
+
+        // The lobby closes once everyone has gone.
+        assert!(room.disconnect(alice).is_empty());
+        assert!(room.lobbies().get(&code).is_some());
+        room.receive(bob, "leave", 0);
+        assert!(room.lobbies().get(&code).is_none());
+    }
+
+    #[test]
+    fn test_versus() {
+        let game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "");
+        let mut room = Room::new(game, 7);
+        let (alice, bob, carol) = (room.connect(), room.connect(), room.connect());
+        let code = code(&room.receive(alice, "create\talice\tversus\t1000\t1", 0));
+        room.receive(bob, &format!("join\t{}\tbob", code), 0);
+        room.receive(carol, &format!("join\t{}\tcarol", code), 0);
+
+        // Carol leaving before the game starts tells everyone still in the lobby.
+        let left = room.receive(carol, "leave", 0);
+        assert_eq!(left.iter().map(|(to, _)| *to).collect::<BTreeSet<_>>(),
+                   [alice, bob].iter().copied().collect());
+        assert_eq!(room.lobbies().get(&code).unwrap().players(), ["alice", "bob"]);
+
+        room.receive(alice, "ready", 0);
+        room.receive(bob, "ready", 100);
+        let found = ServerMessage::parse("found\tbob\twill\t1\t1").unwrap();
+        assert_eq!(room.receive(bob, "play\twill", 500), vec![(alice, found.clone()),
+                                                               (bob, found)]);
+        let found = ServerMessage::parse("found\talice\twill\t1\t1").unwrap();
+        assert_eq!(room.receive(alice, "play\twill", 600), vec![(alice, found.clone()),
+                                                                 (bob, found)]);
+        assert!(room.tick(1_000).is_empty());
+
+        // Bob scored first, so Alice is knocked out when the round ends.
+        let eliminated = ServerMessage::Eliminated { player: "alice".to_string(), round: 1 };
+        let winner = ServerMessage::Winner("bob".to_string());
+        assert_eq!(room.tick(1_100), vec![(alice, eliminated.clone()), (alice, winner.clone()),
+                                          (bob, eliminated), (bob, winner)]);
+        assert_eq!(room.receive(bob, "play\tcoil", 1_200)[0].1,
+                   ServerMessage::Error("the game is over".to_string()));
+    }
+}
@@ -0,0 +1,174 @@
+//! This module provides `Sessions`, which issues and checks the session tokens the HTTP API ties
+//! games and leaderboard entries to, so that players have an identity without a full account
+//! system. It is only built natively, with the `http-api` feature.
+//!
+//! A player starts a session by giving a name, and gets back a `Player` with an id made up for
+//! them and a token, which they send with later requests. A token is four fields joined by dots:
+//! the player's id, their name as hexadecimal UTF-8, when the token expires (in milliseconds since
+//! the Unix epoch), and an HMAC-SHA256 of the rest under the server's secret key, so the server
+//! doesn't have to remember the sessions it has issued and players can't forge or edit them.
+//! Tokens stay good for `SESSION_LENGTH`; there's no way to revoke one before then short of
+//! changing the key, which revokes every one.
+//!
+//! The same key signs anything else the server wants to be able to tell it wrote, like the times
+//! on leaderboard entries, with `Sessions::sign()`.
+
+use std::fmt::Write;
+use async_graphql::SimpleObject;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// The message authentication code tokens are signed with.
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a token is good for, in milliseconds: 30 days.
+pub const SESSION_LENGTH: u64 = 30 * 24 * 60 * 60 * 1000;
+
+/// The longest name a player can have, in bytes.
+pub const MAX_NAME_BYTES: usize = 64;
+
+/// A player with a session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SimpleObject)]
+pub struct Player {
+    /// The id made up for the player, as 16 hexadecimal digits.
+    pub id: String,
+
+    /// The name the player gave.
+    pub name: String,
+}
+
+/// The key sessions are signed with, and what's needed to give out new player ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sessions {
+    /// The secret key tokens are signed with.
+    key: Vec<u8>,
+
+    /// The number of sessions issued so far, which goes into each new player id.
+    issued: u64,
+}
+
+impl Sessions {
+    /// Creates sessions signed with the given secret key, which should be at least 32 random
+    /// bytes. Tokens signed with the same key are good across restarts.
+    pub fn new(key: &[u8]) -> Sessions {
+        Sessions { key: key.to_vec(), issued: 0 }
+    }
+
+    /// Creates sessions signed with 32 random bytes from the operating system, so the tokens are
+    /// only good until the server stops. Panics if the operating system can't supply them.
+    pub fn with_random_key() -> Sessions {
+        let mut key = [0; 32];
+        getrandom::fill(&mut key).expect("the operating system supplies random bytes");
+        Sessions::new(&key)
+    }
+
+    /// Starts a session for a player with the given name at the given time, in milliseconds since
+    /// the Unix epoch, returning the player and their token. Returns `None` if the name is empty,
+    /// longer than `MAX_NAME_BYTES`, or has tabs or line breaks.
+    pub fn start(&mut self, name: &str, now: u64) -> Option<(Player, String)> {
+        if name.is_empty() || name.len() > MAX_NAME_BYTES || name.contains(['\t', '\n', '\r']) {
+            return None;
+        }
+        self.issued += 1;
+        let seed = format!("player\t{}\t{}\t{}", name, now, self.issued);
+        let id = hex(&self.mac(&seed).finalize().into_bytes()[..8]);
+        let payload = format!("{}.{}.{}", id, hex(name.as_bytes()), now + SESSION_LENGTH);
+        let token = format!("{}.{}", payload, self.sign(&payload));
+        Some((Player { id, name: name.to_string() }, token))
+    }
+
+    /// Returns the player a token is for, or `None` if it isn't a token signed with this key or
+    /// it has expired by the given time.
+    pub fn check(&self, token: &str, now: u64) -> Option<Player> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        if !self.is_signed(payload, signature) {
+            return None;
+        }
+
+        let fields: Vec<&str> = payload.split('.').collect();
+        match fields.as_slice() {
+            [id, name, expires] if expires.parse::<u64>().ok()? > now => Some(Player {
+                id: id.to_string(),
+                name: String::from_utf8(unhex(name)?).ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Signs a message with the key, returning the signature as hexadecimal.
+    pub fn sign(&self, message: &str) -> String {
+        hex(&self.mac(message).finalize().into_bytes())
+    }
+
+    /// Returns `true` if the signature is the one `sign()` gives for the message.
+    pub fn is_signed(&self, message: &str, signature: &str) -> bool {
+        // `verify_slice()` compares in constant time, so it doesn't tell an attacker how much of
+        // a forged signature was right.
+        unhex(signature).is_some_and(|bytes| self.mac(message).verify_slice(&bytes).is_ok())
+    }
+
+    /// Returns an HMAC-SHA256 under the key that has been given the message.
+    fn mac(&self, message: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC takes keys of any length");
+        mac.update(message.as_bytes());
+        mac
+    }
+}
+
+/// Writes bytes as lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(text, "{:02x}", byte).expect("writing to a string");
+    }
+    text
+}
+
+/// Reads bytes written by `hex()`, returning `None` if they aren't hexadecimal.
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len()).step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // From RFC 4231, test case 2.
+        assert_eq!(Sessions::new(b"Jefe").sign("what do ya want for nothing?"),
+                   "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        assert_ne!(Sessions::with_random_key().sign("entry"),
+                   Sessions::with_random_key().sign("entry"));
+    }
+
+    #[test]
+    fn test_sessions() {
+        let mut sessions = Sessions::new(b"the server's secret key");
+        let (ana, token) = sessions.start("Ana María", 1_000).unwrap();
+        let (other_ana, _) = sessions.start("Ana María", 1_000).unwrap();
+        assert_eq!((ana.id.len(), ana.name.as_str()), (16, "Ana María"));
+        assert_ne!(ana.id, other_ana.id);
+        assert_eq!(sessions.check(&token, 2_000), Some(ana.clone()));
+        assert_eq!(sessions.check(&token, 1_000 + SESSION_LENGTH), None);
+
+        let forged = token.replacen(&ana.id, &other_ana.id, 1);
+        assert_eq!(sessions.check(&forged, 2_000), None);
+        assert_eq!(Sessions::new(b"another key").check(&token, 2_000), None);
+        assert_eq!(sessions.check("not a token", 2_000), None);
+        assert_eq!(sessions.start("a\tb", 1_000), None);
+        assert_eq!(sessions.start("", 1_000), None);
+
+        let signature = sessions.sign("entry\t15");
+        assert!(sessions.is_signed("entry\t15", &signature));
+        assert!(!sessions.is_signed("entry\t16", &signature));
+        assert!(!sessions.is_signed("entry\t15", &signature[..62]));
+        assert!(!sessions.is_signed("entry\t15", "not hexadecimal"));
+        assert!(!Sessions::new(b"another key").is_signed("entry\t15", &signature));
+    }
+}
@@ -0,0 +1,244 @@
+//! This module provides `WordlistSource`, a word list parsed from one of the formats people keep
+//! dictionaries in, so that they can bring their own without preprocessing it first:
+//!
+//! - `Plain`: one word per line, optionally followed by a tab and how often it occurs, which is
+//!   what `SpellingBeeGame::new()` takes.
+//! - `Csv`: a word and, optionally, its frequency on each line, separated by a comma. Fields can
+//!   be quoted, with `""` for a quote inside one, and a first line whose frequency isn't a number,
+//!   like `word,frequency`, or that is just `word`, is taken as a header and skipped.
+//! - `Json`: an array of words, an array of objects with a `word` and optionally a `frequency`,
+//!   or an object with words as keys and frequencies as values. These are read with serde_json,
+//!   and only with the `json` feature, which is on by default; without it they don't parse.
+//!
+//! Words can't have tabs or line breaks, which would change what the plain format means, so a
+//! CSV or JSON list with one is rejected.
+//!
+//! A parsed list becomes a game with `SpellingBeeGame::from_source()`, which passes the
+//! frequencies on to the game like those in a plain list.
+
+#[cfg(feature = "json")]
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+/// The formats a word list can be in.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WordlistFormat {
+    /// One word per line, optionally followed by a tab and its frequency.
+    Plain,
+
+    /// A word and optionally its frequency per line, separated by a comma.
+    Csv,
+
+    /// An array of words or of objects with `word` and `frequency`, or an object mapping words
+    /// to frequencies.
+    Json,
+}
+
+/// A word list and any frequencies it gave, in the order it gave them.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WordlistSource {
+    /// Each word, with its frequency if the list gave one.
+    entries: Vec<(String, Option<u64>)>,
+}
+
+#[wasm_bindgen]
+impl WordlistSource {
+    /// Parses a word list in the given format, returning `None` if it isn't valid in that
+    /// format or a word has a tab or line break. Blank words are skipped.
+    pub fn parse(format: WordlistFormat, text: &str) -> Option<WordlistSource> {
+        let entries = match format {
+            WordlistFormat::Plain => parse_plain(text),
+            WordlistFormat::Csv => parse_csv(text)?,
+            #[cfg(feature = "json")]
+            WordlistFormat::Json => parse_json(text)?,
+            #[cfg(not(feature = "json"))]
+            WordlistFormat::Json => return None,
+        };
+        if entries.iter().any(|(w, _)| w.contains(['\t', '\n', '\r'])) {
+            return None;
+        }
+        Some(WordlistSource {
+            entries: entries.into_iter().filter(|(w, _)| !w.trim().is_empty()).collect(),
+        })
+    }
+
+    /// Guesses the format of a word list: JSON if it starts with `[` or `{`, CSV if its first
+    /// line has a comma, and plain otherwise.
+    pub fn detect_format(text: &str) -> WordlistFormat {
+        let text = text.trim_start();
+        if text.starts_with('[') || text.starts_with('{') {
+            WordlistFormat::Json
+        } else if text.lines().next().is_some_and(|line| line.contains(',')) {
+            WordlistFormat::Csv
+        } else {
+            WordlistFormat::Plain
+        }
+    }
+
+    /// Parses a word list in the format `detect_format()` guesses for it.
+    pub fn parse_detected(text: &str) -> Option<WordlistSource> {
+        WordlistSource::parse(WordlistSource::detect_format(text), text)
+    }
+
+    /// Returns the number of words.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the list in the plain format, which is what `SpellingBeeGame::new()` takes.
+    pub fn to_plain(&self) -> String {
+        self.entries.iter().map(|(word, frequency)| match frequency {
+            Some(count) => format!("{}\t{}\n", word.trim(), count),
+            None => format!("{}\n", word.trim()),
+        }).collect()
+    }
+}
+
+impl WordlistSource {
+    /// Returns each word, with its frequency if the list gave one.
+    pub fn entries(&self) -> &[(String, Option<u64>)] {
+        &self.entries
+    }
+}
+
+/// Parses a plain word list. Every line is a word, so this can't fail, but a frequency that
+/// isn't a number is dropped.
+fn parse_plain(text: &str) -> Vec<(String, Option<u64>)> {
+    text.lines().map(|line| match line.split_once('\t') {
+        Some((word, count)) => (word.trim().to_string(), count.trim().parse().ok()),
+        None => (line.trim().to_string(), None),
+    }).collect()
+}
+
+/// Parses a CSV word list, returning `None` if a quote isn't closed, a line has more than two
+/// fields, or a frequency isn't a number. A first line that is just `word` is a header.
+fn parse_csv(text: &str) -> Option<Vec<(String, Option<u64>)>> {
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let fields = csv_fields(line)?;
+        let entry = match fields.as_slice() {
+            [word] if i == 0 && word.trim().eq_ignore_ascii_case("word") => continue,
+            [word] => (word.clone(), None),
+            [word, count] if count.trim().is_empty() => (word.clone(), None),
+            [word, count] => match count.trim().parse() {
+                Ok(count) => (word.clone(), Some(count)),
+                Err(_) if i == 0 => continue,
+                Err(_) => return None,
+            },
+            _ => return None,
+        };
+        entries.push(entry);
+    }
+    Some(entries)
+}
+
+/// Splits a line of CSV into its fields, returning `None` if a quote isn't closed.
+fn csv_fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut()?;
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(String::new()),
+            _ => field.push(c),
+        }
+    }
+    if quoted { None } else { Some(fields) }
+}
+
+/// A JSON word list, in one of the shapes described in the module documentation.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum JsonWordlist {
+    /// An array of words, or of objects with a word.
+    Words(Vec<JsonWord>),
+
+    /// An object with words as keys and frequencies as values, in the order the list gave them.
+    Frequencies(serde_json::Map<String, serde_json::Value>),
+}
+
+/// A word in a JSON array of them.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(untagged)]
+enum JsonWord {
+    /// Just the word.
+    Word(String),
+
+    /// An object with the word and optionally its frequency.
+    Entry {
+        word: String,
+        #[serde(default)]
+        frequency: Option<u64>,
+    },
+}
+
+/// Parses a JSON word list, returning `None` if it isn't valid JSON in one of the shapes
+/// described in the module documentation.
+#[cfg(feature = "json")]
+fn parse_json(text: &str) -> Option<Vec<(String, Option<u64>)>> {
+    match serde_json::from_str(text).ok()? {
+        JsonWordlist::Words(words) => Some(words.into_iter().map(|word| match word {
+            JsonWord::Word(word) => (word, None),
+            JsonWord::Entry { word, frequency } => (word, frequency),
+        }).collect()),
+        JsonWordlist::Frequencies(counts) => counts.into_iter()
+            .map(|(word, count)| Some((word, Some(count.as_u64()?))))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources() {
+        let plain = WordlistSource::parse(WordlistFormat::Plain, "will\t900\ncoil\n\n").unwrap();
+        assert_eq!(plain.entries(), [("will".to_string(), Some(900)), ("coil".to_string(), None)]);
+        assert_eq!(plain.to_plain(), "will\t900\ncoil\n");
+
+        let csv = "word,frequency\nwill,900\n\"coil\",\n\"say \"\"hi\"\"\",3\n";
+        assert_eq!(WordlistSource::detect_format(csv), WordlistFormat::Csv);
+        let parsed = WordlistSource::parse_detected(csv).unwrap();
+        assert_eq!(parsed.entries()[..2], plain.entries()[..]);
+        assert_eq!(parsed.entries()[2], ("say \"hi\"".to_string(), Some(3)));
+        assert_eq!(WordlistSource::parse(WordlistFormat::Csv, "will,900\ncoil,many\n"), None);
+        let one_column = WordlistSource::parse(WordlistFormat::Csv, "Word\nwill\ncoil\n").unwrap();
+        assert_eq!(one_column.to_plain(), "will\ncoil\n");
+        assert_eq!(WordlistSource::parse(WordlistFormat::Csv, "\"wi\tll\",900\n"), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        let plain = WordlistSource::parse(WordlistFormat::Plain, "will\t900\ncoil\n").unwrap();
+        assert_eq!(WordlistSource::parse(WordlistFormat::Json, r#"["will\ncoil"]"#), None);
+        let json = r#"[{"word": "will", "frequency": 900}, {"word": "coil"}]"#;
+        assert_eq!(WordlistSource::parse_detected(json), Some(plain));
+        assert_eq!(WordlistSource::parse_detected(r#"["will", "coil"]"#).unwrap().len(), 2);
+        let object = WordlistSource::parse_detected(r#"{"will": 900, "coil": 2}"#).unwrap();
+        assert_eq!(object.to_plain(), "will\t900\ncoil\t2\n");
+        assert_eq!(WordlistSource::parse(WordlistFormat::Json, "[\"will\", 3]"), None);
+        assert_eq!(WordlistSource::parse(WordlistFormat::Json, "[\"will\""), None);
+        assert_eq!(WordlistSource::parse(WordlistFormat::Json, &"[".repeat(100_000)), None);
+    }
+}
@@ -1,48 +1,235 @@
 //! This module provides the `SpellingBeeGame` struct, which stores previously-entered words, checks
 //! words for validity, and scores them appropriately.
 
-use std::collections::BTreeSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::str;
 use lexi::{Lexicon, VecLexicon, wordlist};
+use unicode_normalization::UnicodeNormalization;
 use wasm_bindgen::prelude::*;
-use crate::utils::set_panic_hook;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::utils::{set_panic_hook, is_letter, js_array, letters, letter_count, fold_case, now,
+                   Rng};
+use crate::hive::HiveLayout;
+use crate::report::{Report, ReportWord};
+use crate::printable::Sheet;
+use crate::i18n::{Messages, FALLBACK_LOCALE};
+use crate::translit::{Transliteration, without_accents};
+use crate::alphabet::Alphabet;
+use crate::worddata::WordData;
+use crate::definitions::{Definitions, JsDefinitions};
+use crate::source::WordlistSource;
+use crate::answers::AnswerSet;
+use crate::accuracy::Accuracy;
+use crate::puzzle::PuzzleDef;
+use crate::code::PuzzleCode;
+use crate::config::GameConfig;
+use crate::rank::Rank;
+use crate::scoring::ScoringRules;
+use crate::lexicon::LexiconHandle;
+use crate::dawg::Dawg;
+use crate::hints::{easiest_first, two_letter_list, Hint, HintCosts, HintGrid, HintKind,
+                   HintPurchase};
+use crate::diagnostics::{string_bytes, GameDiagnostics};
+use crate::save::SaveState;
+use crate::generate::{daily_config, PuzzleGenerator, RandomConfig};
 
-use web_sys;
-
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
-macro_rules! log {
-    ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
-    }
-}
-
-/// The minimum length of a play.
+/// The number of optional letters in a puzzle.
+pub const OPTIONAL_LETTERS: usize = 6;
+/// The minimum length of a play, unless the game's `ScoringRules` say
+/// otherwise.
 pub const MIN_LENGTH: usize = 4;
 /// The bonus for playing a pangram.
 pub const PANGRAM_BONUS: usize = 7;
+/// The number of most recent words that can be taken back with `undo()`.
+pub const UNDO_HISTORY: usize = 10;
+/// The source name of answers from the lexicon a game was created with.
+pub const MAIN_SOURCE: &str = "main";
+/// A small list of common English words, one per line, used when a game is created without a main
+/// word list. See `uses_fallback_dictionary()`.
+pub const FALLBACK_WORDS: &str = include_str!("fallback.txt");
+
+/// The bit of a letter mask for the required letter. See `letter_mask()`.
+const REQUIRED_BIT: u32 = 1;
+/// The bit of a letter mask for letters that aren't puzzle letters.
+const OTHER_BIT: u32 = 1 << 31;
+/// The most optional letters with their own bit in a letter mask.
+const MASK_LETTERS: usize = 30;
 
 /// A game of the NYT Spelling Bee, with six optional letters and a required one. Lets users play
 /// words and check them for validity, keeping track of the score.
 #[wasm_bindgen]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SpellingBeeGame {
     /// The letters that may be used, but don't have to be. Kept in sorted order so as to avoid
-    /// revealing any information when shown to the user.
-    optional_letters: BTreeSet<char>,
+    /// revealing any information when shown to the user. Each letter is a grapheme cluster (see
+    /// `utils::letters()`).
+    optional_letters: BTreeSet<String>,
 
     /// The required letter.
-    required_letter: char,
+    required_letter: String,
 
     /// The current score. Check the `score()` method for more information about how this is
     /// computed.
     score: usize,
 
     /// The currently played words.
-    played_so_far: BTreeSet<String>,
+    played_so_far: AnswerSet,
+
+    /// The same words, in the order they were played.
+    play_order: Vec<String>,
+
+    /// The number of words at the end of `play_order` that `undo()` can still
+    /// take back, up to `UNDO_HISTORY`.
+    undoable: usize,
+
+    /// The number of valid plays since the last play that wasn't valid.
+    streak: usize,
+
+    /// The longest streak so far.
+    best_streak: usize,
 
     /// The valid words accepted by the game.
-    words: BTreeSet<String>,
+    words: AnswerSet,
+
+    /// The swears that are accepted without being answers. See
+    /// `accept_swears()`.
+    unlisted: BTreeSet<String>,
+
+    /// The words in `unlisted` that have been played.
+    unlisted_found: BTreeSet<String>,
+
+    /// For answers in any overlays (see `add_overlay()`), the names of their
+    /// sources, in the order they were added, starting with `MAIN_SOURCE` if
+    /// the game was created with them.
+    sources: BTreeMap<String, Vec<String>>,
+
+    /// The layout of the honeycomb, if words have to trace a path of adjacent cells.
+    layout: Option<HiveLayout>,
+
+    /// The ASCII spellings players may type for accented letters, if any.
+    transliteration: Option<Transliteration>,
+
+    /// The alphabet the letters were checked against, if the game was created
+    /// with one. With accent folding on, words are checked against the
+    /// letters by the base letters of their accented forms.
+    alphabet: Option<Alphabet>,
+
+    /// The locale whose case rules are used to lowercase letters, words, and
+    /// input. See `utils::fold_case()`.
+    locale: String,
+
+    /// Definitions and frequencies, loaded separately from the answers.
+    word_data: WordData,
+
+    /// The JavaScript function definitions are looked up with before
+    /// `word_data`, if one has been set. It isn't saved.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    definition_provider: Option<JsDefinitions>,
+
+    /// Whether the answers came from `FALLBACK_WORDS` because no usable main word list was given.
+    fallback_dictionary: bool,
+
+    /// The number of words that had been found when the answers were
+    /// revealed with `reveal()`, if they have been.
+    revealed_after: Option<usize>,
+
+    /// Whether the game has been ended with `finish()`, so that no more words
+    /// can be played.
+    finished: bool,
+
+    /// Whether input is lowercased and trimmed before it's checked. See
+    /// `new_with_normalization()`.
+    normalize_input: bool,
+
+    /// The points each answer is worth.
+    scoring: ScoringRules,
+
+    /// The score with every answer, kept up to date by `update_totals()`
+    /// whenever the answers or scoring change.
+    max_score: usize,
+
+    /// The answers that are pangrams, kept up to date by `update_totals()`
+    /// whenever the answers change.
+    pangrams: AnswerSet,
+
+    /// The letter mask of each answer, in the same order as the answers,
+    /// kept up to date by `update_totals()`. See `letter_mask()`.
+    answer_masks: Vec<u32>,
+
+    /// The time the clock started, in milliseconds since the Unix epoch, or
+    /// `None` if it hasn't. See `play_at()`.
+    started_at: Option<u64>,
+
+    /// The milliseconds from the start each word in `play_order` was found
+    /// at.
+    play_times: Vec<u64>,
+
+    /// The points each kind of hint costs to buy.
+    hint_costs: HintCosts,
+
+    /// The hints bought, in the order they were bought.
+    hint_ledger: Vec<HintPurchase>,
+}
+
+/// The ways creating a game with `try_new()` can fail.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameError {
+    /// The main word list is empty.
+    MissingWordList,
+
+    /// The word lists couldn't be parsed.
+    BadWordList,
+
+    /// There aren't six optional letters and one required letter. Holds the
+    /// numbers of optional and required letters given.
+    WrongLetterCount { optional: usize, required: usize },
+
+    /// An optional letter appears more than once.
+    DuplicateLetter(String),
+
+    /// The required letter is also one of the optional letters.
+    RequiredLetterIsOptional(String),
+
+    /// A letter isn't one of the puzzle letters of the game's alphabet.
+    NotInAlphabet(String),
+
+    /// Something given as a letter isn't one, like a digit or punctuation.
+    NotALetter(String),
+
+    /// A honeycomb layout isn't an ordering of the optional letters. Holds
+    /// the layout given.
+    BadLayout(String),
 }
 
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::MissingWordList => write!(f, "the main word list is empty"),
+            GameError::BadWordList => write!(f, "the word lists couldn't be parsed"),
+            GameError::WrongLetterCount { optional, required } => {
+                write!(f, "expected {} optional letters and 1 required letter, not {} and {}",
+                       OPTIONAL_LETTERS, optional, required)
+            }
+            GameError::DuplicateLetter(l) => write!(f, "the letter {} appears twice", l),
+            GameError::RequiredLetterIsOptional(l) => {
+                write!(f, "the required letter {} is also an optional letter", l)
+            }
+            GameError::NotInAlphabet(l) => write!(f, "{} isn't in the alphabet", l),
+            GameError::NotALetter(l) => write!(f, "{} isn't a letter", l),
+            GameError::BadLayout(l) => {
+                write!(f, "the layout {} isn't an ordering of the optional letters", l)
+            }
+        }
+    }
+}
+
+impl Error for GameError {}
+
 /// The possible outcomes of playing a move.
 #[wasm_bindgen]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -57,149 +244,2499 @@ pub enum PlayResult {
     /// The word is not in the lexicon.
     InvalidWord,
 
-    /// The word is less than `MIN_LENGTH` (four) letters.
+    /// The word is shorter than the minimum length, which is `MIN_LENGTH`
+    /// (four) letters unless the game's `ScoringRules` say otherwise.
     InvalidLength,
 
     /// The word doesn't use the required letter or has letters that are not
     /// allowed.
     InvalidLetters,
+
+    /// The game has a hive layout, and some consecutive letters of the word
+    /// aren't in adjacent cells.
+    InvalidPath,
+
+    /// The word has something that isn't a letter at all, like a digit,
+    /// emoji, or punctuation. Use `invalid_character()` to find out what.
+    InvalidCharacter,
+
+    /// The game has been ended with `SpellingBeeGame::finish()`, so no more
+    /// words can be played.
+    Finished,
+
+    /// The word is a swear accepted with `SpellingBeeGame::accept_swears()`:
+    /// it's been noted as found, but it isn't an answer, so it earns no
+    /// points.
+    AcceptedUnlisted,
+}
+
+/// Everything about a play the frontend needs to show it, from
+/// `SpellingBeeGame::play_outcome()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayOutcome {
+    /// The result of the play.
+    result: PlayResult,
+
+    /// The word as the game understood it: lowercased, and spelled as the
+    /// answer it matched if transliteration is on.
+    word: String,
+
+    /// The points the play earned, including any pangram bonus.
+    points: usize,
+
+    /// Whether the play was a new pangram.
+    pangram: bool,
+
+    /// The score after the play.
+    total: usize,
+
+    /// The rank after the play.
+    rank: Rank,
+
+    /// The definition of the word, if the play was valid and it has one.
+    definition: Option<String>,
+}
+
+#[wasm_bindgen]
+impl PlayOutcome {
+    /// Returns the result of the play.
+    pub fn result(&self) -> PlayResult {
+        self.result
+    }
+
+    /// Returns the word as the game understood it: lowercased, and spelled as
+    /// the answer it matched if transliteration is on.
+    pub fn word(&self) -> String {
+        self.word.clone()
+    }
+
+    /// Returns the points the play earned, including any pangram bonus, or 0
+    /// if it wasn't valid.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Returns `true` if the play was a new pangram.
+    pub fn is_pangram(&self) -> bool {
+        self.pangram
+    }
+
+    /// Returns the score after the play.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the rank after the play.
+    pub fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    /// Returns the definition of the word, if the play was valid and it has
+    /// one (see `SpellingBeeGame::definition()`).
+    pub fn definition(&self) -> Option<String> {
+        self.definition.clone()
+    }
+}
+
+/// What merging another game's progress into a game did, from
+/// `SpellingBeeGame::merge()`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MergeReport {
+    /// Whether the two games had the same letters. If they didn't, nothing
+    /// was merged.
+    same_letters: bool,
+
+    /// The words the other game found that this one hadn't, in alphabetical
+    /// order.
+    from_other: Vec<String>,
+
+    /// The words this game found that the other one hadn't, in alphabetical
+    /// order.
+    from_self: Vec<String>,
+
+    /// The points the other game's words added.
+    points: usize,
+
+    /// The score after merging.
+    total: usize,
+}
+
+#[wasm_bindgen]
+impl MergeReport {
+    /// Returns `true` if the two games had the same letters, and so were
+    /// merged.
+    pub fn same_letters(&self) -> bool {
+        self.same_letters
+    }
+
+    /// Returns the words the other game found that this one hadn't, as an
+    /// array of strings in alphabetical order.
+    pub fn words_from_other(&self) -> JsValue {
+        js_array(&self.from_other)
+    }
+
+    /// Returns the words this game found that the other one hadn't, as an
+    /// array of strings in alphabetical order.
+    pub fn words_from_self(&self) -> JsValue {
+        js_array(&self.from_self)
+    }
+
+    /// Returns the points the other game's words added.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Returns the score after merging.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+impl MergeReport {
+    /// Returns the words the other game found that this one hadn't, in
+    /// alphabetical order.
+    pub fn from_other(&self) -> &[String] {
+        &self.from_other
+    }
+
+    /// Returns the words this game found that the other one hadn't, in
+    /// alphabetical order.
+    pub fn from_self(&self) -> &[String] {
+        &self.from_self
+    }
 }
 
 #[wasm_bindgen]
 impl SpellingBeeGame {
     /// Creates a new spelling bee game from a set of optional letters and a
     /// single required letter, using the given input buffers for lexicons.
-    pub fn new(optional_letters: &str, required_letter: char, main_words: &str,
+    /// Lines of the main word list can give how often the word occurs after
+    /// a tab, which is loaded for the answers like `load_frequencies()`.
+    pub fn new(optional_letters: &str, required_letter: &str, main_words: &str,
                swears: &str) -> SpellingBeeGame
+    {
+        SpellingBeeGame::new_in(FALLBACK_LOCALE, optional_letters, required_letter,
+                                main_words, swears)
+    }
+
+    /// Creates a new game like `new()`, lowercasing the letters, lexicons, and
+    /// played words with the case rules of the given locale, such as `tr` for
+    /// Turkish, where `I` is the capital of `ı` rather than `i`.
+    pub fn new_in(locale: &str, optional_letters: &str, required_letter: &str,
+                  main_words: &str, swears: &str) -> SpellingBeeGame
+    {
+        SpellingBeeGame::new_scored(locale, optional_letters, required_letter, main_words,
+                                    swears, &ScoringRules::default())
+    }
+
+    /// Creates a new game like `new()`, but checks the letters and word lists
+    /// first instead of making do with whatever it's given: see
+    /// `try_new_in()` for what's checked. The error is thrown in JavaScript.
+    pub fn try_new(optional_letters: &str, required_letter: &str, main_words: &str,
+                   swears: &str) -> Result<SpellingBeeGame, JsError>
+    {
+        Ok(SpellingBeeGame::try_new_in(FALLBACK_LOCALE, optional_letters, required_letter,
+                                       main_words, swears)?)
+    }
+
+    /// Creates a new game like `try_new()` for a language other than English,
+    /// checking the letters against the given alphabet and lowercasing with
+    /// its language's case rules. With accent folding on in the alphabet,
+    /// words with accented forms of the letters are answers too, and can be
+    /// typed without the accents. The error is thrown in JavaScript.
+    pub fn new_with_alphabet(optional_letters: &str, required_letter: &str, main_words: &str,
+                             swears: &str, alphabet: &Alphabet) -> Result<SpellingBeeGame, JsError>
+    {
+        Ok(SpellingBeeGame::try_new_with_alphabet(optional_letters, required_letter, main_words,
+                                                  swears, alphabet)?)
+    }
+
+    /// Creates a new game like `new()` from word lists that have already been
+    /// parsed, so that building several games only parses them once. The game
+    /// uses the lexicon's locale.
+    pub fn from_lexicon(optional_letters: &str, required_letter: &str,
+                        lexicon: &LexiconHandle) -> SpellingBeeGame
     {
         set_panic_hook();
-        let mut lex: VecLexicon = wordlist::parse_strings(main_words,
-                                                          swears).unwrap().into();
+        let locale = &lexicon.locale();
+        let optional_letters = &fold_case(optional_letters.trim(), locale);
+        let required_letter = &fold_case(required_letter.trim(), locale);
+        let mut game = SpellingBeeGame::from_words(locale, optional_letters, required_letter,
+                                                   lexicon.words(), &ScoringRules::default());
+        game.fallback_dictionary = lexicon.uses_fallback_dictionary();
+        game
+    }
 
-        let mut allowed_letters: String = required_letter.to_string();
-        allowed_letters.push_str(optional_letters);
-        lex.only_using_letters(allowed_letters.chars());
-        lex.with_letter(required_letter);
-        lex.with_more_length(MIN_LENGTH-1);
+    /// Creates a new game like `new()` from a dictionary compiled by the
+    /// `spellingbee-dawg` tool (see `dawg`), which is much smaller to download
+    /// than the word lists and quicker to build a game from. Returns `None` if
+    /// the bytes aren't a compiled dictionary.
+    pub fn from_dawg(optional_letters: &str, required_letter: &str,
+                     dawg: &[u8]) -> Option<SpellingBeeGame>
+    {
+        set_panic_hook();
+        let dawg = Dawg::from_bytes(dawg)?;
+        let optional_letters = &fold_case(optional_letters.trim(), FALLBACK_LOCALE);
+        let required_letter = &fold_case(required_letter.trim(), FALLBACK_LOCALE);
+        let allowed: Vec<char> = optional_letters.chars().chain(required_letter.chars()).collect();
+        let words = dawg.words_using(|c| allowed.contains(&c));
+        Some(SpellingBeeGame::from_words(FALLBACK_LOCALE, optional_letters, required_letter,
+                                         words, &ScoringRules::default()))
+    }
+
+    /// Creates a new game whose answers are exactly the given words, one per
+    /// line, without checking them against the letters or a lexicon. This is
+    /// for puzzles whose answers are already known, like official ones.
+    pub fn from_answers(optional_letters: &str, required_letter: &str,
+                        answers: &str) -> SpellingBeeGame
+    {
+        set_panic_hook();
+        let fold = |text: &str| fold_case(text, FALLBACK_LOCALE);
+        let answers = answers.lines()
+            .map(|w| fold(w.trim()))
+            .filter(|w| !w.is_empty())
+            .collect();
+        SpellingBeeGame::with_answers(FALLBACK_LOCALE, &fold(optional_letters),
+                                      &fold(required_letter), answers)
+    }
+
+    /// Creates a new game with random letters from the given lexicons, like
+    /// `random()` with the default constraints and the given seed. Returns
+    /// `None` if no puzzle meets them.
+    pub fn new_random(main_words: &str, swears: &str, seed: u32) -> Option<SpellingBeeGame> {
+        let config = RandomConfig { seed: u64::from(seed), ..RandomConfig::default() };
+        SpellingBeeGame::random(main_words, swears, &config)
+    }
 
-        log!("{:?}", lex);
+    /// Creates the puzzle for the given `YYYY-MM-DD` date from the given
+    /// lexicons, like `random()` with a seed derived from the date (see
+    /// `generate::daily_config()`), so that everyone with the same lexicons
+    /// gets the same puzzle on the same day without needing a server. The
+    /// ids from `next_puzzle_at()` are dates in this format. Returns `None` if
+    /// the date isn't one, or if no puzzle meets the constraints.
+    pub fn daily(date: &str, main_words: &str, swears: &str) -> Option<SpellingBeeGame> {
+        SpellingBeeGame::random(main_words, swears, &daily_config(date)?)
+    }
 
-        SpellingBeeGame {
-            optional_letters: optional_letters.chars().collect(),
-            required_letter,
-            score: 0,
-            played_so_far: BTreeSet::new(),
-            words: lex.into_iter().collect()
+    /// Creates a new game like `new_in()`, choosing whether input is
+    /// normalized. Normally, input is lowercased with the case rules of the
+    /// given locale and trimmed of surrounding whitespace before it's checked,
+    /// so " WILL " plays "will"; without normalization, it has to match an
+    /// answer exactly. The word lists are normalized either way.
+    pub fn new_with_normalization(locale: &str, optional_letters: &str, required_letter: &str,
+                                  main_words: &str, swears: &str,
+                                  normalize: bool) -> SpellingBeeGame
+    {
+        let mut game = SpellingBeeGame::new_in(locale, optional_letters, required_letter,
+                                               main_words, swears);
+        game.normalize_input = normalize;
+        game
+    }
+
+    /// Creates a new game like `new()`, from lexicons given as UTF-8 bytes,
+    /// such as a `Uint8Array` straight from a fetched `ArrayBuffer`, which
+    /// saves decoding them to strings in JavaScript. Fails with
+    /// `GameError::BadWordList` if either isn't valid UTF-8 (see
+    /// `try_new_from_bytes()`); the error is thrown in JavaScript.
+    pub fn new_from_bytes(optional_letters: &str, required_letter: &str,
+                          main_words: &[u8], swears: &[u8]) -> Result<SpellingBeeGame, JsError>
+    {
+        Ok(SpellingBeeGame::try_new_from_bytes(optional_letters, required_letter, main_words,
+                                               swears)?)
+    }
+
+    /// Creates a new game like `new()` from a main word list in any of the
+    /// formats `WordlistSource` parses, with any frequencies it gives.
+    pub fn from_source(optional_letters: &str, required_letter: &str,
+                       main_words: &WordlistSource, swears: &str) -> SpellingBeeGame
+    {
+        SpellingBeeGame::new(optional_letters, required_letter, &main_words.to_plain(), swears)
+    }
+
+    /// Creates a new game like `new()`, scored by the given rules instead of
+    /// the official ones. The maximum score and rank thresholds follow the
+    /// same rules, and answers can be as short as the rules' minimum length.
+    pub fn new_with_scoring(optional_letters: &str, required_letter: &str, main_words: &str,
+                            swears: &str, scoring: &ScoringRules) -> SpellingBeeGame
+    {
+        SpellingBeeGame::new_scored(FALLBACK_LOCALE, optional_letters, required_letter,
+                                    main_words, swears, scoring)
+    }
+
+    /// Creates a new game like `new()`, except that consecutive letters of a
+    /// word must be in adjacent cells of the honeycomb. The layout gives the
+    /// optional letters in order around the ring: each touches the letters
+    /// beside it and the required letter in the center. Fails with
+    /// `GameError::BadLayout` if the layout isn't an ordering of the optional
+    /// letters (see `try_new_with_layout()`); the error is thrown in
+    /// JavaScript.
+    pub fn new_with_layout(optional_letters: &str, required_letter: &str,
+                           layout: &str, main_words: &str,
+                           swears: &str) -> Result<SpellingBeeGame, JsError>
+    {
+        Ok(SpellingBeeGame::try_new_with_layout(optional_letters, required_letter, layout,
+                                                main_words, swears)?)
+    }
+
+    /// Saves the puzzle and the progress made on it in a compact binary
+    /// format (see the `save` module), without the answers, which
+    /// `load_bytes()` rebuilds from the word lists. Definitions, frequencies,
+    /// overlays, and transliteration aren't saved.
+    pub fn save_bytes(&self) -> Vec<u8> {
+        SaveState {
+            locale: self.locale.clone(),
+            optional_letters: self.optional_letters(),
+            required_letter: self.required_letter.clone(),
+            layout: self.layout.as_ref().map(|l| l.ring().concat()).unwrap_or_default(),
+            score: self.score,
+            streak: self.streak,
+            best_streak: self.best_streak,
+            started_at: self.started_at,
+            played: self.play_order.clone(),
+            play_times: self.play_times.clone(),
+            hints: self.hint_ledger.clone(),
+            revealed_after: self.revealed_after,
+            finished: self.finished,
+        }.encode()
+    }
+
+    /// Restores a game saved by `save_bytes()`, rebuilding the answers from
+    /// the given lexicons as in `new()`. The most recent words can be taken
+    /// back with `undo()` as usual. Returns `None` if the bytes aren't a
+    /// save, or if the found words or score don't hold up against the
+    /// lexicons, as when they're a different dictionary than the save was
+    /// made with.
+    pub fn load_bytes(bytes: &[u8], main_words: &str,
+                      swears: &str) -> Option<SpellingBeeGame>
+    {
+        SpellingBeeGame::load_bytes_with_scoring(bytes, main_words, swears,
+                                                 &ScoringRules::default())
+    }
+
+    /// Restores a game saved by `save_bytes()` like `load_bytes()`, for a
+    /// game that was scored by the given rules. Saves don't record the rules,
+    /// so they have to be the same ones the game was played with.
+    pub fn load_bytes_with_scoring(bytes: &[u8], main_words: &str, swears: &str,
+                                   scoring: &ScoringRules) -> Option<SpellingBeeGame>
+    {
+        let save = SaveState::decode(bytes)?;
+        SpellingBeeGame::new_scored(&save.locale, &save.optional_letters, &save.required_letter,
+                                    main_words, swears, scoring)
+            .restore(save)
+    }
+
+    /// Restores a game saved by `save_bytes()` like `load_bytes()`, rebuilding
+    /// the answers from word lists that have already been parsed, as in
+    /// `from_lexicon()`. Returns `None` as well if the save was made in a
+    /// different locale than the lexicon's.
+    pub fn load_bytes_from_lexicon(bytes: &[u8],
+                                   lexicon: &LexiconHandle) -> Option<SpellingBeeGame>
+    {
+        let save = SaveState::decode(bytes)?;
+        if save.locale != lexicon.locale() {
+            return None;
+        }
+        SpellingBeeGame::from_lexicon(&save.optional_letters, &save.required_letter, lexicon)
+            .restore(save)
+    }
+
+    /// Returns `true` if the game was created without a usable main word list, so that its
+    /// answers come from the small built-in list in `FALLBACK_WORDS` instead. Frontends should
+    /// warn the player that the puzzle may be missing answers.
+    pub fn uses_fallback_dictionary(&self) -> bool {
+        self.fallback_dictionary
+    }
+
+    /// Removes words in the given swears list, with one word per line, from
+    /// the answers, for games created before the list was available. Words
+    /// that have already been played are kept. Returns the number of answers
+    /// removed.
+    pub fn load_swears(&mut self, swears: &str) -> usize {
+        let swears: BTreeSet<String> = swears.lines()
+            .map(|w| fold_case(w.trim(), &self.locale))
+            .collect();
+        let before = self.words.len();
+        let played = &self.played_so_far;
+        self.words.retain(|w| !swears.contains(w) || played.contains(w));
+        self.update_totals();
+        before - self.words.len()
+    }
+
+    /// Accepts the words in the given swears list, with one word per line,
+    /// that fit the puzzle, the way the official game does: playing one gives
+    /// `AcceptedUnlisted` instead of `InvalidWord`, but it isn't an answer, so
+    /// it earns no points and doesn't count towards the maximum score, the
+    /// ranks, or the hints. Swears that are answers stop being answers,
+    /// unless they've been played. Returns the number of words accepted. The
+    /// accepted words, and which of them have been played, aren't saved.
+    pub fn accept_swears(&mut self, swears: &str) -> usize {
+        let swears: BTreeSet<String> = swears.lines()
+            .map(|w| fold_case(w.trim(), &self.locale))
+            .filter(|w| {
+                letter_count(w) >= self.scoring.min_length() && self.has_valid_letters(w) &&
+                    self.has_valid_path(w) && !self.played_so_far.contains(w)
+            })
+            .collect();
+        self.words.retain(|w| !swears.contains(w));
+        self.update_totals();
+        let count = swears.len();
+        self.unlisted.extend(swears);
+        count
+    }
+
+    /// Returns the swears accepted with `accept_swears()` that have been
+    /// played, as an array of strings in alphabetical order.
+    pub fn unlisted_words(&self) -> JsValue {
+        js_array(&self.unlisted_found)
+    }
+
+    /// Adds the words in another word list, one per line, as answers, tagging
+    /// them with the given source name so that `word_source()` can say where
+    /// an answer came from. Words that aren't valid for the puzzle are
+    /// skipped. Returns the number of new answers.
+    pub fn add_overlay(&mut self, source: &str, words: &str) -> usize {
+        let words: BTreeSet<String> = words.lines()
+            .map(|w| fold_case(w.trim(), &self.locale))
+            .filter(|w| {
+                letter_count(w) >= self.scoring.min_length() && self.has_valid_letters(w) &&
+                    self.has_valid_path(w)
+            })
+            .collect();
+
+        for word in &words {
+            let is_answer = self.words.contains(word);
+            let sources = self.sources.entry(word.clone()).or_insert_with(|| {
+                if is_answer { vec![MAIN_SOURCE.to_string()] } else { Vec::new() }
+            });
+            if !sources.iter().any(|s| s == source) {
+                sources.push(source.to_string());
+            }
+        }
+        let before = self.words.len();
+        self.words.extend(words);
+        self.update_totals();
+        self.words.len() - before
+    }
+
+    /// Returns the names of the sources an answer came from, separated by
+    /// commas, such as `main` or `main,community`, or `None` if the word isn't
+    /// an answer.
+    pub fn word_source(&self, word: &str) -> Option<String> {
+        let word = fold_case(word, &self.locale);
+        if !self.words.contains(&word) {
+            return None;
         }
+        Some(self.sources.get(&word).map_or(MAIN_SOURCE.to_string(), |s| s.join(",")))
+    }
+
+    /// Loads definitions, with one word, a tab, and its definition per line.
+    /// Returns the number of definitions loaded.
+    pub fn load_definitions(&mut self, definitions: &str) -> usize {
+        self.word_data.load_definitions(definitions, &self.locale)
+    }
+
+    /// Loads frequency data, with one word, a tab, and how often it occurs
+    /// per line. Returns the number of frequencies loaded. With a rarity
+    /// bonus in the scoring rules, the score and maximum score are worked out
+    /// again with the new data.
+    pub fn load_frequencies(&mut self, frequencies: &str) -> usize {
+        let count = self.word_data.load_frequencies(frequencies, &self.locale);
+        self.rescore();
+        count
+    }
+
+    /// Looks definitions up with the given JavaScript function, which takes a
+    /// word and returns its definition as a string, or anything else if it
+    /// doesn't have one. Words it doesn't define are looked up in the
+    /// definitions loaded with `load_definitions()`.
+    pub fn set_definition_provider(&mut self, provider: js_sys::Function) {
+        self.definition_provider = Some(JsDefinitions::new(provider));
+    }
+
+    /// Stops looking definitions up with the function from
+    /// `set_definition_provider()`.
+    pub fn clear_definition_provider(&mut self) {
+        self.definition_provider = None;
+    }
+
+    /// Returns the definition of a word from the definition provider or the
+    /// loaded definitions, if either has it.
+    pub fn definition(&self, word: &str) -> Option<String> {
+        self.define(&fold_case(word, &self.locale))
+    }
+
+    /// Returns how often a word occurs, if frequency data has been loaded and
+    /// includes it.
+    pub fn frequency(&self, word: &str) -> Option<f64> {
+        self.word_data.frequency(&fold_case(word, &self.locale)).map(|n| n as f64)
     }
 
     /// Returns the current score.
     ///
-    /// Score is computed as follows: a four-letter word is worth one point. Any
-    /// word longer than that (words shorter than four letters are not allowed)
-    /// is worth a point for every letter it has. If the word uses all of the
-    /// given letters, it receives an additional seven points.
+    /// Score is computed by the game's `ScoringRules`. Under the official ones,
+    /// a four-letter word is worth one point. Any word longer than that (words
+    /// shorter than the minimum length, four letters unless the rules say
+    /// otherwise, are not allowed) is worth a point for every letter it has. If
+    /// the word uses all of the given letters, it receives an additional seven
+    /// points. Points spent on hints aren't taken off; see `net_score()`.
     pub fn score(&self) -> usize {
         self.score
     }
 
+    /// Returns the score less the points spent on hints (see
+    /// `set_hint_costs()`), down to zero. This is what hints are bought with;
+    /// the rank is still earned by `score()`, so that buying hints doesn't put
+    /// Queen Bee out of reach.
+    pub fn net_score(&self) -> usize {
+        self.score.saturating_sub(self.hint_points_spent())
+    }
+
+    /// Lets players type ASCII spellings of accented letters, using the
+    /// built-in table for the given language code (such as `fr` or `de`), so
+    /// that "cafe" is accepted for "café". Returns `false`, leaving the game
+    /// unchanged, if there's no built-in table for the language.
+    pub fn set_transliteration(&mut self, language: &str) -> bool {
+        match Transliteration::for_language(language) {
+            Some(table) => {
+                self.transliteration = Some(table);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Lets players type ASCII spellings of accented letters like
+    /// `set_transliteration()`, using a custom table with one letter, a tab,
+    /// and its ASCII spelling per line.
+    pub fn set_transliteration_table(&mut self, table: &str) {
+        self.transliteration = Some(Transliteration::from_table(table));
+    }
+
+    /// Sets whether inputs that only differ from an answer in their accents
+    /// are accepted for it, in either direction: "cafe" for "café", and
+    /// "café" for "cafe". Either way, the answer's own spelling is the one
+    /// that's played. A table set before with `set_transliteration()` or
+    /// `set_transliteration_table()` is kept, and its spellings are used for
+    /// the letters in it.
+    ///
+    /// Answers with accented forms of the puzzle letters, like "café" in a
+    /// puzzle with e, count as having the puzzle letters. The lexicons given
+    /// to `new()` only give answers with the letters themselves, though, so
+    /// those come from `from_answers()` or an alphabet that folds accents (see
+    /// `new_with_alphabet()`).
+    pub fn set_accent_folding(&mut self, fold: bool) {
+        let mut table = self.transliteration.take()
+            .unwrap_or_else(Transliteration::accent_folding);
+        table.set_strip_accents(fold);
+        if !table.is_empty() {
+            self.transliteration = Some(table);
+        }
+    }
+
+    /// Returns `true` if inputs that only differ from an answer in their
+    /// accents are accepted for it.
+    pub fn accent_folding(&self) -> bool {
+        self.transliteration.as_ref().is_some_and(|t| t.strips_accents())
+    }
+
     /// Accepts a given word, updating internal state and returning the result
-    /// of the play.
+    /// of the play. The word is timed by the system clock; see `play_at()`.
     pub fn play(&mut self, word: &str) -> PlayResult {
-        if word.len() < MIN_LENGTH {
-            PlayResult::InvalidLength
-        } else if !self.has_valid_letters(word) {
-            PlayResult::InvalidLetters
-        } else if !self.is_valid_word(word) {
-            PlayResult::InvalidWord
-        } else if self.played_so_far.contains(word) {
-            PlayResult::AlreadyPlayed
-        } else {
-            self.played_so_far.insert(word.to_string());
-            self.score += self.score_word(word);
-            PlayResult::Valid
-        }
+        self.play_at(word, now())
     }
 
-    /// Checks if the given input is valid, in that it only consists of allowed
-    /// letters.
-    pub fn is_valid_partial_input(&self, word: &str) -> bool {
-        word.chars().all(|c| self.optional_letters.contains(&c) ||
-                         c == self.required_letter)
+    /// Plays a word like `play()` at the given time, in milliseconds since
+    /// the Unix epoch, as from `Date.now()`, for frontends that keep their own
+    /// clock. The clock starts with the first play, valid or not, unless
+    /// `start_clock()` started it earlier.
+    pub fn play_at(&mut self, word: &str, now: f64) -> PlayResult {
+        if self.finished {
+            return PlayResult::Finished;
+        }
+        let now = now as u64;
+        let start = *self.started_at.get_or_insert(now);
+        let result = self.play_word(word, now.saturating_sub(start));
+        match result {
+            PlayResult::Valid => {
+                self.undoable = (self.undoable + 1).min(UNDO_HISTORY);
+                self.streak += 1;
+                self.best_streak = self.best_streak.max(self.streak);
+            }
+            // A swear neither adds to the streak nor breaks it.
+            PlayResult::AcceptedUnlisted => {}
+            _ => self.streak = 0,
+        }
+        result
     }
 
-    /// Checks if the given word has only the allowed letters and includes the
-    /// required letter.
-    fn has_valid_letters(&self, word: &str) -> bool {
-        word.contains(self.required_letter) &&
-            word.chars().all(|c| self.optional_letters.contains(&c) ||
-                             c == self.required_letter)
+    /// Plays a word like `play()`, returning everything the frontend needs to
+    /// show the play, such as the points it earned, so that scoring doesn't
+    /// have to be duplicated in JavaScript.
+    pub fn play_outcome(&mut self, word: &str) -> PlayOutcome {
+        let resolved = self.resolve_input(&self.normalize(word));
+        let before = self.score;
+        let result = self.play(word);
+        let valid = result == PlayResult::Valid;
+        PlayOutcome {
+            result,
+            pangram: valid && self.is_pangram(&resolved),
+            definition: if valid { self.define(&resolved) } else { None },
+            word: resolved,
+            points: self.score - before,
+            total: self.score(),
+            rank: self.rank(),
+        }
     }
 
-    /// Checks if the given word is in the answer list.
-    fn is_valid_word(&self, word: &str) -> bool {
-        self.words.contains(word)
+    /// Takes back the most recently played word that's still played,
+    /// subtracting its points and one from the streak, and returns it. Only
+    /// the last `UNDO_HISTORY` words can be taken back; returns `None` once
+    /// there are none left to take back.
+    pub fn undo(&mut self) -> Option<String> {
+        if self.undoable == 0 || self.finished {
+            return None;
+        }
+
+        let word = self.play_order.pop()?;
+        self.play_times.pop();
+        self.undoable -= 1;
+        self.played_so_far.remove(&word);
+        self.score -= self.score_word(&word);
+        self.streak = self.streak.saturating_sub(1);
+        Some(word)
     }
 
-    /// Computes the score for a word. See the `score()` function for more on
-    /// how this is calculated. Returns 0 for invalid words.
-    fn score_word(&self, word: &str) -> usize {
-        let base = if word.len() < MIN_LENGTH {
-            0
-        } else if word.len() == MIN_LENGTH {
-            1
-        } else {
-            word.len()
+    /// Adds the words another game with the same letters has found to this
+    /// one, for players solving a puzzle together on separate devices, and
+    /// reports which words each side contributed. Only the other game's
+    /// words that are answers here are added, after the words already
+    /// played, with the time the other game found them at if both clocks
+    /// have started; they count towards the score but not the streak, and
+    /// can't be taken back with `undo()`, nor can anything played before
+    /// them. Games with different letters aren't merged at all, and neither
+    /// is anything into a game that's been ended with `finish()`.
+    pub fn merge(&mut self, other: &SpellingBeeGame) -> MergeReport {
+        let same_letters = self.optional_letters == other.optional_letters &&
+            self.required_letter == other.required_letter;
+        let mut report = MergeReport {
+            same_letters,
+            from_other: Vec::new(),
+            from_self: Vec::new(),
+            points: 0,
+            total: self.score(),
         };
+        if !same_letters || self.finished {
+            return report;
+        }
 
-        if self.is_pangram(word) {
-            base + PANGRAM_BONUS
-        } else {
-            base
+        report.from_self = self.played_so_far.difference(&other.played_so_far)
+            .map(String::from)
+            .collect();
+        for (i, word) in other.play_order.iter().enumerate() {
+            if self.played_so_far.contains(word) || !self.is_valid_word(word) {
+                continue;
+            }
+            let elapsed = match (self.started_at, other.started_at, other.play_times.get(i)) {
+                (Some(start), Some(theirs), Some(&t)) => {
+                    theirs.saturating_add(t).saturating_sub(start)
+                }
+                _ => 0,
+            };
+            self.played_so_far.insert(word);
+            self.play_order.push(word.clone());
+            self.play_times.push(elapsed);
+            report.points += self.score_word(word);
+            report.from_other.push(word.clone());
         }
+        if !report.from_other.is_empty() {
+            self.undoable = 0;
+        }
+        report.from_other.sort_unstable();
+        self.score += report.points;
+        report.total = self.score();
+        report
     }
 
-    /// Returns `true` if this word is both valid and contains every given
-    /// letter and `false` otherwise.
-    pub fn is_pangram(&self, word: &str) -> bool {
-        self.is_valid_word(word) &&
-            word.contains(self.required_letter) &&
-            self.optional_letters.iter().all(|c| word.contains(*c))
+    /// Starts the clock at the given time, in milliseconds since the Unix
+    /// epoch, as when the puzzle is first shown. Does nothing if the clock
+    /// has already started.
+    pub fn start_clock(&mut self, now: f64) {
+        self.started_at.get_or_insert(now as u64);
     }
 
-    /// Returns the required central letter.
-    pub fn required_letter(&self) -> char {
-        self.required_letter
+    /// Returns the milliseconds from the start of the clock to the most
+    /// recent word found, or `None` if the clock hasn't started. Games loaded
+    /// from saves made before games kept time haven't started.
+    pub fn elapsed_time(&self) -> Option<f64> {
+        self.started_at?;
+        Some(self.play_times.iter().max().copied().unwrap_or(0) as f64)
     }
 
-    /// Returns the maximum score with all words.
-    pub fn max_score(&self) -> usize {
-        self.words.iter().map(|w| self.score_word(w)).sum()
+    /// Returns the milliseconds from the start of the clock to the word that
+    /// reached Genius, or `None` if Genius hasn't been reached or the clock
+    /// hasn't started.
+    pub fn time_to_genius(&self) -> Option<f64> {
+        self.started_at?;
+        let threshold = self.rank_threshold(Rank::Genius);
+        let mut score = 0;
+        for (word, &time) in self.play_order.iter().zip(&self.play_times) {
+            score += self.score_word(word);
+            if score >= threshold {
+                return Some(time as f64);
+            }
+        }
+        None
     }
-}
 
+    /// Returns the milliseconds from the start of the clock the given word
+    /// was found at, or `None` if it hasn't been found or the clock hasn't
+    /// started.
+    pub fn time_found(&self, word: &str) -> Option<f64> {
+        self.started_at?;
+        let word = self.resolve_input(&self.normalize(word));
+        let index = self.play_order.iter().position(|w| *w == word)?;
+        Some(self.play_times[index] as f64)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns the milliseconds from the start of the clock each word was
+    /// found at, in the order of `played_words(true)`.
+    pub fn word_times(&self) -> Vec<f64> {
+        self.play_times.iter().map(|&t| t as f64).collect()
+    }
 
-    #[test]
-    fn test_score() {
-        let mut game: SpellingBeeGame = SpellingBeeGame::new("clwgro", 'i');
-        assert_eq!(game.score(), 0);
-        assert_eq!(game.play("will"), PlayResult::Valid);
-        assert_eq!(game.score(), 1);
-        assert_eq!(game.play("cowgirl"), PlayResult::Valid);
-        assert_eq!(game.score(), 15);
-        assert_eq!(game.play("rail"), PlayResult::InvalidLetters);
-        assert_eq!(game.score(), 15);
-        assert_eq!(game.play("roll"), PlayResult::InvalidLetters);
-        assert_eq!(game.score(), 15);
-        assert_eq!(game.play("clwgi"), PlayResult::InvalidWord);
-        assert_eq!(game.score(), 15);
-        assert_eq!(game.play("oil"), PlayResult::InvalidLength);
-        assert_eq!(game.score(), 15);
-        assert_eq!(game.play("cowgirl"), PlayResult::AlreadyPlayed);
-        assert_eq!(game.score(), 15);
+    /// Returns `true` if `undo()` has a word to take back.
+    pub fn can_undo(&self) -> bool {
+        self.undoable > 0 && !self.finished
+    }
+
+    /// Returns what playing the given word would give, without playing it:
+    /// `Valid` means the word would be accepted. Nothing about the game
+    /// changes, including the streak.
+    pub fn check(&self, word: &str) -> PlayResult {
+        self.check_resolved(&self.resolve_input(&self.normalize(word)))
+    }
+
+    /// Returns the number of valid plays in a row since the last one that
+    /// wasn't valid, for any reason.
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    /// Returns the longest streak of valid plays in the game.
+    pub fn best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    /// Checks if the given input is valid, in that it only consists of allowed
+    /// letters.
+    pub fn is_valid_partial_input(&self, word: &str) -> bool {
+        let word = &self.normalize(word);
+        match &self.transliteration {
+            None => self.letter_mask(word) & OTHER_BIT == 0,
+            Some(t) => letters(&self.folded(word)).into_iter().all(|typed| {
+                self.optional_letters.iter().any(|l| t.can_type(l, typed)) ||
+                    t.can_type(&self.required_letter, typed)
+            }),
+        }
+    }
+
+    /// Returns `true` if some answer starts with the given input, so that
+    /// frontends can show as the player types whether it can still become an
+    /// answer. Answers that have been found count too. With transliteration
+    /// on, an ASCII spelling of the start of an answer counts. This works
+    /// whether or not the answers have been revealed (see `reveal()`).
+    pub fn is_prefix_of_answer(&self, partial: &str) -> bool {
+        let partial = &self.normalize(partial);
+        // The answers are sorted, so the ones starting with the input come
+        // right at or after it.
+        let next = self.words.iter_from(partial).next();
+        if next.is_some_and(|w| w.starts_with(partial.as_str())) {
+            return true;
+        }
+
+        match &self.transliteration {
+            None => false,
+            Some(t) => {
+                let folded = t.fold(partial);
+                self.words.iter().any(|w| t.fold(w).starts_with(&folded))
+            }
+        }
+    }
+
+    /// Checks partial input as it's typed, returning `InvalidCharacter` if
+    /// it has something that isn't a letter, `InvalidLetters` if it has
+    /// letters that aren't allowed, and `Valid` otherwise.
+    pub fn check_partial(&self, word: &str) -> PlayResult {
+        if self.invalid_character(word).is_some() {
+            PlayResult::InvalidCharacter
+        } else if !self.is_valid_partial_input(word) {
+            PlayResult::InvalidLetters
+        } else {
+            PlayResult::Valid
+        }
+    }
+
+    /// Returns the first grapheme of the input that isn't a letter, such as
+    /// a whole emoji, or `None` if the input is all letters.
+    pub fn invalid_character(&self, word: &str) -> Option<String> {
+        letters(&self.normalize(word)).into_iter().find(|g| !is_letter(g)).map(String::from)
+    }
+
+    /// Lowercases and trims input, unless input normalization is off. Either
+    /// way, the input is put in normalization form C (see
+    /// `utils::fold_case()`), which doesn't change how it looks.
+    fn normalize(&self, input: &str) -> String {
+        if self.normalize_input {
+            fold_case(input.trim(), &self.locale)
+        } else {
+            input.nfc().collect()
+        }
+    }
+
+    /// Plays a word like `play()`, without updating the streak, recording it
+    /// as found the given milliseconds from the start.
+    fn play_word(&mut self, word: &str, elapsed: u64) -> PlayResult {
+        let word = &self.resolve_input(&self.normalize(word));
+        let result = self.check_resolved(word);
+        if result == PlayResult::Valid {
+            self.played_so_far.insert(word);
+            self.play_order.push(word.to_string());
+            self.play_times.push(elapsed);
+            self.score += self.score_word(word);
+        } else if result == PlayResult::AcceptedUnlisted {
+            self.unlisted_found.insert(word.to_string());
+        }
+        result
+    }
+
+    /// Checks an input that has already been through `resolve_input()`, like
+    /// `check()`.
+    fn check_resolved(&self, word: &str) -> PlayResult {
+        if self.finished {
+            PlayResult::Finished
+        } else if self.invalid_character(word).is_some() {
+            PlayResult::InvalidCharacter
+        } else if letter_count(word) < self.scoring.min_length() {
+            PlayResult::InvalidLength
+        } else if !self.has_valid_letters(word) {
+            PlayResult::InvalidLetters
+        } else if !self.has_valid_path(word) {
+            PlayResult::InvalidPath
+        } else if self.unlisted_found.contains(word) {
+            PlayResult::AlreadyPlayed
+        } else if self.unlisted.contains(word) {
+            PlayResult::AcceptedUnlisted
+        } else if !self.is_valid_word(word) {
+            PlayResult::InvalidWord
+        } else if self.played_so_far.contains(word) {
+            PlayResult::AlreadyPlayed
+        } else {
+            PlayResult::Valid
+        }
+    }
+
+    /// Returns the answer that the given input spells, if transliteration is
+    /// on and the input isn't itself an answer, preferring answers that
+    /// haven't been played. Otherwise, returns the input unchanged.
+    fn resolve_input(&self, word: &str) -> String {
+        let t = match &self.transliteration {
+            Some(t) if !self.words.contains(word) => t,
+            _ => return word.to_string(),
+        };
+
+        let folded = t.fold(word);
+        let matches: Vec<&str> = self.words.iter()
+            .filter(|w| t.fold(w) == folded)
+            .collect();
+        matches.iter()
+            .find(|w| !self.played_so_far.contains(w))
+            .or_else(|| matches.first())
+            .map(|w| w.to_string())
+            .unwrap_or_else(|| word.to_string())
+    }
+
+    /// Checks if the given word has only the allowed letters and includes the
+    /// required letter.
+    fn has_valid_letters(&self, word: &str) -> bool {
+        self.letter_mask(word) & (REQUIRED_BIT | OTHER_BIT) == REQUIRED_BIT
+    }
+
+    /// Returns a bit for each different letter in the word, once folded (see
+    /// `folded()`): `REQUIRED_BIT` for the required letter, the next bits up
+    /// for the optional letters in sorted order, and `OTHER_BIT` for any
+    /// letter that isn't a puzzle letter, so that letter checks are a single
+    /// bitwise operation. Optional letters past the first `MASK_LETTERS`
+    /// share the last of their bits.
+    fn letter_mask(&self, word: &str) -> u32 {
+        letters(&self.folded(word)).into_iter().fold(0, |mask, l| mask | self.letter_bit(l))
+    }
+
+    /// Returns the bit a letter sets in a letter mask. See `letter_mask()`.
+    fn letter_bit(&self, letter: &str) -> u32 {
+        if letter == self.required_letter {
+            return REQUIRED_BIT;
+        }
+        match self.optional_letters.iter().position(|l| l == letter) {
+            Some(i) => REQUIRED_BIT << (i.min(MASK_LETTERS - 1) + 1),
+            None => OTHER_BIT,
+        }
+    }
+
+    /// Returns the letter mask of a word with every puzzle letter.
+    fn all_letters_mask(&self) -> u32 {
+        let optional = self.optional_letters.len().min(MASK_LETTERS) as u32;
+        (REQUIRED_BIT << (optional + 1)) - 1
+    }
+
+    /// Spells a word with the letters its letters count as, if the game's
+    /// alphabet folds accents (see `Alphabet::fold()`), or with accent
+    /// folding on, without the accents of letters that aren't puzzle letters.
+    fn folded<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let word = match &self.alphabet {
+            Some(alphabet) if alphabet.fold_accents() => Cow::Owned(alphabet.fold(word)),
+            _ => Cow::Borrowed(word),
+        };
+        if !self.accent_folding() {
+            return word;
+        }
+        Cow::Owned(letters(&word).into_iter()
+            .map(|l| if self.is_allowed_letter(l) { l.to_string() } else { without_accents(l) })
+            .collect())
+    }
+
+    /// Checks if the given letter is the required letter or one of the
+    /// optional ones.
+    fn is_allowed_letter(&self, letter: &str) -> bool {
+        letter == self.required_letter || self.optional_letters.contains(letter)
+    }
+
+    /// Checks if the given word traces a path of adjacent cells, if the game
+    /// has a hive layout.
+    fn has_valid_path(&self, word: &str) -> bool {
+        let word = &self.folded(word);
+        self.layout.as_ref().is_none_or(|l| l.is_valid_path(word, &self.required_letter))
+    }
+
+    /// Checks if the given word is in the answer list.
+    fn is_valid_word(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// Computes the score for a word. See the `score()` function for more on
+    /// how this is calculated. Returns 0 for invalid words.
+    fn score_word(&self, word: &str) -> usize {
+        let points = self.scoring.points(letter_count(word), self.is_pangram(word)) +
+            self.scoring.rarity_points(self.word_data.frequency(word));
+        if self.is_perfect_pangram(word) {
+            points + self.scoring.perfect_pangram_bonus()
+        } else {
+            points
+        }
+    }
+
+    /// Returns `true` if this word is both valid and contains every given
+    /// letter and `false` otherwise.
+    pub fn is_pangram(&self, word: &str) -> bool {
+        let all = self.all_letters_mask();
+        self.words.position(word).is_some_and(|i| self.answer_masks[i] & all == all)
+    }
+
+    /// Returns the required central letter.
+    pub fn required_letter(&self) -> String {
+        self.required_letter.clone()
+    }
+
+    /// Returns the optional letters, in sorted order.
+    pub fn optional_letters(&self) -> String {
+        self.optional_letters.iter().map(String::as_str).collect()
+    }
+
+    /// Returns the optional letters in the order they go around the hive,
+    /// starting from the top left. The order is shuffled by the given seed, so
+    /// every view that uses the same seed shows the same hive, and a new seed
+    /// reshuffles it. Games with a hive layout always use that layout.
+    pub fn hive_layout(&self, seed: u32) -> String {
+        if let Some(layout) = &self.layout {
+            return layout.ring().concat();
+        }
+
+        let mut letters: Vec<&str> = self.optional_letters.iter().map(String::as_str).collect();
+        Rng::new(u64::from(seed)).shuffle(&mut letters);
+        letters.concat()
+    }
+
+    /// Returns the rules the game is scored by.
+    pub fn scoring(&self) -> ScoringRules {
+        self.scoring.clone()
+    }
+
+    /// Returns the maximum score with all words.
+    pub fn max_score(&self) -> usize {
+        self.max_score
+    }
+
+    /// Returns the score needed to reach each rank in this puzzle, from
+    /// `Beginner` to `QueenBee` (see `Rank::ALL`), for drawing a rank bar.
+    pub fn rank_thresholds(&self) -> Vec<usize> {
+        Rank::ALL.iter().map(|rank| rank.threshold(self.max_score)).collect()
+    }
+
+    /// Returns the number of answers, found or not.
+    pub fn total_answer_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns the number of answers that are pangrams.
+    pub fn pangram_count(&self) -> usize {
+        self.pangrams.len()
+    }
+
+    /// Returns the pangrams that have been found, as an array of strings in
+    /// alphabetical order.
+    pub fn found_pangrams(&self) -> JsValue {
+        js_array(self.pangrams.intersection(&self.played_so_far))
+    }
+
+    /// Returns `true` if a word starting with each of the seven letters has
+    /// been found.
+    pub fn has_bingo(&self) -> bool {
+        self.missing_bingo_letters().is_empty()
+    }
+
+    /// Returns the letters that no found word starts with yet, as an array of
+    /// strings in sorted order, so that frontends can show how close the
+    /// player is to a bingo (see `has_bingo()`).
+    pub fn bingo_progress(&self) -> JsValue {
+        js_array(self.missing_bingo_letters())
+    }
+
+    /// Returns the number of pangrams that haven't been found yet, so that
+    /// frontends can show how many are left without giving them away.
+    pub fn pangrams_remaining(&self) -> usize {
+        self.pangrams.difference(&self.played_so_far).count()
+    }
+
+    /// Returns the number of answers that are perfect pangrams, using every
+    /// letter exactly once.
+    pub fn perfect_pangram_count(&self) -> usize {
+        self.words.iter().filter(|w| self.is_perfect_pangram(w)).count()
+    }
+
+    /// Returns `true` if this word is a pangram that uses every letter exactly
+    /// once.
+    pub fn is_perfect_pangram(&self, word: &str) -> bool {
+        self.is_pangram(word) && letter_count(word) == OPTIONAL_LETTERS + 1
+    }
+
+    /// Returns the rank earned by the current score.
+    pub fn rank(&self) -> Rank {
+        Rank::for_score(self.score(), self.max_score)
+    }
+
+    /// Returns the rank earned by the current score. This is the same as
+    /// `rank()`, named to go with `next_rank_threshold()`.
+    pub fn current_rank(&self) -> Rank {
+        self.rank()
+    }
+
+    /// Returns the English name of the rank earned by the current score, such
+    /// as "Good Start".
+    pub fn rank_name(&self) -> String {
+        self.rank().name().to_string()
+    }
+
+    /// Returns the name of the rank earned by the current score in the locale
+    /// of the given messages.
+    pub fn rank_name_in(&self, messages: &Messages) -> String {
+        messages.rank(self.rank())
+    }
+
+    /// Returns the score needed to reach the given rank in this puzzle.
+    pub fn rank_threshold(&self, rank: Rank) -> usize {
+        rank.threshold(self.max_score())
+    }
+
+    /// Returns the score needed to reach the next rank up, or `None` once the
+    /// player is Queen Bee.
+    pub fn next_rank_threshold(&self) -> Option<usize> {
+        self.rank().next().map(|rank| self.rank_threshold(rank))
+    }
+
+    /// Returns a hint about the easiest answer that hasn't been found yet (see
+    /// `hints::easiest_first()`), or `None` if every answer has been found.
+    pub fn next_hint(&self) -> Option<Hint> {
+        self.missing_easiest_first().first().map(|w| Hint::defined(w, self))
+    }
+
+    /// Sets the points each kind of hint costs to buy with `buy_letter_hint()`
+    /// and the other `buy_` methods, for playing with a hint economy. Hints
+    /// are free until costs are set. The costs aren't saved by `save_bytes()`,
+    /// but the hints bought and what they cost are.
+    pub fn set_hint_costs(&mut self, costs: &HintCosts) {
+        self.hint_costs = *costs;
+    }
+
+    /// Returns the alphabet the game was created with, if any.
+    pub fn alphabet(&self) -> Option<Alphabet> {
+        self.alphabet.clone()
+    }
+
+    /// Returns the points each kind of hint costs to buy.
+    pub fn hint_costs(&self) -> HintCosts {
+        self.hint_costs
+    }
+
+    /// Buys one more letter of the easiest answer that hasn't been found or
+    /// spelled out by letter hints already, and returns the letters bought
+    /// so far from its start. Returns `None` without charging anything if
+    /// there's no such answer or the net score (see `net_score()`) is less
+    /// than the cost.
+    pub fn buy_letter_hint(&mut self) -> Option<String> {
+        let word = self.missing_easiest_first().into_iter()
+            .find(|w| self.hints_about(HintKind::Letter, w) < letter_count(w))?
+            .to_string();
+        let shown = self.hints_about(HintKind::Letter, &word) + 1;
+        if !self.buy_hint(HintKind::Letter, &word) {
+            return None;
+        }
+        Some(letters(&word).into_iter().take(shown).collect())
+    }
+
+    /// Buys the length of the easiest answer that hasn't been found and
+    /// whose length hasn't been bought, like `buy_letter_hint()`.
+    pub fn buy_length_hint(&mut self) -> Option<usize> {
+        let word = self.missing_easiest_first().into_iter()
+            .find(|w| self.hints_about(HintKind::Length, w) == 0)?
+            .to_string();
+        if !self.buy_hint(HintKind::Length, &word) {
+            return None;
+        }
+        Some(letter_count(&word))
+    }
+
+    /// Buys the first `hints::HINT_START_LETTERS` letters of the easiest
+    /// pangram that hasn't been found and whose start hasn't been bought,
+    /// like `buy_letter_hint()`.
+    pub fn buy_pangram_hint(&mut self) -> Option<String> {
+        let word = self.missing_easiest_first().into_iter()
+            .find(|w| self.is_pangram(w) && self.hints_about(HintKind::PangramStart, w) == 0)?
+            .to_string();
+        if !self.buy_hint(HintKind::PangramStart, &word) {
+            return None;
+        }
+        Some(Hint::for_word(&word).start())
+    }
+
+    /// Returns the points spent on hints.
+    pub fn hint_points_spent(&self) -> usize {
+        self.hint_ledger.iter().map(|h| h.cost).sum()
+    }
+
+    /// Returns the number of hints bought.
+    pub fn hints_bought(&self) -> usize {
+        self.hint_ledger.len()
+    }
+
+    /// Reveals the answers, so that `all_answers()` and `remaining_answers()`
+    /// return them, for a review once the player is done. This can't be
+    /// undone.
+    ///
+    /// The gate is advisory, to keep frontends from spoiling the puzzle by
+    /// accident rather than to keep the answers from a player who goes looking:
+    /// `to_json()` saves them, `is_prefix_of_answer()` can be asked about any
+    /// start, and Rust code can always read `answers()`. A frontend that has to
+    /// keep the answers secret should keep the game on a server.
+    pub fn reveal(&mut self) {
+        self.revealed_after.get_or_insert(self.play_order.len());
+    }
+
+    /// Returns `true` if the answers have been revealed with `reveal()`.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed_after.is_some()
+    }
+
+    /// Gives up on the puzzle: reveals the answers and locks the game, so
+    /// that playing any word gives `Finished` and nothing can be taken back
+    /// with `undo()`. Returns the answers that weren't found as an array of
+    /// strings in alphabetical order; `word_points()` and `is_pangram()` give
+    /// what each was worth.
+    pub fn finish(&mut self) -> JsValue {
+        js_array(self.finish_game().into_iter().map(|w| w.word))
+    }
+
+    /// Returns `true` if the game has been ended with `finish()`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the score from the words found before the answers were
+    /// revealed, less any points spent on hints, so that stats can tell a
+    /// Queen Bee or Genius reached without help from one reached by typing in
+    /// revealed answers. This is the same as `score()` if the answers haven't
+    /// been revealed.
+    pub fn unaided_score(&self) -> usize {
+        let found = self.revealed_after.map_or(self.play_order.len(),
+                                               |n| n.min(self.play_order.len()));
+        let earned: usize = self.play_order[..found].iter().map(|w| self.score_word(w)).sum();
+        earned.saturating_sub(self.hint_points_spent())
+    }
+
+    /// Returns the rank the unaided score reaches (see `unaided_score()`).
+    pub fn unaided_rank(&self) -> Rank {
+        Rank::for_score(self.unaided_score(), self.max_score)
+    }
+
+    /// Returns the points the given word is worth as an answer, including any
+    /// pangram bonus, or 0 if it isn't one.
+    pub fn word_points(&self, word: &str) -> usize {
+        let word = self.resolve_input(&self.normalize(word));
+        if self.is_valid_word(&word) { self.score_word(&word) } else { 0 }
+    }
+
+    /// Returns every answer as an array of strings in alphabetical order, or
+    /// `undefined` if the answers haven't been revealed with `reveal()`.
+    pub fn all_answers(&self) -> JsValue {
+        self.revealed_answers().map_or(JsValue::UNDEFINED, js_array)
+    }
+
+    /// Returns the answers that haven't been found as an array of strings in
+    /// alphabetical order, or `undefined` if the answers haven't been revealed
+    /// with `reveal()`.
+    pub fn remaining_answers(&self) -> JsValue {
+        self.revealed_remaining().map_or(JsValue::UNDEFINED, js_array)
+    }
+
+    /// Returns the number of answers that haven't been found yet by starting
+    /// letter and length, like the grid in the official hints column.
+    pub fn hint_grid(&self) -> HintGrid {
+        HintGrid::new(self.words.difference(&self.played_so_far))
+    }
+
+    /// Returns how many answers that haven't been found yet start with each
+    /// pair of letters, in the community hint format: `CO: 4, GI: 2`. See
+    /// `hints::two_letter_counts()` for the counts themselves.
+    pub fn two_letter_list(&self) -> String {
+        two_letter_list(self.words.difference(&self.played_so_far))
+    }
+
+    /// Returns the rules the game is played with.
+    pub fn config(&self) -> GameConfig {
+        let layout = self.layout.as_ref().map(|l| l.ring().concat());
+        GameConfig::new(self.scoring.clone(), &self.locale, layout,
+                        self.transliteration.is_some(), &self.words)
+    }
+
+    /// Returns the definition of the puzzle, without any progress, using the
+    /// default id (see `PuzzleDef::default_id()`).
+    pub fn puzzle_def(&self) -> PuzzleDef {
+        self.puzzle_def_with_id(&PuzzleDef::default_id(&self.optional_letters(),
+                                                       &self.required_letter))
+    }
+
+    /// Returns the definition of the puzzle, without any progress, with the
+    /// given id.
+    pub fn puzzle_def_with_id(&self, id: &str) -> PuzzleDef {
+        PuzzleDef::new(id, &self.optional_letters(), &self.required_letter, self.words.len(),
+                       self.max_score())
+    }
+
+    /// Returns a short code for the puzzle that's safe to put in a URL, with
+    /// the given version id for the word list, if any (see the `code`
+    /// module). `from_puzzle_code()` rebuilds the puzzle from it. Returns
+    /// `None` if the game doesn't have six different optional letters and a
+    /// different required letter, which constructors like `new()` don't
+    /// check.
+    pub fn puzzle_code(&self, lexicon_version: Option<u32>) -> Option<String> {
+        PuzzleCode::new(&self.optional_letters(), &self.required_letter, lexicon_version)
+            .map(|code| code.encode())
+    }
+
+    /// Creates a new game of the puzzle with the given code from
+    /// `puzzle_code()`, with the same lexicons as `new()`. Returns `None` if
+    /// the code isn't valid. The word list version in the code isn't
+    /// checked; use `PuzzleCode::decode()` to compare it with the frontend's
+    /// own.
+    pub fn from_puzzle_code(code: &str, main_words: &str,
+                            swears: &str) -> Option<SpellingBeeGame>
+    {
+        let code = PuzzleCode::decode(code)?;
+        Some(SpellingBeeGame::new(&code.optional_letters(), &code.required_letter(), main_words,
+                                  swears))
+    }
+
+    /// Returns how much memory the game takes up.
+    pub fn diagnostics(&self) -> GameDiagnostics {
+        let source_bytes = string_bytes(self.sources.keys()) +
+            self.sources.values().map(string_bytes).sum::<usize>();
+        GameDiagnostics::new(self.words.len(), self.words.heap_bytes(),
+                             self.played_so_far.heap_bytes(), source_bytes,
+                             self.word_data.heap_bytes())
+    }
+
+    /// Returns the words that have been found as an array of strings, in the
+    /// order they were played if `in_play_order` is `true`, and otherwise in
+    /// alphabetical order. This is `played_words()` in JavaScript; in Rust,
+    /// `played_words()` gives the words in alphabetical order and
+    /// `play_order()` in the order they were played.
+    #[wasm_bindgen(js_name = played_words)]
+    pub fn played_words_array(&self, in_play_order: bool) -> JsValue {
+        if in_play_order {
+            js_array(&self.play_order)
+        } else {
+            js_array(&self.played_so_far)
+        }
+    }
+
+    /// Returns the number of answers that have been found.
+    pub fn words_found(&self) -> usize {
+        self.played_so_far.len()
+    }
+
+    /// Returns the percentage of the answers that have been found, rounded
+    /// down so that 100 means every answer has been found. Returns 0 if the
+    /// puzzle has no answers.
+    pub fn percent_words_found(&self) -> u32 {
+        percent(self.played_so_far.len(), self.words.len())
+    }
+
+    /// Returns the score as a percentage of the maximum score, rounded down
+    /// like `percent_words_found()`. Returns 0 if the puzzle has no answers.
+    pub fn percent_score(&self) -> u32 {
+        percent(self.score(), self.max_score())
+    }
+
+    /// Returns the score as a fraction of the maximum score, from 0 to 1, for
+    /// drawing progress bars. Returns 0 if the puzzle has no answers.
+    pub fn progress_fraction(&self) -> f64 {
+        if self.max_score == 0 {
+            0.0
+        } else {
+            self.score().min(self.max_score) as f64 / self.max_score as f64
+        }
+    }
+
+    /// Returns the score needed to reach Genius in this puzzle, 70% of the
+    /// maximum score under the game's scoring rules.
+    pub fn genius_score(&self) -> usize {
+        self.rank_threshold(Rank::Genius)
+    }
+
+    /// Returns how many more points are needed to reach Genius, or 0 once the
+    /// player has.
+    pub fn points_to_genius(&self) -> usize {
+        self.genius_score().saturating_sub(self.score())
+    }
+
+    /// Returns the found words as CSV, in the order they were found, with a
+    /// header row and columns for the word, its length, its score, whether
+    /// it's a pangram, and the timestamp it was found at, in milliseconds
+    /// since the Unix epoch, which is empty if the clock hasn't started or,
+    /// for a game restored from a corrupt save, if it's too late to count.
+    pub fn export_found_csv(&self) -> String {
+        let mut csv = String::from("word,length,score,pangram,timestamp\n");
+        for (word, &time) in self.play_order.iter().zip(&self.play_times) {
+            let timestamp = self.started_at
+                .and_then(|start| start.checked_add(time))
+                .map(|timestamp| timestamp.to_string());
+            csv.push_str(&format!("{},{},{},{},{}\n", word, letter_count(word),
+                                  self.score_word(word), self.is_pangram(word),
+                                  timestamp.unwrap_or_default()));
+        }
+        csv
+    }
+
+    /// Returns the post-game report (see `final_report()`) rendered as
+    /// Markdown.
+    pub fn final_report_markdown(&self) -> String {
+        self.final_report().to_markdown()
+    }
+
+    /// Returns the post-game report rendered as Markdown in the locale of the
+    /// given messages.
+    pub fn final_report_markdown_in(&self, messages: &Messages) -> String {
+        self.final_report().to_markdown_in(messages)
+    }
+
+    /// Returns a spoiler-free summary of the game for pasting into a group
+    /// chat, with the letters, rank, score, and numbers of words and pangrams
+    /// found, and if `grid` is `true`, a row of squares for each answer
+    /// length. See `Report::to_share_text()`.
+    pub fn share_summary(&self, grid: bool) -> String {
+        self.final_report().to_share_text(grid)
+    }
+
+    /// Returns the spoiler-free summary like `share_summary()`, in the locale
+    /// of the given messages.
+    pub fn share_summary_in(&self, messages: &Messages, grid: bool) -> String {
+        self.final_report().to_share_text_in(messages, grid)
+    }
+
+    /// Compares the answers with a puzzle's official answer list, one word per
+    /// line, and summarizes the differences, listing at most `examples` words
+    /// the lexicon gets wrong each way. See `evaluate()`.
+    pub fn evaluation_summary(&self, official_answers: &str, examples: usize) -> String {
+        self.evaluate(official_answers).summary(examples)
+    }
+
+    /// Returns a printable version of the puzzle as plain text. See
+    /// `printable_sheet()`.
+    pub fn printable_text(&self) -> String {
+        self.printable_sheet().to_text()
+    }
+
+    /// Returns a printable version of the puzzle as simple HTML. See
+    /// `printable_sheet()`.
+    pub fn printable_html(&self) -> String {
+        self.printable_sheet().to_html()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen]
+impl SpellingBeeGame {
+    /// Saves the whole game, answers and progress included, as JSON that
+    /// `from_json()` can restore without the lexicons. The answers are saved
+    /// whether or not they have been revealed (see `reveal()`).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("games always serialize")
+    }
+
+    /// Restores a game saved by `to_json()`. Returns `None` if the JSON isn't
+    /// a saved game.
+    pub fn from_json(json: &str) -> Option<SpellingBeeGame> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+impl SpellingBeeGame {
+    /// Creates a new game like `new_in()`, but checks the letters and word
+    /// lists first. Fails if there aren't six distinct optional letters and a
+    /// different required letter, or if the main word list is empty or can't
+    /// be parsed, rather than falling back to the built-in word list.
+    pub fn try_new_in(locale: &str, optional_letters: &str, required_letter: &str,
+                      main_words: &str, swears: &str) -> Result<SpellingBeeGame, GameError>
+    {
+        set_panic_hook();
+        let optional_letters = &fold_case(optional_letters.trim(), locale);
+        let required_letter = &fold_case(required_letter.trim(), locale);
+        check_letters(optional_letters, required_letter)?;
+
+        if main_words.trim().is_empty() {
+            return Err(GameError::MissingWordList);
+        }
+        let lex: VecLexicon = wordlist::parse_strings(&normalize_list(main_words, locale),
+                                                      &normalize_list(swears, locale))
+            .map_err(|_| GameError::BadWordList)?
+            .into();
+        let mut game = SpellingBeeGame::from_vec_lexicon(locale, optional_letters,
+                                                         required_letter, lex,
+                                                         &ScoringRules::default());
+        game.load_list_frequencies(main_words);
+        Ok(game)
+    }
+
+    /// Creates a new game like `new()` from lexicons given as UTF-8 bytes.
+    /// Fails with `GameError::BadWordList` if either isn't valid UTF-8.
+    pub fn try_new_from_bytes(optional_letters: &str, required_letter: &str,
+                              main_words: &[u8], swears: &[u8])
+                              -> Result<SpellingBeeGame, GameError>
+    {
+        let text = |bytes| str::from_utf8(bytes).map_err(|_| GameError::BadWordList);
+        Ok(SpellingBeeGame::new(optional_letters, required_letter, text(main_words)?,
+                                text(swears)?))
+    }
+
+    /// Creates a new game like `new()`, except that consecutive letters of a
+    /// word must be in adjacent cells of the honeycomb, as for
+    /// `new_with_layout()`. Fails with `GameError::BadLayout` if the layout
+    /// isn't an ordering of the optional letters.
+    pub fn try_new_with_layout(optional_letters: &str, required_letter: &str, layout: &str,
+                               main_words: &str, swears: &str)
+                               -> Result<SpellingBeeGame, GameError>
+    {
+        let mut game = SpellingBeeGame::new(optional_letters, required_letter,
+                                            main_words, swears);
+        let ring = HiveLayout::from_ring(&fold_case(layout, &game.locale))
+            .filter(|l| l.letters() == game.optional_letters)
+            .ok_or_else(|| GameError::BadLayout(layout.to_string()))?;
+        game.apply_layout(ring);
+        Ok(game)
+    }
+
+    /// Creates a new game like `from_lexicon()`, but checks the letters
+    /// first, as for `try_new_in()`.
+    pub fn try_from_lexicon(optional_letters: &str, required_letter: &str,
+                            lexicon: &LexiconHandle) -> Result<SpellingBeeGame, GameError>
+    {
+        let locale = &lexicon.locale();
+        check_letters(&fold_case(optional_letters.trim(), locale),
+                      &fold_case(required_letter.trim(), locale))?;
+        Ok(SpellingBeeGame::from_lexicon(optional_letters, required_letter, lexicon))
+    }
+
+    /// Creates a new game like `new_with_alphabet()`, returning the error if
+    /// it can't be created, as for `try_new_in()`, or if a letter isn't one
+    /// of the alphabet's puzzle letters.
+    pub fn try_new_with_alphabet(optional_letters: &str, required_letter: &str,
+                                 main_words: &str, swears: &str,
+                                 alphabet: &Alphabet) -> Result<SpellingBeeGame, GameError>
+    {
+        set_panic_hook();
+        let locale = &alphabet.language();
+        let optional_letters = &fold_case(optional_letters.trim(), locale);
+        let required_letter = &fold_case(required_letter.trim(), locale);
+        check_letters(optional_letters, required_letter)?;
+        let puzzle_letters: Vec<&str> = letters(required_letter).into_iter()
+            .chain(letters(optional_letters))
+            .collect();
+        if let Some(l) = puzzle_letters.iter().find(|l| !alphabet.is_puzzle_letter(l)) {
+            return Err(GameError::NotInAlphabet(l.to_string()));
+        }
+
+        if main_words.trim().is_empty() {
+            return Err(GameError::MissingWordList);
+        }
+        let mut lex: VecLexicon = wordlist::parse_strings(&normalize_list(main_words, locale),
+                                                          &normalize_list(swears, locale))
+            .map_err(|_| GameError::BadWordList)?
+            .into();
+        // The accented forms of the letters have to get through the first
+        // pass too, and can stand in for the required letter.
+        let allowed: String = alphabet.forms_of(&puzzle_letters).collect();
+        lex.only_using_letters(allowed.chars());
+        let scoring = ScoringRules::default();
+        lex.with_more_length(scoring.min_length().saturating_sub(1));
+
+        let mut game = SpellingBeeGame::with_answers(locale, optional_letters, required_letter,
+                                                     AnswerSet::new());
+        game.transliteration = alphabet.transliteration();
+        game.alphabet = Some(alphabet.clone());
+        game.words = lex.into_iter()
+            .filter(|w| letter_count(w) >= scoring.min_length() && game.has_valid_letters(w))
+            .collect();
+        game.update_totals();
+        game.load_list_frequencies(main_words);
+        Ok(game)
+    }
+
+    /// Replays a decoded save onto a fresh game of its puzzle, for
+    /// `load_bytes()` and `load_bytes_from_lexicon()`. Returns `None` if the
+    /// save's words or score don't hold up against the game's answers.
+    fn restore(mut self, save: SaveState) -> Option<SpellingBeeGame> {
+        if !save.layout.is_empty() {
+            let layout = HiveLayout::from_ring(&save.layout)
+                .filter(|l| l.letters() == self.optional_letters)?;
+            self.apply_layout(layout);
+        }
+
+        for (word, &time) in save.played.iter().zip(&save.play_times) {
+            if self.play_word(word, time) != PlayResult::Valid {
+                return None;
+            }
+        }
+        if self.score != save.score {
+            return None;
+        }
+
+        self.streak = save.streak;
+        self.best_streak = save.best_streak;
+        self.started_at = save.started_at;
+        if save.hints.iter().any(|h| !self.is_answer(&h.word)) {
+            return None;
+        }
+        self.hint_ledger = save.hints;
+        self.revealed_after = save.revealed_after;
+        self.finished = save.finished;
+        self.undoable = self.play_order.len().min(UNDO_HISTORY);
+        Some(self)
+    }
+
+    /// Creates a new game like `new_in()`, scored by the given rules.
+    fn new_scored(locale: &str, optional_letters: &str, required_letter: &str,
+                  main_words: &str, swears: &str, scoring: &ScoringRules) -> SpellingBeeGame
+    {
+        set_panic_hook();
+        let optional_letters = &fold_case(optional_letters.trim(), locale);
+        let required_letter = &fold_case(required_letter.trim(), locale);
+        let (lex, fallback_dictionary) = parse_lexicon(locale, main_words, swears);
+        let mut game = SpellingBeeGame::from_vec_lexicon(locale, optional_letters,
+                                                         required_letter, lex, scoring);
+        game.fallback_dictionary = fallback_dictionary;
+        if !fallback_dictionary {
+            game.load_list_frequencies(main_words);
+        }
+        game
+    }
+
+    /// Creates a game with the given already-lowercased letters whose answers
+    /// are the words in the lexicon that can be made from them and are long
+    /// enough for the scoring rules.
+    fn from_vec_lexicon(locale: &str, optional_letters: &str, required_letter: &str,
+                        mut lex: VecLexicon, scoring: &ScoringRules) -> SpellingBeeGame
+    {
+        // The lexicon works with chars, so this is only a first pass: a letter
+        // made of several chars still needs checking as a whole below.
+        let mut allowed_letters: String = required_letter.to_string();
+        allowed_letters.push_str(optional_letters);
+        lex.only_using_letters(allowed_letters.chars());
+        if let Some(c) = required_letter.chars().next() {
+            lex.with_letter(c);
+        }
+        lex.with_more_length(scoring.min_length().saturating_sub(1));
+
+        SpellingBeeGame::from_words(locale, optional_letters, required_letter, lex, scoring)
+    }
+
+    /// Creates a game with the given already-lowercased letters whose answers
+    /// are the given words that can be made from them and are long enough for
+    /// the scoring rules.
+    fn from_words<I, S>(locale: &str, optional_letters: &str, required_letter: &str, words: I,
+                        scoring: &ScoringRules) -> SpellingBeeGame
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        let mut game = SpellingBeeGame::with_answers(locale, optional_letters, required_letter,
+                                                     AnswerSet::new());
+        game.scoring = scoring.clone();
+        game.words = words.into_iter()
+            .filter(|w| {
+                letter_count(w.as_ref()) >= scoring.min_length() &&
+                    game.has_valid_letters(w.as_ref())
+            })
+            .map(|w| w.as_ref().to_string())
+            .collect();
+        game.update_totals();
+        game
+    }
+
+    /// Creates a game with the given already-lowercased letters and answers
+    /// and no progress.
+    fn with_answers(locale: &str, optional_letters: &str, required_letter: &str,
+                    words: AnswerSet) -> SpellingBeeGame
+    {
+        let mut game = SpellingBeeGame {
+            optional_letters: letters(optional_letters).into_iter()
+                .map(String::from)
+                .collect(),
+            required_letter: required_letter.to_string(),
+            score: 0,
+            played_so_far: AnswerSet::new(),
+            unlisted: BTreeSet::new(),
+            unlisted_found: BTreeSet::new(),
+            play_order: Vec::new(),
+            undoable: 0,
+            streak: 0,
+            best_streak: 0,
+            words,
+            sources: BTreeMap::new(),
+            layout: None,
+            transliteration: None,
+            alphabet: None,
+            locale: locale.to_string(),
+            word_data: WordData::new(),
+            definition_provider: None,
+            fallback_dictionary: false,
+            revealed_after: None,
+            finished: false,
+            normalize_input: true,
+            scoring: ScoringRules::default(),
+            max_score: 0,
+            pangrams: AnswerSet::new(),
+            answer_masks: Vec::new(),
+            started_at: None,
+            play_times: Vec::new(),
+            hint_costs: HintCosts::default(),
+            hint_ledger: Vec::new(),
+        };
+        game.update_totals();
+        game
+    }
+
+    /// Recomputes the pangrams and the maximum score, after the answers or
+    /// scoring change.
+    fn update_totals(&mut self) {
+        self.answer_masks = self.words.iter().map(|w| self.letter_mask(w)).collect();
+        let all = self.all_letters_mask();
+        self.pangrams = self.words.iter().zip(&self.answer_masks)
+            .filter(|(_, &mask)| mask & all == all)
+            .map(|(w, _)| w)
+            .collect();
+        self.max_score = self.words.iter().map(|w| self.score_word(w)).sum();
+    }
+
+    /// Works out the score of the words played and the maximum score again,
+    /// after word data that scoring uses has changed.
+    fn rescore(&mut self) {
+        self.update_totals();
+        self.score = self.played_so_far.iter().map(|w| self.score_word(w)).sum();
+    }
+
+    /// Loads the frequency counts some main word lists give after a tab on
+    /// each line, keeping only those of the answers.
+    fn load_list_frequencies(&mut self, main_words: &str) {
+        if !main_words.contains('\t') {
+            return;
+        }
+        let folded = fold_case(main_words, &self.locale);
+        let answers: Vec<&str> = folded.lines()
+            .filter(|line| line.split_once('\t').is_some_and(|(w, _)| self.is_answer(w.trim())))
+            .collect();
+        self.word_data.load_frequencies(&answers.join("\n"), &self.locale);
+        self.rescore();
+    }
+
+    /// Creates a new game with random letters from the given lexicons, which
+    /// are the same as for `new()`. The puzzle has at least one pangram and
+    /// meets the constraints in `config` (see
+    /// `PuzzleGenerator::random()`). Returns `None` if no puzzle meets them,
+    /// or if the word lists can't be parsed.
+    pub fn random(main_words: &str, swears: &str,
+                  config: &RandomConfig) -> Option<SpellingBeeGame>
+    {
+        PuzzleGenerator::new(main_words, swears).ok()?.random(config)
+    }
+
+    /// Makes words trace a path of adjacent cells in the given layout,
+    /// dropping the answers that can't.
+    fn apply_layout(&mut self, layout: HiveLayout) {
+        let center = self.required_letter.clone();
+        self.words.retain(|w| layout.is_valid_path(w, &center));
+        self.layout = Some(layout);
+        self.update_totals();
+    }
+
+    /// Returns every answer, in alphabetical order, whether or not they have
+    /// been revealed (see `reveal()`).
+    pub fn answers(&self) -> &AnswerSet {
+        &self.words
+    }
+
+    /// Returns every answer, in alphabetical order, if the answers have been
+    /// revealed with `reveal()`.
+    pub fn revealed_answers(&self) -> Option<&AnswerSet> {
+        Some(&self.words).filter(|_| self.is_revealed())
+    }
+
+    /// Returns the answers that haven't been found, in alphabetical order, if
+    /// the answers have been revealed with `reveal()`.
+    pub fn revealed_remaining(&self) -> Option<Vec<&str>> {
+        self.revealed_answers().map(|words| words.difference(&self.played_so_far).collect())
+    }
+
+    /// Returns the letters that no found word starts with yet, in sorted
+    /// order, like `bingo_progress()`.
+    pub fn missing_bingo_letters(&self) -> Vec<&str> {
+        let starts: BTreeSet<&str> = self.played_so_far.iter()
+            .filter_map(|w| letters(w).first().copied())
+            .collect();
+        let mut missing: Vec<&str> = self.optional_letters.iter()
+            .chain(std::iter::once(&self.required_letter))
+            .map(String::as_str)
+            .filter(|l| !starts.contains(l))
+            .collect();
+        missing.sort_unstable();
+        missing
+    }
+
+    /// Returns the answers that are pangrams, in alphabetical order.
+    pub fn pangrams(&self) -> &AnswerSet {
+        &self.pangrams
+    }
+
+    /// Returns the words that have been found, in alphabetical order.
+    pub fn played_words(&self) -> &AnswerSet {
+        &self.played_so_far
+    }
+
+    /// Returns the accepted swears that have been played, like
+    /// `unlisted_words()`.
+    pub fn found_unlisted(&self) -> &BTreeSet<String> {
+        &self.unlisted_found
+    }
+
+    /// Returns the words that have been found, in the order they were played.
+    pub fn play_order(&self) -> &[String] {
+        &self.play_order
+    }
+
+    /// Returns the milliseconds from the start of the clock each word was
+    /// found at, in the order they were played, as in `play_order()`.
+    pub fn play_times(&self) -> &[u64] {
+        &self.play_times
+    }
+
+    /// Returns the time the clock started, in milliseconds since the Unix
+    /// epoch, if it has.
+    pub fn started_at(&self) -> Option<u64> {
+        self.started_at
+    }
+
+    /// Returns each rank above the lowest with the milliseconds from the start
+    /// of the clock it was reached at, in order, or nothing if the clock hasn't
+    /// started. Only words found before the answers were revealed count, and
+    /// every hint bought is taken off from the start, since hints aren't timed,
+    /// so the last rank is always `unaided_rank()`.
+    pub fn rank_splits(&self) -> Vec<(Rank, u64)> {
+        if self.started_at.is_none() {
+            return Vec::new();
+        }
+        let found = self.revealed_after.map_or(self.play_order.len(),
+                                               |n| n.min(self.play_order.len()));
+        let spent = self.hint_points_spent();
+        let mut splits = Vec::new();
+        let mut earned = 0;
+        let mut rank = Rank::for_score(0, self.max_score);
+        for (word, &time) in self.play_order[..found].iter().zip(&self.play_times) {
+            earned += self.score_word(word);
+            let reached = Rank::for_score(earned.saturating_sub(spent), self.max_score);
+            splits.extend(Rank::ALL.iter()
+                .filter(|&&r| r > rank && r <= reached)
+                .map(|&r| (r, time)));
+            rank = rank.max(reached);
+        }
+        splits
+    }
+
+    /// Ends the game like `finish()`, returning the answers that weren't
+    /// found, in alphabetical order, with their points and whether they're
+    /// pangrams.
+    pub fn finish_game(&mut self) -> Vec<ReportWord> {
+        self.reveal();
+        self.finished = true;
+        self.final_report().missed
+    }
+
+    /// Returns the answers that haven't been found yet, easiest first.
+    pub fn missing_easiest_first(&self) -> Vec<&str> {
+        easiest_first(self.words.difference(&self.played_so_far), &self.word_data)
+    }
+
+    /// Returns the hints bought, in the order they were bought.
+    pub fn hint_ledger(&self) -> &[HintPurchase] {
+        &self.hint_ledger
+    }
+
+    /// Returns the number of hints of the given kind bought about a word.
+    fn hints_about(&self, kind: HintKind, word: &str) -> usize {
+        self.hint_ledger.iter().filter(|h| h.kind == kind && h.word == word).count()
+    }
+
+    /// Charges for a hint about a word, adding it to the ledger. Returns
+    /// `false`, charging nothing, if the net score is less than the cost.
+    fn buy_hint(&mut self, kind: HintKind, word: &str) -> bool {
+        let cost = self.hint_costs.cost(kind);
+        if self.net_score() < cost {
+            return false;
+        }
+        self.hint_ledger.push(HintPurchase { kind, word: word.to_string(), cost });
+        true
+    }
+
+    /// Returns hints about every answer that hasn't been found yet, easiest
+    /// first.
+    pub fn hints(&self) -> Vec<Hint> {
+        self.missing_easiest_first().into_iter().map(|w| Hint::defined(w, self)).collect()
+    }
+
+    /// Returns `true` if the word is one of the puzzle's answers.
+    pub fn is_answer(&self, word: &str) -> bool {
+        self.is_valid_word(word)
+    }
+
+    /// Returns a summary of the game, including every answer that was missed.
+    pub fn final_report(&self) -> Report {
+        let entry = |word: &str| ReportWord {
+            word: word.to_string(),
+            points: self.score_word(word),
+            pangram: self.is_pangram(word),
+        };
+
+        Report {
+            optional_letters: self.optional_letters(),
+            required_letter: self.required_letter.clone(),
+            score: self.score(),
+            max_score: self.max_score(),
+            best_streak: self.best_streak,
+            found: self.played_so_far.iter().map(entry).collect(),
+            missed: self.words.difference(&self.played_so_far).map(entry).collect(),
+        }
+    }
+
+    /// Compares the answers with a puzzle's official answer list, one word per
+    /// line, to find the words the lexicon wrongly accepts or misses.
+    pub fn evaluate(&self, official_answers: &str) -> Accuracy {
+        let official: AnswerSet = official_answers.lines()
+            .map(|w| fold_case(w.trim(), &self.locale))
+            .filter(|w| !w.is_empty())
+            .collect();
+        Accuracy::compare(&self.words, &official)
+    }
+
+    /// Returns a blank sheet for playing the puzzle on paper, with the hive
+    /// letters, blank lines for every answer, and the hint grid.
+    pub fn printable_sheet(&self) -> Sheet {
+        Sheet::new(&self.optional_letters, &self.required_letter, &self.words)
+    }
+}
+
+/// Checks that there are six distinct optional letters and a different
+/// required letter.
+pub(crate) fn check_letters(optional_letters: &str,
+                            required_letter: &str) -> Result<(), GameError>
+{
+    let optional = letters(optional_letters);
+    let required = letters(required_letter);
+    if optional.len() != OPTIONAL_LETTERS || required.len() != 1 {
+        return Err(GameError::WrongLetterCount { optional: optional.len(),
+                                                 required: required.len() });
+    }
+    if let Some(l) = optional.iter().chain(&required).find(|l| !is_letter(l)) {
+        return Err(GameError::NotALetter(l.to_string()));
+    }
+
+    let mut seen = BTreeSet::new();
+    if let Some(l) = optional.iter().find(|l| !seen.insert(**l)) {
+        return Err(GameError::DuplicateLetter(l.to_string()));
+    }
+    if seen.contains(required[0]) {
+        return Err(GameError::RequiredLetterIsOptional(required[0].to_string()));
+    }
+    Ok(())
+}
+
+/// Parses the main word list and swears list, lowercased with the case rules
+/// of the given locale, into a lexicon. If the main word list is empty or
+/// can't be parsed, the lexicon comes from `FALLBACK_WORDS` instead, and the
+/// flag returned is `true`.
+pub(crate) fn parse_lexicon(locale: &str, main_words: &str, swears: &str) -> (VecLexicon, bool) {
+    let swears = &normalize_list(swears, locale);
+    // If the host couldn't supply a word list, fall back to the built-in one, so that there's
+    // still a puzzle to play.
+    let parsed = if main_words.trim().is_empty() {
+        None
+    } else {
+        wordlist::parse_strings(&normalize_list(main_words, locale), swears).ok()
+    };
+    match parsed {
+        Some(list) => (list.into(), false),
+        None => (wordlist::parse_strings(FALLBACK_WORDS, swears).unwrap().into(), true),
+    }
+}
+
+/// Lowercases a word list with the case rules of the given locale and trims
+/// each line, dropping any frequency count after a tab (see
+/// `SpellingBeeGame::load_list_frequencies()`).
+fn normalize_list(text: &str, locale: &str) -> String {
+    fold_case(text, locale).lines()
+        .map(|line| line.split('\t').next().unwrap_or_default().trim())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Returns `n` as a whole percentage of `total`, rounded down, or 0 if `total`
+/// is 0.
+fn percent(n: usize, total: usize) -> u32 {
+    (n.min(total) * 100).checked_div(total).unwrap_or(0) as u32
+}
+
+impl Definitions for SpellingBeeGame {
+    fn define(&self, word: &str) -> Option<String> {
+        self.definition_provider.as_ref()
+            .and_then(|p| p.define(word))
+            .or_else(|| self.word_data.define(word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        assert_eq!(game.score(), 0);
+        assert_eq!(game.play("will"), PlayResult::Valid);
+        assert_eq!(game.score(), 1);
+        assert_eq!(game.play("cowgirl"), PlayResult::Valid);
+        assert_eq!(game.score(), 15);
+        assert_eq!(game.play("rail"), PlayResult::InvalidLetters);
+        assert_eq!(game.score(), 15);
+        assert_eq!(game.play("roll"), PlayResult::InvalidLetters);
+        assert_eq!(game.score(), 15);
+        assert_eq!(game.play("clwgi"), PlayResult::InvalidWord);
+        assert_eq!(game.score(), 15);
+        assert_eq!(game.play("oil"), PlayResult::InvalidLength);
+        assert_eq!(game.score(), 15);
+        assert_eq!(game.play("cowgirl"), PlayResult::AlreadyPlayed);
+        assert_eq!(game.score(), 15);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        assert_eq!(game.check("will"), PlayResult::Valid);
+        assert_eq!((game.score(), game.words_found()), (0, 0));
+        assert_eq!(game.check("roll"), PlayResult::InvalidLetters);
+        game.play("will");
+        assert_eq!(game.check("will"), PlayResult::AlreadyPlayed);
+        assert_eq!(game.score(), 1);
+    }
+
+    #[test]
+    fn test_play_order() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        game.play("will");
+        game.play("grill");
+        game.play("cowgirl");
+        assert_eq!(game.play_order(), ["will", "cowgirl"]);
+        assert_eq!(game.played_words().iter().collect::<Vec<_>>(), ["cowgirl", "will"]);
+    }
+
+    #[test]
+    fn test_streak() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        game.play("will");
+        game.play("coil");
+        assert_eq!((game.streak(), game.best_streak()), (2, 2));
+        game.play("grill");
+        assert_eq!((game.streak(), game.best_streak()), (0, 2));
+        game.play("cowgirl");
+        assert_eq!((game.streak(), game.best_streak()), (1, 2));
+        assert_eq!(game.final_report().best_streak, 2);
+    }
+
+    #[test]
+    fn test_export_found_csv() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        assert_eq!(game.export_found_csv(), "word,length,score,pangram,timestamp\n");
+        game.start_clock(1_000.0);
+        game.play_at("will", 61_000.0);
+        game.play_at("cowgirl", 90_000.0);
+        assert_eq!(game.export_found_csv(), "word,length,score,pangram,timestamp\n\
+                                             will,4,1,false,61000\n\
+                                             cowgirl,7,14,true,90000\n");
+
+        // A corrupt save can have times that overflow when added up.
+        game.started_at = Some(u64::MAX);
+        assert_eq!(game.export_found_csv(), "word,length,score,pangram,timestamp\n\
+                                             will,4,1,false,\n\
+                                             cowgirl,7,14,true,\n");
+    }
+
+    #[test]
+    fn test_play_outcome() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        let outcome = game.play_outcome("COWGIRL");
+        assert_eq!((outcome.result(), outcome.word(), outcome.points(), outcome.is_pangram()),
+                   (PlayResult::Valid, "cowgirl".to_string(), 14, true));
+        assert_eq!((outcome.total(), outcome.rank()), (14, Rank::Genius));
+
+        let outcome = game.play_outcome("cowgirl");
+        assert_eq!((outcome.result(), outcome.points(), outcome.is_pangram()),
+                   (PlayResult::AlreadyPlayed, 0, false));
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", "will\ncowgirl\ncoil\n");
+        assert_eq!(game.undo(), None);
+        game.play("will");
+        game.play("cowgirl");
+        assert_eq!(game.undo(), Some("cowgirl".to_string()));
+        assert_eq!((game.score(), game.streak()), (1, 1));
+        assert_eq!(game.play_order(), ["will"]);
+        assert_eq!(game.play("cowgirl"), PlayResult::Valid);
+
+        let words: Vec<String> = (0..UNDO_HISTORY + 2)
+            .map(|i| format!("wil{}", "l".repeat(i + 1)))
+            .collect();
+        let mut game = SpellingBeeGame::from_answers("clwgro", "i", &words.join("\n"));
+        for word in &words {
+            game.play(word);
+        }
+        while game.undo().is_some() {}
+        assert_eq!(game.words_found(), 2);
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn test_fallback_dictionary() {
+        let game = SpellingBeeGame::new("aehmrt", "o", "", "");
+        assert!(game.uses_fallback_dictionary());
+        assert!(game.is_answer("mother"));
+        assert!(!SpellingBeeGame::new("aehmrt", "o", "mother\n", "").uses_fallback_dictionary());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\n");
+        game.play("will");
+        let restored = SpellingBeeGame::from_json(&game.to_json()).unwrap();
+        assert_eq!(restored, game);
+        assert_eq!(SpellingBeeGame::from_json("{}"), None);
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        // Good Start needs 2% of the 16 points, which rounds to the nearest point: none.
+        assert_eq!(game.current_rank(), Rank::GoodStart);
+        assert_eq!(game.next_rank_threshold(), Some(1));
+        game.play("cowgirl");
+        assert_eq!(game.rank_name(), "Genius");
+        assert_eq!(game.next_rank_threshold(), Some(16));
+        assert_eq!(game.rank_thresholds()[Rank::Genius as usize], 11);
+        assert_eq!(game.rank_thresholds().last(), Some(&game.max_score()));
+        assert_eq!((game.genius_score(), game.points_to_genius()), (11, 0));
+        assert_eq!(game.progress_fraction(), 14.0 / 16.0);
+        game.play("will");
+        game.play("coil");
+        assert_eq!(game.next_rank_threshold(), None);
+    }
+
+    #[test]
+    fn test_scoring() {
+        let words = "will\ncowgirl\ncoil\ngrill\n";
+        let mut rules = ScoringRules::new();
+        rules.set_length_points(5, 2);
+        rules.set_pangram_bonus(2 * PANGRAM_BONUS);
+        let mut game = SpellingBeeGame::new_with_scoring("cglorw", "i", words, "", &rules);
+        assert_eq!(game.max_score(), 1 + 21 + 1 + 2);
+        assert_eq!(game.play_outcome("grill").points(), 2);
+        assert_eq!(game.rank_threshold(Rank::Genius), 18);
+
+        let bytes = game.save_bytes();
+        assert_eq!(SpellingBeeGame::load_bytes(&bytes, words, ""), None);
+        assert_eq!(SpellingBeeGame::load_bytes_with_scoring(&bytes, words, "", &rules),
+                   Some(game));
+
+        let mut perfect = ScoringRules::new();
+        perfect.set_perfect_pangram_bonus(3);
+        let mut game = SpellingBeeGame::new_with_scoring("cglorw", "i", "cowgirl\ncoilgrow\n", "",
+                                                         &perfect);
+        assert_eq!(game.max_score(), 7 + 7 + 3 + 8 + 7);
+        assert_eq!(game.play_outcome("cowgirl").points(), 17);
+
+        let mut kids = ScoringRules::new();
+        kids.set_min_length(3);
+        let mut game = SpellingBeeGame::new_with_scoring("cglorw", "i", "wig\nwill\n", "", &kids);
+        assert_eq!(game.answers().len(), 2);
+        assert_eq!(game.play("wig"), PlayResult::Valid);
+        assert_eq!(game.config().min_length(), 3);
+        assert_eq!(Messages::new("en").play_result(PlayResult::InvalidLength, &game),
+                   "Words need to be at least 3 letters long");
+
+        let mut rare = ScoringRules::new();
+        rare.set_rarity_bonus(2);
+        rare.set_rare_frequency(1000);
+        let words = "will\t90000\ncoil\t400\ngrill\n";
+        let mut game = SpellingBeeGame::new_with_scoring("cglorw", "i", words, "", &rare);
+        assert_eq!(game.frequency("coil"), Some(400.0));
+        assert_eq!(game.max_score(), 1 + 3 + 5);
+        assert_eq!(game.play_outcome("coil").points(), 3);
+        game.load_frequencies("grill\t12\n");
+        assert_eq!((game.max_score(), game.score()), (1 + 3 + 7, 3));
+    }
+
+    #[test]
+    fn test_timing() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        assert_eq!(game.elapsed_time(), None);
+        game.start_clock(1_000.0);
+        assert_eq!(game.play_at("will", 61_000.0), PlayResult::Valid);
+        assert_eq!(game.play_at("grill", 90_000.0), PlayResult::InvalidWord);
+        assert_eq!(game.time_to_genius(), None);
+        assert_eq!(game.play_at("cowgirl", 721_000.0), PlayResult::Valid);
+        assert_eq!(game.elapsed_time(), Some(720_000.0));
+        assert_eq!(game.time_to_genius(), Some(720_000.0));
+        assert_eq!(game.time_found("WILL"), Some(60_000.0));
+        assert_eq!(game.word_times(), [60_000.0, 720_000.0]);
+
+        let bytes = game.save_bytes();
+        let loaded = SpellingBeeGame::load_bytes(&bytes, "will\ncowgirl\ncoil\n", "").unwrap();
+        assert_eq!(loaded.started_at(), Some(1_000));
+        assert_eq!(loaded.play_times(), [60_000, 720_000]);
+        game.undo();
+        assert_eq!(game.elapsed_time(), Some(60_000.0));
+    }
+
+    #[test]
+    fn test_hint_costs() {
+        let words = "will\ncowgirl\ncoil\ngrill\n";
+        let mut game = SpellingBeeGame::new("cglorw", "i", words, "");
+        assert_eq!(game.buy_length_hint(), Some(4));
+        assert_eq!(game.score(), 0);
+
+        let mut costs = HintCosts::new();
+        costs.set_cost(HintKind::Letter, 2);
+        costs.set_cost(HintKind::PangramStart, 5);
+        game.set_hint_costs(&costs);
+        assert_eq!(game.buy_letter_hint(), None);
+        game.play("cowgirl");
+        assert_eq!(game.buy_pangram_hint(), None);
+        assert_eq!(game.buy_letter_hint(), Some("c".to_string()));
+        assert_eq!(game.buy_letter_hint(), Some("co".to_string()));
+        assert_eq!((game.net_score(), game.hint_points_spent(), game.hints_bought()), (10, 4, 3));
+        assert_eq!(game.score(), 14);
+        for word in ["will", "coil", "grill"] {
+            game.play(word);
+        }
+        assert_eq!((game.rank(), game.score() == game.max_score()), (Rank::QueenBee, true));
+
+        let bytes = game.save_bytes();
+        let loaded = SpellingBeeGame::load_bytes(&bytes, words, "").unwrap();
+        assert_eq!(loaded.hint_ledger(), game.hint_ledger());
+        assert_eq!(loaded.net_score(), 17);
+    }
+
+    #[test]
+    fn test_reveal() {
+        let mut game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "");
+        game.play("will");
+        assert!(!game.is_revealed());
+        assert_eq!(game.revealed_answers(), None);
+        assert_eq!(game.revealed_remaining(), None);
+
+        game.reveal();
+        assert_eq!(game.revealed_answers().map(|a| a.len()), Some(3));
+        assert_eq!(game.revealed_remaining(), Some(vec!["coil", "cowgirl"]));
+    }
+
+    #[test]
+    fn test_finish() {
+        let words = "will\ncowgirl\ncoil\n";
+        let mut game = SpellingBeeGame::new("cglorw", "i", words, "");
+        game.play("Will");
+        game.reveal();
+        game.play("cowgirl");
+        assert_eq!((game.score(), game.unaided_score()), (15, 1));
+        assert_eq!(game.unaided_rank(), Rank::Good);
+
+        let missed = game.finish_game();
+        assert_eq!(missed.len(), 1);
+        assert_eq!((missed[0].word.as_str(), missed[0].points), ("coil", 1));
+        assert_eq!(game.play("coil"), PlayResult::Finished);
+        assert_eq!((game.undo(), game.word_points("COIL")), (None, 1));
+
+        let bytes = game.save_bytes();
+        let loaded = SpellingBeeGame::load_bytes(&bytes, words, "").unwrap();
+        assert!(loaded.is_finished());
+        assert_eq!(loaded.unaided_score(), 1);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let try_new = |optional, required, words| {
+            SpellingBeeGame::try_new_in("en", optional, required, words, "")
+        };
+        assert_eq!(try_new("clwgro", "I", "will\nwilk\n").map(|g| g.answers().len()), Ok(1));
+        assert_eq!(try_new("clwgro", "i", " \n"), Err(GameError::MissingWordList));
+        assert_eq!(try_new("clwgr", "i", "will\n"),
+                   Err(GameError::WrongLetterCount { optional: 5, required: 1 }));
+        assert_eq!(try_new("clwgrr", "i", "will\n"),
+                   Err(GameError::DuplicateLetter("r".to_string())));
+        assert_eq!(try_new("clwgri", "i", "will\n"),
+                   Err(GameError::RequiredLetterIsOptional("i".to_string())));
+        assert_eq!(try_new("clwgr1", "i", "will\n"), Err(GameError::NotALetter("1".to_string())));
+
+        let from_bytes = |words: &[u8]| {
+            SpellingBeeGame::try_new_from_bytes("clwgro", "i", words, b"")
+        };
+        assert_eq!(from_bytes(b"will\nwilk\n").map(|g| g.answers().len()), Ok(1));
+        assert_eq!(from_bytes(b"will\n\xffcoil\n").err(), Some(GameError::BadWordList));
+
+        let with_layout = |layout| {
+            SpellingBeeGame::try_new_with_layout("clwgro", "i", layout, "will\ncoil\n", "")
+        };
+        assert_eq!(with_layout("CLOWGR").map(|g| g.answers().len()), Ok(1));
+        assert_eq!(with_layout("clwgrx").err(), Some(GameError::BadLayout("clwgrx".to_string())));
+        assert_eq!(with_layout("clwgr").err(), Some(GameError::BadLayout("clwgr".to_string())));
+    }
+
+    #[test]
+    fn test_puzzle_stats() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i",
+                                                     "will\ncowgirl\ncoil\ncoilgrow\n");
+        assert_eq!(game.total_answer_count(), 4);
+        assert_eq!((game.pangram_count(), game.perfect_pangram_count()), (2, 1));
+        assert!(game.is_perfect_pangram("cowgirl") && !game.is_perfect_pangram("will"));
+        game.play("cowgirl");
+        assert_eq!(game.pangrams_remaining(), 1);
+        assert!(game.pangrams().contains("coilgrow") && !game.is_pangram("cowgirls"));
+
+        game.play("will");
+        assert_eq!(game.missing_bingo_letters(), ["g", "i", "l", "o", "r"]);
+        assert!(!game.has_bingo());
+    }
+
+    #[test]
+    fn test_prefix_of_answer() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        assert!(game.is_prefix_of_answer("co") && game.is_prefix_of_answer("COW"));
+        assert!(game.is_prefix_of_answer("will") && game.is_prefix_of_answer(""));
+        assert!(!game.is_prefix_of_answer("cl") && !game.is_prefix_of_answer("wills"));
+        game.play("coil");
+        assert!(game.is_prefix_of_answer("coi"));
+    }
+
+    #[test]
+    fn test_input_normalization() {
+        let mut game = SpellingBeeGame::new("clwgro", "i", " WILL \ncowgirl\n", "");
+        assert_eq!(game.check_partial(" Wi"), PlayResult::Valid);
+        assert_eq!(game.play(" Will "), PlayResult::Valid);
+
+        let mut game = SpellingBeeGame::new_with_normalization("en", "clwgro", "i", " WILL \n",
+                                                               "", false);
+        assert_eq!(game.play("WILL"), PlayResult::InvalidLetters);
+        assert_eq!(game.play(" will"), PlayResult::InvalidCharacter);
+        assert_eq!(game.play("will"), PlayResult::Valid);
+
+        let mut game = SpellingBeeGame::new_with_normalization("tr", "ALKMNS", "I", "KALIN\n", "",
+                                                               true);
+        assert_eq!(game.play(" SALİ "), PlayResult::InvalidLetters);
+        assert_eq!(game.play(" KALIN "), PlayResult::Valid);
+    }
+
+    #[test]
+    fn test_normalization() {
+        let mut game = SpellingBeeGame::from_answers("acefrt", "e\u{301}",
+                                                     "caf\u{e9}\nfe\u{301}te\n");
+        assert_eq!(game.required_letter(), "\u{e9}");
+        assert_eq!(game.play("cafe\u{301}"), PlayResult::Valid);
+        assert_eq!(game.play("f\u{e9}te"), PlayResult::Valid);
+        assert_eq!(game.score(), 2);
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let mut game = SpellingBeeGame::new_in("tr", "ALKMNS", "I", "KALIN\nsalı\n", "");
+        assert_eq!(game.required_letter(), "ı");
+        assert_eq!(game.play("KALIN"), PlayResult::Valid);
+        assert_eq!(game.play("Salı"), PlayResult::Valid);
+        assert_eq!(game.play("SALİ"), PlayResult::InvalidLetters);
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let words = "canción\ncancion\nniño\nnoción\ncanal\n";
+        let mut spanish = Alphabet::for_language("es").unwrap();
+        let strict = SpellingBeeGame::try_new_with_alphabet("acilos", "n", words, "", &spanish)
+            .unwrap();
+        assert_eq!(strict.total_answer_count(), 2);
+
+        spanish.set_fold_accents(true);
+        let mut game = SpellingBeeGame::try_new_with_alphabet("ACILOS", "N", words, "", &spanish)
+            .unwrap();
+        assert_eq!(game.total_answer_count(), 4);
+        assert_eq!(game.play("noción"), PlayResult::Valid);
+        assert_eq!(game.play("cancion"), PlayResult::Valid);
+        assert!(game.is_valid_partial_input("canció"));
+        assert_eq!(game.play("niño"), PlayResult::InvalidLetters);
+        assert_eq!(SpellingBeeGame::try_new_with_alphabet("acilos", "ó", words, "", &spanish),
+                   Err(GameError::NotInAlphabet("ó".to_string())));
+
+        let german = Alphabet::for_language("de").unwrap();
+        let words = "großer\ngießt\n";
+        let mut game = SpellingBeeGame::try_new_with_alphabet("ießlrt", "g", words, "", &german)
+            .unwrap();
+        assert_eq!(game.play("gießt"), PlayResult::Valid);
+    }
+
+    #[test]
+    fn test_accent_folding() {
+        let mut game = SpellingBeeGame::from_answers("abcdfr", "e", "café\nfacade\nbarre\n");
+        assert_eq!(game.play("cafe"), PlayResult::InvalidWord);
+        game.set_accent_folding(true);
+        assert!(game.accent_folding());
+        assert!(game.is_valid_partial_input("facadé"));
+        assert_eq!(game.play("cafe"), PlayResult::Valid);
+        assert_eq!(game.play("facadé"), PlayResult::Valid);
+        assert_eq!(game.play("café"), PlayResult::AlreadyPlayed);
+        assert!(game.played_words().contains("café") && game.played_words().contains("facade"));
+        game.set_accent_folding(false);
+        assert!(!game.accent_folding());
+        assert_eq!(game.play("barré"), PlayResult::InvalidLetters);
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncoil\n");
+        game.load_definitions("WILL\tthe faculty of choosing\ncoil\ta spiral\n");
+        assert_eq!(game.definition("Will"), Some("the faculty of choosing".to_string()));
+        assert_eq!(game.play_outcome("will").definition(), game.definition("will"));
+        assert_eq!(game.play_outcome("will").definition(), None);
+        assert_eq!(game.next_hint().and_then(|h| h.definition()), Some("a spiral".to_string()));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_from_source() {
+        let json = r#"[{"word": "will", "frequency": 900}, {"word": "cowgirl"}, {"word": "coil"}]"#;
+        let source = WordlistSource::parse_detected(json).unwrap();
+        let game = SpellingBeeGame::from_source("cglorw", "i", &source, "coil\n");
+        assert_eq!(game.answers().iter().collect::<Vec<_>>(), ["cowgirl", "will"]);
+        assert_eq!(game.frequency("will"), Some(900.0));
+    }
+
+    #[test]
+    fn test_accept_swears() {
+        let mut game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "coil\n");
+        assert_eq!(game.play("coil"), PlayResult::InvalidWord);
+        assert_eq!(game.accept_swears("coil\nwill\nword\n"), 2);
+        assert_eq!((game.total_answer_count(), game.max_score()), (1, 14));
+        game.play("cowgirl");
+        assert_eq!(game.play("coil"), PlayResult::AcceptedUnlisted);
+        assert_eq!((game.score(), game.streak(), game.rank()), (14, 1, Rank::QueenBee));
+        assert_eq!(game.play("coil"), PlayResult::AlreadyPlayed);
+        assert!(game.found_unlisted().contains("coil") && !game.played_words().contains("coil"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let words = "will\ncowgirl\ncoil\nroil\n";
+        let mut ours = SpellingBeeGame::new("cglorw", "i", words, "");
+        let mut theirs = SpellingBeeGame::new("cglorw", "i", words, "");
+        ours.play("will");
+        ours.play("coil");
+        theirs.play("coil");
+        theirs.play("cowgirl");
+
+        let report = ours.merge(&theirs);
+        assert!(report.same_letters());
+        assert_eq!((report.from_other(), report.from_self()), (&["cowgirl".to_string()][..],
+                                                               &["will".to_string()][..]));
+        assert_eq!((report.points(), report.total(), ours.score()), (14, 16, 16));
+        assert!(ours.played_words().contains("cowgirl") && !ours.can_undo());
+        assert!(ours.merge(&theirs).from_other().is_empty());
+
+        let other = SpellingBeeGame::new("cglorw", "l", "will\n", "");
+        assert!(!ours.merge(&other).same_letters());
+    }
+
+    #[test]
+    fn test_merge_finished() {
+        let words = "will\ncowgirl\ncoil\n";
+        let mut ours = SpellingBeeGame::new("cglorw", "i", words, "");
+        let mut theirs = SpellingBeeGame::new("cglorw", "i", words, "");
+        ours.play("will");
+        theirs.play("cowgirl");
+        ours.finish_game();
+
+        let report = ours.merge(&theirs);
+        assert!(report.from_other().is_empty());
+        assert_eq!((report.points(), report.total(), ours.score()), (0, 1, 1));
+        assert!(!ours.played_words().contains("cowgirl"));
+    }
+
+    #[test]
+    fn test_letter_masks() {
+        let game = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\n", "");
+        assert_eq!(game.letter_mask("coil"), 0b11011);
+        assert_eq!(game.letter_mask("wilt") & OTHER_BIT, OTHER_BIT);
+        assert!(game.has_valid_letters("coil") && !game.has_valid_letters("crow"));
+        assert!(game.is_pangram("cowgirl") && !game.is_pangram("will") && !game.is_pangram("wig"));
+        assert!(game.is_valid_partial_input("cow") && !game.is_valid_partial_input("cot"));
     }
 }
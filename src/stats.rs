@@ -0,0 +1,196 @@
+//! This module provides `Stats`, a player's lifetime statistics across every game they've played:
+//! how many, how often they reached Genius and Queen Bee, how much of the maximum score they get
+//! on average, their longest word, and the letters their words most often start with.
+//!
+//! Games are recorded by puzzle id, and recording a puzzle again replaces what was recorded for it
+//! before, so a frontend can record a game in progress after every word without counting it
+//! twice. Ranks count only the words found before the answers were revealed (see
+//! `SpellingBeeGame::unaided_score()`), so typing in revealed answers doesn't earn a Queen Bee.
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::rank::Rank;
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::{js_array, letters, letter_count};
+
+/// What's kept of one game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct GameRecord {
+    /// The score.
+    score: usize,
+
+    /// The score from the words found before the answers were revealed.
+    unaided_score: usize,
+
+    /// The maximum score.
+    max_score: usize,
+
+    /// The words found, in alphabetical order.
+    words: Vec<String>,
+}
+
+impl GameRecord {
+    /// Returns the rank the game reached.
+    fn rank(&self) -> Rank {
+        Rank::for_score(self.unaided_score, self.max_score)
+    }
+}
+
+/// A player's statistics across games.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stats {
+    /// The games recorded, by puzzle id.
+    games: BTreeMap<String, GameRecord>,
+}
+
+#[wasm_bindgen]
+impl Stats {
+    /// Creates statistics with no games.
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    /// Records a game of the puzzle with the given id, finished or not, replacing anything
+    /// recorded for the puzzle before.
+    pub fn record(&mut self, puzzle_id: &str, game: &SpellingBeeGame) {
+        self.games.insert(puzzle_id.to_string(), GameRecord {
+            score: game.score(),
+            unaided_score: game.unaided_score(),
+            max_score: game.max_score(),
+            words: game.played_words().iter().map(String::from).collect(),
+        });
+    }
+
+    /// Returns the number of games recorded.
+    pub fn games_played(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Returns the number of games that reached Genius or better.
+    pub fn genius_count(&self) -> usize {
+        self.games.values().filter(|g| g.rank() >= Rank::Genius).count()
+    }
+
+    /// Returns the number of games that reached Queen Bee.
+    pub fn queen_bee_count(&self) -> usize {
+        self.games.values().filter(|g| g.rank() == Rank::QueenBee).count()
+    }
+
+    /// Returns the average score as a percentage of the maximum score, from 0 to 100, or 0 if no
+    /// games have been recorded. Puzzles without answers count as 0.
+    pub fn average_percent(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+
+        let total: f64 = self.games.values()
+            .filter(|g| g.max_score > 0)
+            .map(|g| 100.0 * g.score.min(g.max_score) as f64 / g.max_score as f64)
+            .sum();
+        total / self.games.len() as f64
+    }
+
+    /// Returns the longest word found in any game, the first alphabetically if there's a tie, or
+    /// `None` if no words have been found.
+    pub fn longest_word(&self) -> Option<String> {
+        self.games.values()
+            .flat_map(|g| &g.words)
+            .max_by_key(|w| (letter_count(w), std::cmp::Reverse(*w)))
+            .cloned()
+    }
+
+    /// Returns the letters that found words most often start with, most common first, as an
+    /// array of at most `count` strings. Letters that are equally common are in alphabetical
+    /// order.
+    pub fn favorite_starting_letters(&self, count: usize) -> JsValue {
+        js_array(self.favorite_starts(count))
+    }
+
+    /// Encodes the statistics as text, with a line for each game giving its puzzle id, score,
+    /// maximum score, the words found separated by spaces, and the unaided score, all separated
+    /// by tabs.
+    pub fn encode(&self) -> String {
+        self.games.iter()
+            .map(|(id, g)| format!("{}\t{}\t{}\t{}\t{}\n", id, g.score, g.max_score,
+                                   g.words.join(" "), g.unaided_score))
+            .collect()
+    }
+
+    /// Decodes statistics written by `encode()`, returning `None` if any line isn't a valid
+    /// game. Lines without an unaided score, from before it was recorded, count the whole score
+    /// as unaided.
+    pub fn decode(text: &str) -> Option<Stats> {
+        let mut stats = Stats::new();
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (id, score, max_score, words, unaided) = match fields.as_slice() {
+                [id, score, max_score, words] => (id, score, max_score, words, score),
+                [id, score, max_score, words, unaided] => (id, score, max_score, words, unaided),
+                _ => return None,
+            };
+            stats.games.insert(id.to_string(), GameRecord {
+                score: score.parse().ok()?,
+                unaided_score: unaided.parse().ok()?,
+                max_score: max_score.parse().ok()?,
+                words: words.split_whitespace().map(String::from).collect(),
+            });
+        }
+        Some(stats)
+    }
+}
+
+impl Stats {
+    /// Returns the number of found words starting with each letter, across every game.
+    pub fn starting_letter_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for word in self.games.values().flat_map(|g| &g.words) {
+            if let Some(first) = letters(word).first() {
+                *counts.entry(first.to_string()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns the letters that found words most often start with, like
+    /// `favorite_starting_letters()`.
+    pub fn favorite_starts(&self, count: usize) -> Vec<String> {
+        let mut counts: Vec<(String, usize)> = self.starting_letter_counts().into_iter().collect();
+        // The sort is stable, so that ties stay in alphabetical order.
+        counts.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+        counts.into_iter().take(count).map(|(letter, _)| letter).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut stats = Stats::new();
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        game.play("will");
+        stats.record("2024-05-06", &game);
+        game.play("cowgirl");
+        game.play("coil");
+        stats.record("2024-05-06", &game);
+        let mut other = SpellingBeeGame::from_answers("aehmrt", "o", "mother\nmoth\n");
+        other.play("moth");
+        stats.record("2024-05-07", &other);
+
+        assert_eq!(stats.games_played(), 2);
+        assert_eq!((stats.genius_count(), stats.queen_bee_count()), (1, 1));
+        assert_eq!(stats.average_percent(), (100.0 + 100.0 / 7.0) / 2.0);
+        assert_eq!(stats.longest_word(), Some("cowgirl".to_string()));
+        assert_eq!(stats.favorite_starts(2), ["c", "m"]);
+        assert_eq!(Stats::decode(&stats.encode()), Some(stats));
+        assert_eq!(Stats::decode("2024-05-06\tmany\t1\t\n"), None);
+        let old = Stats::decode("2024-05-06\t16\t16\tcoil cowgirl will\n").unwrap();
+        assert_eq!(old.queen_bee_count(), 1);
+    }
+}
@@ -0,0 +1,550 @@
+//! This module provides `Storage`, where the HTTP API keeps its games and its leaderboard, so that
+//! a server can be restarted without losing either. It is only built natively, with the
+//! `http-api` feature.
+//!
+//! Games are stored as the bytes of `SpellingBeeGame::save_bytes()`, by game id, and leaderboard
+//! entries are stored by puzzle id, one line each in the format of `LeaderboardEntry::encode()`.
+//! `MemoryStorage` keeps them in memory, for tests and for servers that only want a leaderboard
+//! while they're running, and `DirStorage` keeps them as files in a directory. With the
+//! `storage-sled` feature, `SledStorage` keeps them in a sled database, and with the
+//! `storage-sqlite` feature, `SqliteStorage` keeps them in a SQLite database.
+
+use std::collections::BTreeMap;
+#[cfg(any(feature = "storage-sled", feature = "storage-sqlite"))]
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use crate::rank::Rank;
+
+/// A player's result for a puzzle, as the leaderboard keeps it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LeaderboardEntry {
+    /// The id of the puzzle, as from `PuzzleDef::default_id()`.
+    pub puzzle_id: String,
+
+    /// The player's id (see `session::Player`).
+    pub player_id: String,
+
+    /// The player's name, which can't have tabs or line breaks.
+    pub player: String,
+
+    /// The score, counting only the words found before the answers were revealed, less any
+    /// points spent on hints (see `SpellingBeeGame::unaided_score()`).
+    pub score: usize,
+
+    /// The rank for that score.
+    pub rank: Rank,
+
+    /// The number of words found.
+    pub words: usize,
+
+    /// The milliseconds from the start of the game to the last word found, if the game kept
+    /// time.
+    pub elapsed: Option<u64>,
+
+    /// Each rank reached, with the milliseconds from the start of the game it was reached at, as
+    /// from `SpellingBeeGame::rank_splits()`.
+    pub splits: Vec<(Rank, u64)>,
+
+    /// The server's signature of `timing_payload()`, so that times edited in storage can be told
+    /// apart from the ones the server recorded (see `session::Sessions::sign()`), or empty if the
+    /// entry was never signed.
+    pub proof: String,
+}
+
+impl LeaderboardEntry {
+    /// Encodes the entry as a line of tab-separated fields: the puzzle id, the player's id and
+    /// name, the score, the rank (as its place in `Rank::ALL`), the number of words, the elapsed
+    /// time, which is empty if there isn't one, the splits, as each rank's place and time joined by
+    /// a colon and separated by commas, and the proof.
+    pub fn encode(&self) -> String {
+        let elapsed = self.elapsed.map(|t| t.to_string()).unwrap_or_default();
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}", self.puzzle_id, self.player_id,
+                self.player, self.score, rank_index(self.rank), self.words, elapsed,
+                self.encode_splits(), self.proof)
+    }
+
+    /// Decodes an entry written by `encode()`, returning `None` if it isn't one. Entries from
+    /// before splits were kept have none, and no proof.
+    pub fn decode(line: &str) -> Option<LeaderboardEntry> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (splits, proof) = match fields.len() {
+            7 => ("", ""),
+            9 => (fields[7], fields[8]),
+            _ => return None,
+        };
+        let (puzzle_id, player_id, player, score, rank, words, elapsed) =
+            (fields[0], fields[1], fields[2], fields[3], fields[4], fields[5], fields[6]);
+        Some(LeaderboardEntry {
+            puzzle_id: puzzle_id.to_string(),
+            player_id: player_id.to_string(),
+            player: player.to_string(),
+            score: score.parse().ok()?,
+            rank: *Rank::ALL.get(rank.parse::<usize>().ok()?)?,
+            words: words.parse().ok()?,
+            elapsed: match elapsed {
+                "" => None,
+                elapsed => Some(elapsed.parse().ok()?),
+            },
+            splits: splits.split(',').filter(|s| !s.is_empty()).map(|split| {
+                let (rank, time) = split.split_once(':')?;
+                Some((*Rank::ALL.get(rank.parse::<usize>().ok()?)?, time.parse().ok()?))
+            }).collect::<Option<_>>()?,
+            proof: proof.to_string(),
+        })
+    }
+
+    /// Returns what `proof` signs: the puzzle id, the player's id, the score, the elapsed time,
+    /// and the splits, so that none of them can be changed without the proof failing.
+    pub fn timing_payload(&self) -> String {
+        let elapsed = self.elapsed.map(|t| t.to_string()).unwrap_or_default();
+        format!("entry\t{}\t{}\t{}\t{}\t{}", self.puzzle_id, self.player_id, self.score, elapsed,
+                self.encode_splits())
+    }
+
+    /// Encodes the splits as in `encode()`.
+    fn encode_splits(&self) -> String {
+        self.splits.iter()
+            .map(|&(rank, time)| format!("{}:{}", rank_index(rank), time))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Returns a rank's place in `Rank::ALL`.
+fn rank_index(rank: Rank) -> usize {
+    Rank::ALL.iter().position(|&r| r == rank).expect("every rank")
+}
+
+/// The ways storing or loading can fail.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StorageError {
+    /// The backend couldn't read or write, with what it said went wrong.
+    Backend(String),
+
+    /// Something that was stored can't be read back, like a leaderboard entry that isn't in the
+    /// format of `LeaderboardEntry::encode()`.
+    Corrupt(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Backend(message) => write!(f, "storage failed: {}", message),
+            StorageError::Corrupt(what) => write!(f, "stored data is corrupt: {}", what),
+        }
+    }
+}
+
+impl Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> StorageError {
+        StorageError::Backend(e.to_string())
+    }
+}
+
+/// Somewhere to keep games and leaderboard entries.
+pub trait Storage {
+    /// Saves a game's bytes under its id, replacing what was saved for it before.
+    fn save_game(&mut self, id: u64, bytes: &[u8]) -> Result<(), StorageError>;
+
+    /// Loads the bytes saved for a game, or `None` if there aren't any.
+    fn load_game(&self, id: u64) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Deletes a game. Deleting one that isn't saved does nothing.
+    fn delete_game(&mut self, id: u64) -> Result<(), StorageError>;
+
+    /// Returns the highest id a game has been saved under, or `None` if none have.
+    fn last_game_id(&self) -> Result<Option<u64>, StorageError>;
+
+    /// Adds an entry to the leaderboard.
+    fn record_entry(&mut self, entry: &LeaderboardEntry) -> Result<(), StorageError>;
+
+    /// Returns the leaderboard entries for a puzzle, in the order they were recorded.
+    fn entries(&self, puzzle_id: &str) -> Result<Vec<LeaderboardEntry>, StorageError>;
+}
+
+/// Decodes a stored leaderboard entry, failing if it isn't one.
+fn decode_entry(line: &str) -> Result<LeaderboardEntry, StorageError> {
+    LeaderboardEntry::decode(line)
+        .ok_or_else(|| StorageError::Corrupt(format!("bad leaderboard entry {:?}", line)))
+}
+
+/// Storage in memory, which is lost when the server stops. It keeps every game saved in it,
+/// including those the API stops keeping in memory itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStorage {
+    /// The games' bytes, by id.
+    games: BTreeMap<u64, Vec<u8>>,
+
+    /// The leaderboard entries, oldest first.
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl MemoryStorage {
+    /// Creates empty storage.
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_game(&mut self, id: u64, bytes: &[u8]) -> Result<(), StorageError> {
+        self.games.insert(id, bytes.to_vec());
+        Ok(())
+    }
+
+    fn load_game(&self, id: u64) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.games.get(&id).cloned())
+    }
+
+    fn delete_game(&mut self, id: u64) -> Result<(), StorageError> {
+        self.games.remove(&id);
+        Ok(())
+    }
+
+    fn last_game_id(&self) -> Result<Option<u64>, StorageError> {
+        Ok(self.games.keys().next_back().copied())
+    }
+
+    fn record_entry(&mut self, entry: &LeaderboardEntry) -> Result<(), StorageError> {
+        self.entries.push(entry.clone());
+        Ok(())
+    }
+
+    fn entries(&self, puzzle_id: &str) -> Result<Vec<LeaderboardEntry>, StorageError> {
+        Ok(self.entries.iter().filter(|e| e.puzzle_id == puzzle_id).cloned().collect())
+    }
+}
+
+/// Storage in a directory, with each game in `games/ID.save` and the leaderboard in
+/// `leaderboard.tsv`, one entry per line. Games are written to a temporary file and renamed into
+/// place, so a crash mid-write leaves the old save.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DirStorage {
+    /// The directory.
+    dir: PathBuf,
+}
+
+impl DirStorage {
+    /// Opens storage in the given directory, creating it if it doesn't exist.
+    pub fn open<P: Into<PathBuf>>(dir: P) -> Result<DirStorage, StorageError> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("games"))?;
+        Ok(DirStorage { dir })
+    }
+
+    /// Returns the path of a game's file.
+    fn game_path(&self, id: u64) -> PathBuf {
+        self.dir.join("games").join(format!("{}.save", id))
+    }
+
+    /// Returns the path of the leaderboard.
+    fn leaderboard_path(&self) -> PathBuf {
+        self.dir.join("leaderboard.tsv")
+    }
+}
+
+impl Storage for DirStorage {
+    fn save_game(&mut self, id: u64, bytes: &[u8]) -> Result<(), StorageError> {
+        let path = self.game_path(id);
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, bytes)?;
+        fs::rename(&temporary, &path)?;
+        Ok(())
+    }
+
+    fn load_game(&self, id: u64) -> Result<Option<Vec<u8>>, StorageError> {
+        match fs::read(self.game_path(id)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn delete_game(&mut self, id: u64) -> Result<(), StorageError> {
+        match fs::remove_file(self.game_path(id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn last_game_id(&self) -> Result<Option<u64>, StorageError> {
+        let mut last = None;
+        for file in fs::read_dir(self.dir.join("games"))? {
+            let name = file?.file_name();
+            let id = name.to_str()
+                .and_then(|name| name.strip_suffix(".save"))
+                .and_then(|id| id.parse::<u64>().ok());
+            last = last.max(id);
+        }
+        Ok(last)
+    }
+
+    fn record_entry(&mut self, entry: &LeaderboardEntry) -> Result<(), StorageError> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.leaderboard_path())?;
+        writeln!(file, "{}", entry.encode())?;
+        Ok(())
+    }
+
+    fn entries(&self, puzzle_id: &str) -> Result<Vec<LeaderboardEntry>, StorageError> {
+        let text = match fs::read_to_string(self.leaderboard_path()) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        text.lines()
+            .filter(|line| line.split('\t').next() == Some(puzzle_id))
+            .map(decode_entry)
+            .collect()
+    }
+}
+
+#[cfg(feature = "storage-sled")]
+impl From<sled::Error> for StorageError {
+    fn from(e: sled::Error) -> StorageError {
+        StorageError::Backend(e.to_string())
+    }
+}
+
+/// Storage in a sled database, with the games in a `games` tree keyed by their big-endian ids, so
+/// that the last key is the highest id, and the leaderboard in a `leaderboard` tree keyed by the
+/// puzzle id, a tab, and an id sled generates, so that a puzzle's entries are a prefix scan.
+#[cfg(feature = "storage-sled")]
+#[derive(Debug, Clone)]
+pub struct SledStorage {
+    /// The database.
+    db: sled::Db,
+
+    /// The games.
+    games: sled::Tree,
+
+    /// The leaderboard.
+    leaderboard: sled::Tree,
+}
+
+#[cfg(feature = "storage-sled")]
+impl SledStorage {
+    /// Opens the database at the given path, creating it if it doesn't exist.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<SledStorage, StorageError> {
+        let db = sled::open(path)?;
+        let (games, leaderboard) = (db.open_tree("games")?, db.open_tree("leaderboard")?);
+        Ok(SledStorage { db, games, leaderboard })
+    }
+}
+
+#[cfg(feature = "storage-sled")]
+impl Storage for SledStorage {
+    fn save_game(&mut self, id: u64, bytes: &[u8]) -> Result<(), StorageError> {
+        self.games.insert(id.to_be_bytes(), bytes)?;
+        self.games.flush()?;
+        Ok(())
+    }
+
+    fn load_game(&self, id: u64) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.games.get(id.to_be_bytes())?.map(|bytes| bytes.to_vec()))
+    }
+
+    fn delete_game(&mut self, id: u64) -> Result<(), StorageError> {
+        self.games.remove(id.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn last_game_id(&self) -> Result<Option<u64>, StorageError> {
+        match self.games.last()? {
+            Some((key, _)) => {
+                let key = <[u8; 8]>::try_from(key.as_ref())
+                    .map_err(|_| StorageError::Corrupt("bad game id".to_string()))?;
+                Ok(Some(u64::from_be_bytes(key)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn record_entry(&mut self, entry: &LeaderboardEntry) -> Result<(), StorageError> {
+        let mut key = format!("{}\t", entry.puzzle_id).into_bytes();
+        key.extend_from_slice(&self.db.generate_id()?.to_be_bytes());
+        self.leaderboard.insert(key, entry.encode().as_bytes())?;
+        self.leaderboard.flush()?;
+        Ok(())
+    }
+
+    fn entries(&self, puzzle_id: &str) -> Result<Vec<LeaderboardEntry>, StorageError> {
+        self.leaderboard.scan_prefix(format!("{}\t", puzzle_id))
+            .map(|item| {
+                let (_, value) = item?;
+                let line = std::str::from_utf8(&value)
+                    .map_err(|_| StorageError::Corrupt("a leaderboard entry isn't UTF-8".into()))?;
+                decode_entry(line)
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "storage-sqlite")]
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> StorageError {
+        StorageError::Backend(e.to_string())
+    }
+}
+
+/// Storage in a SQLite database, with the games in a `games` table of ids and saves, and the
+/// leaderboard in a `leaderboard` table of puzzle ids and encoded entries.
+#[cfg(feature = "storage-sqlite")]
+#[derive(Debug)]
+pub struct SqliteStorage {
+    /// The connection to the database.
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "storage-sqlite")]
+impl SqliteStorage {
+    /// Opens the database at the given path, creating it and its tables if they don't exist.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<SqliteStorage, StorageError> {
+        let connection = rusqlite::Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS games (id INTEGER PRIMARY KEY, save BLOB NOT NULL);
+             CREATE TABLE IF NOT EXISTS leaderboard (puzzle TEXT NOT NULL, entry TEXT NOT NULL);
+             CREATE INDEX IF NOT EXISTS leaderboard_puzzle ON leaderboard (puzzle);")?;
+        Ok(SqliteStorage { connection })
+    }
+}
+
+/// Converts a game id to the signed integers SQLite keeps.
+#[cfg(feature = "storage-sqlite")]
+fn sql_id(id: u64) -> Result<i64, StorageError> {
+    i64::try_from(id).map_err(|_| StorageError::Backend(format!("game id {} is too big", id)))
+}
+
+#[cfg(feature = "storage-sqlite")]
+impl Storage for SqliteStorage {
+    fn save_game(&mut self, id: u64, bytes: &[u8]) -> Result<(), StorageError> {
+        self.connection.execute("INSERT OR REPLACE INTO games (id, save) VALUES (?1, ?2)",
+                                rusqlite::params![sql_id(id)?, bytes])?;
+        Ok(())
+    }
+
+    fn load_game(&self, id: u64) -> Result<Option<Vec<u8>>, StorageError> {
+        use rusqlite::OptionalExtension;
+        Ok(self.connection
+            .query_row("SELECT save FROM games WHERE id = ?1", [sql_id(id)?], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn delete_game(&mut self, id: u64) -> Result<(), StorageError> {
+        self.connection.execute("DELETE FROM games WHERE id = ?1", [sql_id(id)?])?;
+        Ok(())
+    }
+
+    fn last_game_id(&self) -> Result<Option<u64>, StorageError> {
+        let last: Option<i64> = self.connection
+            .query_row("SELECT MAX(id) FROM games", [], |row| row.get(0))?;
+        Ok(last.map(|id| id as u64))
+    }
+
+    fn record_entry(&mut self, entry: &LeaderboardEntry) -> Result<(), StorageError> {
+        self.connection.execute("INSERT INTO leaderboard (puzzle, entry) VALUES (?1, ?2)",
+                                [&entry.puzzle_id, &entry.encode()])?;
+        Ok(())
+    }
+
+    fn entries(&self, puzzle_id: &str) -> Result<Vec<LeaderboardEntry>, StorageError> {
+        let mut statement = self.connection
+            .prepare("SELECT entry FROM leaderboard WHERE puzzle = ?1 ORDER BY rowid")?;
+        let lines = statement.query_map([puzzle_id], |row| row.get::<_, String>(0))?;
+        let mut entries = Vec::new();
+        for line in lines {
+            entries.push(decode_entry(&line?)?);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that storage keeps games and leaderboard entries.
+    fn check_storage<S: Storage>(storage: &mut S) {
+        assert_eq!(storage.last_game_id(), Ok(None));
+        storage.save_game(2, b"two").unwrap();
+        storage.save_game(10, b"ten").unwrap();
+        storage.save_game(2, b"second").unwrap();
+        assert_eq!(storage.load_game(2), Ok(Some(b"second".to_vec())));
+        assert_eq!(storage.last_game_id(), Ok(Some(10)));
+        storage.delete_game(10).unwrap();
+        storage.delete_game(10).unwrap();
+        assert_eq!(storage.load_game(10), Ok(None));
+
+        let entry = |puzzle_id: &str, player: &str, elapsed| LeaderboardEntry {
+            puzzle_id: puzzle_id.to_string(),
+            player_id: format!("{}-id", player),
+            player: player.to_string(),
+            score: 15,
+            rank: Rank::Genius,
+            words: 2,
+            elapsed,
+            splits: elapsed.map_or(vec![], |t| vec![(Rank::Great, 1_000), (Rank::Genius, t)]),
+            proof: String::new(),
+        };
+        storage.record_entry(&entry("i-cglorw", "ana", Some(60_000))).unwrap();
+        storage.record_entry(&entry("p-aehnrt", "ana", None)).unwrap();
+        storage.record_entry(&entry("i-cglorw", "ben", None)).unwrap();
+        let expected = vec![entry("i-cglorw", "ana", Some(60_000)), entry("i-cglorw", "ben", None)];
+        assert_eq!(storage.entries("i-cglorw"), Ok(expected));
+        assert_eq!(storage.entries("o-aehmrt"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_storage() {
+        check_storage(&mut MemoryStorage::new());
+
+        let dir = std::env::temp_dir().join(format!("spellingbee-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        check_storage(&mut DirStorage::open(&dir).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "storage-sled")]
+    #[test]
+    fn test_sled_storage() {
+        let path = std::env::temp_dir().join(format!("spellingbee-sled-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        check_storage(&mut SledStorage::open(&path).unwrap());
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[cfg(feature = "storage-sqlite")]
+    #[test]
+    fn test_sqlite_storage() {
+        let path = std::env::temp_dir().join(format!("spellingbee-{}.sqlite", std::process::id()));
+        let _ = fs::remove_file(&path);
+        check_storage(&mut SqliteStorage::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_encode_entry() {
+        let line = "i-cglorw\t0123456789abcdef\tana\t15\t8\t2\t60000\t6:1000,8:60000\tabc";
+        let entry = LeaderboardEntry::decode(line).unwrap();
+        assert_eq!((entry.rank, entry.elapsed), (Rank::Genius, Some(60_000)));
+        assert_eq!(entry.splits, vec![(Rank::Great, 1_000), (Rank::Genius, 60_000)]);
+        assert_eq!(entry.proof, "abc");
+        assert_eq!(entry.encode(), line);
+        assert_eq!(entry.timing_payload(),
+                   "entry\ti-cglorw\t0123456789abcdef\t15\t60000\t6:1000,8:60000");
+        assert_eq!(LeaderboardEntry::decode(&line.replace("\t8\t", "\t10\t")), None);
+        assert_eq!(LeaderboardEntry::decode(&line.replace("8:", "8")), None);
+
+        // Entries from before splits were kept still load.
+        let old = LeaderboardEntry::decode("i-cglorw\t0123456789abcdef\tana\t15\t8\t2\t60000");
+        assert_eq!(old.map(|e| (e.splits, e.proof)), Some((vec![], String::new())));
+    }
+}
@@ -0,0 +1,415 @@
+//! This module provides `SyncState`, which keeps copies of a game on several devices in sync by
+//! exchanging compact binary deltas, in any order and as often as they like, the way a CRDT does.
+//! Unlike `PeerSync`, words can be taken back as well as found.
+//!
+//! The found words are an observed-remove set. Every change is an event tagged with a dot: the id
+//! of the device that made it and that device's count of changes so far, starting from 1. Finding
+//! a word adds it with a new dot, and taking it back removes the dots it was added with that the
+//! device has seen. A word is found as long as it has some dot that hasn't been removed, so if one
+//! device takes back a word while another finds it again, the word stays found. Devices that have
+//! seen the same events have the same words, and so the same score, whatever order they saw them
+//! in, and seeing an event twice does nothing.
+//!
+//! A device's version says which events it has: for each device, the highest count up to which
+//! it has every event. `export_delta()` takes another device's version and returns the events it's
+//! missing, and `apply_delta()` adds them. Device ids may be any text, but each device needs its
+//! own, and a device that reloads has to restore its state with `from_bytes()` rather than start
+//! again with `new()`: otherwise its counts start again from 1, and other devices skip its new
+//! events as ones they've already seen.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+use crate::spellingbee::{PlayResult, SpellingBeeGame};
+
+/// The first byte of every delta, so that the format can change later.
+const DELTA_FORMAT: u8 = 1;
+
+/// An event's tag: the id of the device that made it, and that device's count of changes.
+type Dot = (String, u64);
+
+/// A change to the found words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Event {
+    /// Found the word.
+    Add(String),
+
+    /// Took back the words added with these dots.
+    Remove(Vec<Dot>),
+}
+
+/// One device's copy of a game shared between devices.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncState {
+    /// This device's id.
+    device: String,
+
+    /// The puzzle as it was before anything was found, to rebuild the game from.
+    puzzle: SpellingBeeGame,
+
+    /// The game, with every found word played.
+    game: SpellingBeeGame,
+
+    /// Every event seen, by dot.
+    events: BTreeMap<Dot, Event>,
+
+    /// The dots of the adds that have been removed.
+    removed: BTreeSet<Dot>,
+}
+
+#[wasm_bindgen]
+impl SyncState {
+    /// Starts syncing the given game as the device with the given id, which no other device may
+    /// use. Every device should start from the same puzzle, before anything has been found.
+    pub fn new(game: SpellingBeeGame, device: &str) -> SyncState {
+        SyncState {
+            device: device.to_string(),
+            puzzle: game.clone(),
+            game,
+            events: BTreeMap::new(),
+            removed: BTreeSet::new(),
+        }
+    }
+
+    /// Plays a word on this device, recording it for the other devices if it's accepted.
+    pub fn play(&mut self, word: &str) -> PlayResult {
+        let outcome = self.game.play_outcome(word);
+        if outcome.result() == PlayResult::Valid {
+            let dot = self.next_dot();
+            self.events.insert(dot, Event::Add(outcome.word()));
+        }
+        outcome.result()
+    }
+
+    /// Takes back a found word on every device that has seen it found, returning `false` if it
+    /// isn't found. A device that finds it again without having seen it taken back keeps it.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let dots: Vec<Dot> = self.live_adds()
+            .filter(|(_, w)| *w == word)
+            .map(|(dot, _)| dot.clone())
+            .collect();
+        if dots.is_empty() {
+            return false;
+        }
+
+        self.removed.extend(dots.iter().cloned());
+        let dot = self.next_dot();
+        self.events.insert(dot, Event::Remove(dots));
+        self.rebuild();
+        true
+    }
+
+    /// Takes back the most recent word found on this device that's still found, like
+    /// `remove()`, and returns it.
+    pub fn undo(&mut self) -> Option<String> {
+        let word = self.live_adds()
+            .filter(|((device, _), _)| *device == self.device)
+            .last()
+            .map(|(_, word)| word.to_string())?;
+        self.remove(&word);
+        Some(word)
+    }
+
+    /// Returns the current score.
+    pub fn score(&self) -> usize {
+        self.game.score()
+    }
+
+    /// Returns a copy of the shared game.
+    pub fn game(&self) -> SpellingBeeGame {
+        self.game.clone()
+    }
+
+    /// Returns this device's version. Send it to another device so that it can work out which
+    /// events to send back with `export_delta()`.
+    pub fn version(&self) -> Vec<u8> {
+        let mut counts: BTreeMap<&str, u64> = BTreeMap::new();
+        for (device, count) in self.events.keys() {
+            let seen = counts.entry(device.as_str()).or_insert(0);
+            if *count == *seen + 1 {
+                *seen = *count;
+            }
+        }
+
+        let mut bytes = Vec::new();
+        put_varint(&mut bytes, counts.len() as u64);
+        for (device, count) in counts {
+            put_string(&mut bytes, device);
+            put_varint(&mut bytes, count);
+        }
+        bytes
+    }
+
+    /// Returns a delta with every event a device with the given version hasn't seen. An empty or
+    /// unreadable version counts as having seen nothing, so the delta has every event.
+    pub fn export_delta(&self, since: &[u8]) -> Vec<u8> {
+        let seen = decode_version(since).unwrap_or_default();
+        let events: Vec<(&Dot, &Event)> = self.events.iter()
+            .filter(|((device, count), _)| seen.get(device).is_none_or(|seen| count > seen))
+            .collect();
+
+        let mut devices: Vec<&str> = Vec::new();
+        for (dot, event) in &events {
+            devices.push(&dot.0);
+            if let Event::Remove(dots) = event {
+                devices.extend(dots.iter().map(|(device, _)| device.as_str()));
+            }
+        }
+        devices.sort_unstable();
+        devices.dedup();
+        let index = |device: &str| devices.binary_search(&device).unwrap_or(0) as u64;
+
+        let mut bytes = vec![DELTA_FORMAT];
+        put_varint(&mut bytes, devices.len() as u64);
+        for device in &devices {
+            put_string(&mut bytes, device);
+        }
+        put_varint(&mut bytes, events.len() as u64);
+        for ((device, count), event) in events {
+            put_varint(&mut bytes, index(device));
+            put_varint(&mut bytes, *count);
+            match event {
+                Event::Add(word) => {
+                    bytes.push(0);
+                    put_string(&mut bytes, word);
+                }
+                Event::Remove(dots) => {
+                    bytes.push(1);
+                    put_varint(&mut bytes, dots.len() as u64);
+                    for (device, count) in dots {
+                        put_varint(&mut bytes, index(device));
+                        put_varint(&mut bytes, *count);
+                    }
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Saves the state, with this device's id and every event it has seen, so that it can be
+    /// restored with `from_bytes()`. The puzzle isn't saved.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        put_string(&mut bytes, &self.device);
+        bytes.extend(self.export_delta(&[]));
+        bytes
+    }
+
+    /// Restores a state saved by `to_bytes()` for the given puzzle, which should be the one it was
+    /// started with. Returns `None` if the bytes aren't a saved state.
+    pub fn from_bytes(puzzle: SpellingBeeGame, bytes: &[u8]) -> Option<SyncState> {
+        let mut reader = Reader { bytes };
+        let device = reader.string()?;
+        let mut state = SyncState::new(puzzle, &device);
+        state.apply_delta(reader.bytes)?;
+        Some(state)
+    }
+
+    /// Applies a delta from another device, skipping events already seen. Returns the number of
+    /// new events, or `None`, applying nothing, if the delta isn't valid.
+    pub fn apply_delta(&mut self, delta: &[u8]) -> Option<usize> {
+        let events = decode_delta(delta)?;
+        let mut count = 0;
+        let mut rebuild = false;
+        for (dot, event) in events {
+            if self.events.contains_key(&dot) {
+                continue;
+            }
+            match &event {
+                Event::Add(word) => {
+                    if !self.removed.contains(&dot) {
+                        self.game.play(word);
+                    }
+                }
+                Event::Remove(dots) => {
+                    for removed in dots {
+                        rebuild |= self.removed.insert(removed.clone()) &&
+                            self.events.contains_key(removed);
+                    }
+                }
+            }
+            self.events.insert(dot, event);
+            count += 1;
+        }
+        if rebuild {
+            self.rebuild();
+        }
+        Some(count)
+    }
+}
+
+impl SyncState {
+    /// Returns the shared game.
+    pub fn game_ref(&self) -> &SpellingBeeGame {
+        &self.game
+    }
+
+    /// Returns the adds that haven't been removed, with their words, in dot order.
+    fn live_adds(&self) -> impl Iterator<Item = (&Dot, &str)> {
+        self.events.iter().filter_map(move |(dot, event)| match event {
+            Event::Add(word) if !self.removed.contains(dot) => Some((dot, word.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Returns the dot for this device's next event.
+    fn next_dot(&self) -> Dot {
+        let count = self.events.keys()
+            .filter(|(device, _)| *device == self.device)
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0);
+        (self.device.clone(), count + 1)
+    }
+
+    /// Plays the found words again on a fresh copy of the puzzle, after some were taken back.
+    fn rebuild(&mut self) {
+        let mut game = self.puzzle.clone();
+        for (_, word) in self.live_adds() {
+            game.play(word);
+        }
+        self.game = game;
+    }
+}
+
+/// Appends a number as a variable-length integer: seven bits per byte, lowest first, with the
+/// top bit set on every byte but the last.
+fn put_varint(bytes: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        bytes.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+/// Appends a string as its length in bytes followed by its UTF-8.
+fn put_string(bytes: &mut Vec<u8>, text: &str) {
+    put_varint(bytes, text.len() as u64);
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+/// Reads the values written by `put_varint()` and `put_string()`.
+struct Reader<'a> {
+    /// The bytes not read yet.
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a byte.
+    fn byte(&mut self) -> Option<u8> {
+        let (&first, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(first)
+    }
+
+    /// Reads a variable-length integer.
+    fn varint(&mut self) -> Option<u64> {
+        let mut n = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(n);
+            }
+        }
+        None
+    }
+
+    /// Reads a string.
+    fn string(&mut self) -> Option<String> {
+        let len = usize::try_from(self.varint()?).ok()?;
+        if len > self.bytes.len() {
+            return None;
+        }
+        let (text, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        String::from_utf8(text.to_vec()).ok()
+    }
+
+    /// Reads a dot, as an index into the given devices and a count.
+    fn dot(&mut self, devices: &[String]) -> Option<Dot> {
+        let device = devices.get(usize::try_from(self.varint()?).ok()?)?;
+        let count = self.varint().filter(|&count| count > 0)?;
+        Some((device.clone(), count))
+    }
+}
+
+/// Decodes a version written by `SyncState::version()`.
+fn decode_version(bytes: &[u8]) -> Option<BTreeMap<String, u64>> {
+    let mut reader = Reader { bytes };
+    let len = reader.varint()?;
+    let mut version = BTreeMap::new();
+    for _ in 0..len {
+        version.insert(reader.string()?, reader.varint()?);
+    }
+    if reader.bytes.is_empty() { Some(version) } else { None }
+}
+
+/// Decodes the events in a delta written by `SyncState::export_delta()`.
+fn decode_delta(bytes: &[u8]) -> Option<Vec<(Dot, Event)>> {
+    let mut reader = Reader { bytes };
+    if reader.byte()? != DELTA_FORMAT {
+        return None;
+    }
+    let devices = (0..reader.varint()?).map(|_| reader.string()).collect::<Option<Vec<_>>>()?;
+    let mut events = Vec::new();
+    for _ in 0..reader.varint()? {
+        let dot = reader.dot(&devices)?;
+        let event = match reader.byte()? {
+            0 => Event::Add(reader.string()?),
+            1 => Event::Remove((0..reader.varint()?).map(|_| reader.dot(&devices))
+                               .collect::<Option<Vec<Dot>>>()?),
+            _ => return None,
+        };
+        events.push((dot, event));
+    }
+    if reader.bytes.is_empty() { Some(events) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync() {
+        let puzzle = SpellingBeeGame::new("cglorw", "i", "will\ncowgirl\ncoil\nroil\n", "");
+        let mut phone = SyncState::new(puzzle.clone(), "phone");
+        let mut laptop = SyncState::new(puzzle.clone(), "laptop");
+
+        phone.play("will");
+        phone.play("coil");
+        let first = phone.export_delta(&laptop.version());
+        assert_eq!(laptop.apply_delta(&first), Some(2));
+
+        // The phone takes back coil while the laptop finds it again, and finds cowgirl.
+        assert_eq!(phone.undo(), Some("coil".to_string()));
+        laptop.play("cowgirl");
+        assert!(!laptop.remove("roil") && laptop.remove("coil"));
+        laptop.play("coil");
+
+        let to_laptop = phone.export_delta(&laptop.version());
+        let to_phone = laptop.export_delta(&phone.version());
+        assert_eq!(phone.apply_delta(&to_phone), Some(3));
+        assert_eq!(laptop.apply_delta(&to_laptop), Some(1));
+        assert_eq!(laptop.apply_delta(&to_laptop), Some(0));
+        assert_eq!(phone.apply_delta(&first), Some(0));
+
+        assert_eq!(phone.game_ref().played_words(), laptop.game_ref().played_words());
+        assert_eq!((phone.score(), laptop.score()), (16, 16));
+        assert!(phone.game_ref().played_words().contains("coil"));
+        assert_eq!(phone.version(), laptop.version());
+        assert_eq!(phone.export_delta(&phone.version()), vec![DELTA_FORMAT, 0, 0]);
+        assert_eq!(phone.apply_delta(&first[..first.len() - 1]), None);
+
+        // After reloading, the phone carries on counting from where it was, so the laptop still
+        // sees what it finds next.
+        let mut reloaded = SyncState::from_bytes(puzzle.clone(), &phone.to_bytes()).unwrap();
+        assert_eq!(reloaded.version(), phone.version());
+        assert_eq!(reloaded.game_ref().played_words(), phone.game_ref().played_words());
+        reloaded.play("roil");
+        assert_eq!(laptop.apply_delta(&reloaded.export_delta(&laptop.version())), Some(1));
+        assert!(laptop.game_ref().played_words().contains("roil"));
+        assert_eq!(SyncState::from_bytes(puzzle, b"phone"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! This module provides async versions of the slow operations, which return `Promise`s to
+//! JavaScript and hand control back to the browser between chunks of work, so that building a game
+//! or searching for puzzles doesn't freeze the page while it runs.
+//!
+//! It is only built for WebAssembly, since it needs the browser's event loop.
+
+use lexi::{VecLexicon, wordlist};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use crate::i18n::FALLBACK_LOCALE;
+use crate::spellingbee::{GameError, SpellingBeeGame};
+use crate::search::{Interest, PuzzleSearch, SearchConfig};
+use crate::puzzle::PuzzleDef;
+use crate::utils::{fold_case, js_array};
+
+/// The number of lines of a word list worked through between breaks.
+const CHUNK_LINES: usize = 10_000;
+
+/// Gives the browser a chance to handle input and repaint before carrying on. Outside a browser
+/// window, this just waits for the next turn of the event loop.
+async fn yield_now() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let scheduled = web_sys::window()
+            .map(|window| window.set_timeout_with_callback(&resolve).is_ok())
+            .unwrap_or(false);
+        if !scheduled {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Splits a word list into pieces of `CHUNK_LINES` lines.
+fn line_chunks(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines.chunks(CHUNK_LINES).map(|chunk| chunk.join("\n")).collect()
+}
+
+/// Creates a game like `SpellingBeeGame::new()`, resolving to the game. The main word list is
+/// checked against the puzzle `CHUNK_LINES` lines at a time, with a break before each chunk, and
+/// the game is built from just the lines that are answers. The swears list is applied as a
+/// separate step, after another break.
+#[wasm_bindgen]
+pub async fn new_game_async(optional_letters: String, required_letter: String,
+                            main_words: String, swears: String) -> SpellingBeeGame
+{
+    let mut answer_lines = String::new();
+    for chunk in line_chunks(&main_words) {
+        yield_now().await;
+        let partial = SpellingBeeGame::new(&optional_letters, &required_letter, &chunk, "");
+        for line in chunk.lines() {
+            let word = line.split('\t').next().unwrap_or_default().trim();
+            if partial.is_answer(&fold_case(word, FALLBACK_LOCALE)) {
+                answer_lines.push_str(line);
+                answer_lines.push('\n');
+            }
+        }
+    }
+    if answer_lines.is_empty() {
+        // A list with no answers still has to give a game with no answers, rather than one from
+        // the fallback dictionary, which any line that isn't an answer does.
+        answer_lines = main_words.lines().find(|l| !l.trim().is_empty()).unwrap_or("").to_string();
+    }
+
+    yield_now().await;
+    let mut game = SpellingBeeGame::new(&optional_letters, &required_letter, &answer_lines, "");
+    yield_now().await;
+    game.load_swears(&swears);
+    game
+}
+
+/// Searches the given lexicons for the most interesting puzzles, using the default search
+/// settings and the given seed, and resolves to an array of their ids (see
+/// `PuzzleDef::default_id()`), most interesting first. The main word list is parsed
+/// `CHUNK_LINES` lines at a time, and the search takes a break after every chunk and every search
+/// step. Rejects with `GameError::BadWordList` if the lexicons can't be parsed.
+#[wasm_bindgen]
+pub async fn search_puzzles_async(main_words: String, swears: String,
+                                  seed: u32) -> Result<JsValue, JsError>
+{
+    let mut words = Vec::new();
+    for chunk in line_chunks(&main_words) {
+        yield_now().await;
+        let lex: VecLexicon = wordlist::parse_strings(&chunk, &swears)
+            .map_err(|_| GameError::BadWordList)?
+            .into();
+        words.extend(lex);
+    }
+    yield_now().await;
+    let search = PuzzleSearch::from_words(words);
+    let interest = Interest::default();
+    let config = SearchConfig { seed: u64::from(seed), ..SearchConfig::default() };
+
+    let mut run = search.start(&interest, &config);
+    loop {
+        yield_now().await;
+        if !run.step() {
+            break;
+        }
+    }
+
+    Ok(js_array(run.finish().iter().map(|candidate| {
+        PuzzleDef::default_id(&candidate.optional_letters, &candidate.required_letter.to_string())
+    })))
+}
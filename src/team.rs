@@ -0,0 +1,143 @@
+//! This module provides `TeamGame`, a cooperative mode where several players build one shared
+//! word list and score, with each word credited to whoever found it first.
+
+use std::collections::BTreeMap;
+use crate::spellingbee::{SpellingBeeGame, PlayOutcome, PlayResult};
+
+/// A word found by one of the team.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Find {
+    /// The player who found the word.
+    finder: String,
+
+    /// The points the word earned.
+    points: usize,
+
+    /// Whether the word is a pangram.
+    pangram: bool,
+}
+
+/// One player's contribution to a team game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Contribution {
+    /// The player's id.
+    pub player: String,
+
+    /// The words the player found, in alphabetical order.
+    pub words: Vec<String>,
+
+    /// The points earned by the player's words.
+    pub score: usize,
+
+    /// The number of pangrams the player found.
+    pub pangrams: usize,
+}
+
+/// A game played by a team sharing one word list and score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamGame {
+    /// The shared game.
+    game: SpellingBeeGame,
+
+    /// Every word found so far, with who found it.
+    finds: BTreeMap<String, Find>,
+}
+
+impl TeamGame {
+    /// Starts a team game on the given puzzle.
+    pub fn new(game: SpellingBeeGame) -> TeamGame {
+        TeamGame { game, finds: BTreeMap::new() }
+    }
+
+    /// Returns the shared game.
+    pub fn game(&self) -> &SpellingBeeGame {
+        &self.game
+    }
+
+    /// Returns the team's score.
+    pub fn score(&self) -> usize {
+        self.game.score()
+    }
+
+    /// Plays a word on behalf of the given player, crediting it to them if it's valid and new.
+    pub fn play(&mut self, player: &str, word: &str) -> PlayResult {
+        self.play_outcome(player, word).result()
+    }
+
+    /// Plays a word like `play()`, returning everything about the play, like
+    /// `SpellingBeeGame::play_outcome()`. The word is credited as the answer it was accepted as,
+    /// whatever case or spacing it was typed with.
+    pub fn play_outcome(&mut self, player: &str, word: &str) -> PlayOutcome {
+        let outcome = self.game.play_outcome(word);
+        if outcome.result() == PlayResult::Valid {
+            self.finds.insert(outcome.word(), Find {
+                finder: player.to_string(),
+                points: outcome.points(),
+                pangram: outcome.is_pangram(),
+            });
+        }
+        outcome
+    }
+
+    /// Returns the player who found the given word, if anyone has.
+    pub fn finder(&self, word: &str) -> Option<&str> {
+        self.finds.get(word).map(|f| f.finder.as_str())
+    }
+
+    /// Returns every player's contribution, highest scoring first.
+    pub fn contributions(&self) -> Vec<Contribution> {
+        let mut by_player: BTreeMap<&str, Contribution> = BTreeMap::new();
+        for (word, find) in &self.finds {
+            let c = by_player.entry(&find.finder).or_insert_with(|| Contribution {
+                player: find.finder.clone(),
+                words: Vec::new(),
+                score: 0,
+                pangrams: 0,
+            });
+            c.words.push(word.clone());
+            c.score += find.points;
+            if find.pangram {
+                c.pangrams += 1;
+            }
+        }
+
+        let mut contributions: Vec<Contribution> = by_player.into_values().collect();
+        contributions.sort_by_key(|c| std::cmp::Reverse(c.score));
+        contributions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team() {
+        let game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let mut team = TeamGame::new(game);
+        assert_eq!(team.play("ana", " Will"), PlayResult::Valid);
+        assert_eq!(team.play("ben", "will"), PlayResult::AlreadyPlayed);
+        assert_eq!(team.play("ben", "cowgirl"), PlayResult::Valid);
+        assert_eq!(team.play("ana", "coil"), PlayResult::Valid);
+        assert_eq!(team.play("ana", "wilco"), PlayResult::InvalidWord);
+
+        assert_eq!(team.finder("will"), Some("ana"));
+        assert_eq!(team.finder("cowgirl"), Some("ben"));
+        assert_eq!(team.finder("wilco"), None);
+        assert_eq!(team.score(), 16);
+        assert_eq!(team.contributions(), [
+            Contribution {
+                player: "ben".to_string(),
+                words: vec!["cowgirl".to_string()],
+                score: 14,
+                pangrams: 1,
+            },
+            Contribution {
+                player: "ana".to_string(),
+                words: vec!["coil".to_string(), "will".to_string()],
+                score: 2,
+                pangrams: 0,
+            },
+        ]);
+    }
+}
@@ -0,0 +1,139 @@
+//! This module provides `Transliteration`, a table mapping letters to the ASCII a player would type
+//! for them on a keyboard without them, so that typing "cafe" can match the answer "café" in
+//! non-English games.
+//!
+//! A table can also strip the accents from letters it doesn't map, in any language, so that
+//! inputs and answers that only differ in their accents match either way: "cafe" for "café", and
+//! "café" for "cafe".
+
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The built-in tables, by language, as letters followed by their ASCII spellings.
+const BUILT_IN: &[(&str, &[(char, &str)])] = &[
+    ("de", &[('ä', "ae"), ('ö', "oe"), ('ü', "ue"), ('ß', "ss")]),
+    ("es", &[('á', "a"), ('é', "e"), ('í', "i"), ('ó', "o"), ('ú', "u"), ('ü', "u"),
+             ('ñ', "n")]),
+    ("fr", &[('à', "a"), ('â', "a"), ('æ', "ae"), ('ç', "c"), ('é', "e"), ('è', "e"),
+             ('ê', "e"), ('ë', "e"), ('î', "i"), ('ï', "i"), ('ô', "o"), ('œ', "oe"),
+             ('ù', "u"), ('û', "u"), ('ü', "u"), ('ÿ', "y")]),
+    ("it", &[('à', "a"), ('è', "e"), ('é', "e"), ('ì', "i"), ('ò', "o"), ('ù', "u")]),
+    ("pt", &[('á', "a"), ('â', "a"), ('ã', "a"), ('à', "a"), ('ç', "c"), ('é', "e"),
+             ('ê', "e"), ('í', "i"), ('ó', "o"), ('ô', "o"), ('õ', "o"), ('ú', "u")]),
+];
+
+/// A mapping from letters to the ASCII spellings players may type instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transliteration {
+    /// The ASCII spelling of each mapped letter.
+    map: BTreeMap<char, String>,
+
+    /// Whether letters that aren't mapped are spelled without their accents.
+    strip_accents: bool,
+}
+
+impl Transliteration {
+    /// Returns the built-in table for the given language code, like `fr` or `de-CH`, if there
+    /// is one.
+    pub fn for_language(language: &str) -> Option<Transliteration> {
+        let language = language.split(['-', '_']).next()?.to_lowercase();
+        BUILT_IN.iter().find(|(l, _)| *l == language).map(|(_, table)| Transliteration {
+            map: table.iter().map(|(c, s)| (*c, s.to_string())).collect(),
+            strip_accents: false,
+        })
+    }
+
+    /// Parses a table with one letter, a tab, and its ASCII spelling per line. Lines that don't
+    /// fit that format are skipped.
+    pub fn from_table(table: &str) -> Transliteration {
+        let map = table.lines().filter_map(|line| {
+            let (letter, spelling) = line.split_once('\t')?;
+            let mut chars = letter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !spelling.is_empty() => Some((c, spelling.to_string())),
+                _ => None,
+            }
+        }).collect();
+        Transliteration { map, strip_accents: false }
+    }
+
+    /// Returns a table that maps no letters and strips the accents from all of them.
+    pub fn accent_folding() -> Transliteration {
+        Transliteration { map: BTreeMap::new(), strip_accents: true }
+    }
+
+    /// Returns `true` if letters that aren't mapped are spelled without their accents.
+    pub fn strips_accents(&self) -> bool {
+        self.strip_accents
+    }
+
+    /// Sets whether letters that aren't mapped are spelled without their accents.
+    pub fn set_strip_accents(&mut self, strip: bool) {
+        self.strip_accents = strip;
+    }
+
+    /// Returns `true` if the table doesn't change any spelling.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty() && !self.strip_accents
+    }
+
+    /// Spells a word using only the ASCII spellings of its mapped letters, and without the
+    /// accents of the others if accents are stripped.
+    pub fn fold(&self, word: &str) -> String {
+        word.chars().fold(String::new(), |mut folded, c| {
+            match self.map.get(&c) {
+                Some(spelling) => folded.push_str(spelling),
+                None if self.strip_accents => folded.push_str(&without_accents(&c.to_string())),
+                None => folded.push(c),
+            }
+            folded
+        })
+    }
+
+    /// Returns `true` if `typed` can be typed for `letter`: it's the letter itself, part of its
+    /// ASCII spelling, or spelled the same as it.
+    pub fn can_type(&self, letter: &str, typed: &str) -> bool {
+        if letter == typed || self.fold(letter) == self.fold(typed) {
+            return true;
+        }
+
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => self.map.get(&c).is_some_and(|s| s.contains(typed)),
+            _ => false,
+        }
+    }
+}
+
+/// Spells the given text without its accents, so that é becomes e but ß and ø stay as they are.
+pub fn without_accents(text: &str) -> String {
+    text.nfd().filter(|&c| !is_combining_mark(c)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        let fr = Transliteration::for_language("fr-CA").unwrap();
+        assert_eq!(fr.fold("café"), "cafe");
+        assert_eq!(fr.fold("cœur"), "coeur");
+        assert!(fr.can_type("é", "e"));
+        assert!(!fr.can_type("é", "a"));
+
+        let de = Transliteration::from_table("ß\tss\nä\tae\nbad line\n");
+        assert_eq!(de.fold("straße"), "strasse");
+        assert_eq!(Transliteration::for_language("xx"), None);
+
+        let accents = Transliteration::accent_folding();
+        assert_eq!(accents.fold("niño"), "nino");
+        assert!(accents.can_type("e", "é") && accents.can_type("é", "e"));
+        assert!(fr.can_type("e", "é"));
+    }
+}
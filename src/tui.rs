@@ -0,0 +1,206 @@
+//! This module provides a full-screen terminal frontend, with the `tui` feature. It draws the
+//! letters as a honeycomb with the required letter in the middle, the words found so far beside
+//! it, and a bar showing progress through the ranks.
+//!
+//! Letters typed go into the input line; Enter plays it, Backspace deletes a letter, Space
+//! shuffles the hive, and Esc quits.
+
+use std::io::{self, Stdout};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
+                                   LeaveAlternateScreen};
+use ratatui::layout::{Alignment, Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph};
+use ratatui::{Frame, Terminal};
+use crate::i18n::{Messages, FALLBACK_LOCALE};
+use crate::spellingbee::SpellingBeeGame;
+use crate::utils::letters;
+
+/// The state of the screen besides the game itself.
+struct App<'a> {
+    /// The game being played.
+    game: &'a mut SpellingBeeGame,
+
+    /// The seed the hive is shuffled with (see `SpellingBeeGame::hive_layout()`).
+    seed: u32,
+
+    /// The word being typed.
+    input: String,
+
+    /// What happened to the last word played.
+    message: String,
+
+    /// The messages shown for plays.
+    messages: Messages,
+}
+
+impl App<'_> {
+    /// Plays the word being typed and clears it.
+    fn play(&mut self) {
+        let outcome = self.game.play_outcome(&self.input);
+        self.message = self.messages.play_result(outcome.result(), self.game);
+        if outcome.points() > 0 {
+            self.message.push_str(&format!(" +{}", outcome.points()));
+        }
+        if outcome.is_pangram() {
+            self.message.push_str(" Pangram!");
+        }
+        self.input.clear();
+    }
+}
+
+/// Runs the full-screen frontend on the terminal until the player quits, leaving the game with
+/// whatever progress they made.
+pub fn run(game: &mut SpellingBeeGame) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut app = App {
+        game,
+        seed: 0,
+        input: String::new(),
+        message: String::new(),
+        messages: Messages::new(FALLBACK_LOCALE),
+    };
+    let result = event_loop(&mut terminal, &mut app);
+
+    // Put the terminal back the way it was even if drawing failed.
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+/// Draws the screen and handles keys until the player quits.
+fn event_loop(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Enter if !app.input.is_empty() => app.play(),
+            KeyCode::Backspace => {
+                app.input.pop();
+            }
+            KeyCode::Char(' ') => app.seed = app.seed.wrapping_add(1),
+            KeyCode::Char(c) => app.input.push(c),
+            _ => {}
+        }
+    }
+}
+
+/// Draws the hive, the input, the last message, and the rank bar on the left, and the words found
+/// so far on the right.
+fn draw(frame: &mut Frame, app: &App) {
+    let [left, right] = Layout::horizontal([Constraint::Min(30), Constraint::Length(24)])
+        .areas(frame.area());
+    let [hive, input, message, rank] = Layout::vertical([
+        Constraint::Length(7),
+        Constraint::Length(3),
+        Constraint::Length(1),
+        Constraint::Length(3),
+    ]).areas(left);
+
+    frame.render_widget(Paragraph::new(hive_lines(app)).alignment(Alignment::Center), hive);
+    frame.render_widget(Paragraph::new(app.input.to_uppercase())
+                        .alignment(Alignment::Center)
+                        .block(Block::default().borders(Borders::ALL)), input);
+    frame.render_widget(Paragraph::new(app.message.as_str()).alignment(Alignment::Center),
+                        message);
+
+    let game = &app.game;
+    let rank_name = game.rank_name_in(&app.messages);
+    let ratio = if game.max_score() == 0 {
+        1.0
+    } else {
+        game.score() as f64 / game.max_score() as f64
+    };
+    frame.render_widget(Gauge::default()
+                        .block(Block::default().borders(Borders::ALL).title(rank_name))
+                        .gauge_style(Style::default().fg(Color::Yellow))
+                        .ratio(ratio)
+                        .label(format!("{} / {}", game.score(), game.max_score())), rank);
+
+    let found: Vec<ListItem> = game.played_words().iter().map(ListItem::new).collect();
+    let title = format!("{} of {} words", found.len(), game.answers().len());
+    frame.render_widget(List::new(found).block(Block::default().borders(Borders::ALL)
+                                               .title(title)), right);
+}
+
+/// Lays out the letters as a honeycomb: the letters around the hive go clockwise from the top
+/// left, with the required letter in the middle.
+fn hive_lines(app: &App) -> Vec<Line<'static>> {
+    let ring = app.game.hive_layout(app.seed).to_uppercase();
+    let ring: Vec<String> = letters(&ring).into_iter().map(str::to_string).collect();
+    let outer = Style::default().add_modifier(Modifier::BOLD);
+    let cell = |i: usize| {
+        Span::styled(format!(" {} ", ring.get(i).map_or(" ", String::as_str)), outer)
+    };
+    let center = Span::styled(format!(" {} ", app.game.required_letter().to_uppercase()),
+                              outer.fg(Color::Black).bg(Color::Yellow));
+    let gap = || Span::raw("   ");
+
+    vec![
+        Line::from(""),
+        Line::from(vec![cell(0), gap(), cell(1)]),
+        Line::from(""),
+        Line::from(vec![cell(5), gap(), center, gap(), cell(2)]),
+        Line::from(""),
+        Line::from(vec![cell(4), gap(), cell(3)]),
+        Line::from(""),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a screen for the game with nothing typed yet.
+    fn app(game: &mut SpellingBeeGame) -> App<'_> {
+        App {
+            game,
+            seed: 0,
+            input: String::new(),
+            message: String::new(),
+            messages: Messages::new(FALLBACK_LOCALE),
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\n");
+        let mut app = app(&mut game);
+        app.input.push_str("cowgirl");
+        app.play();
+        assert_eq!(app.message, "Nice! +14 Pangram!");
+        assert!(app.input.is_empty());
+        app.input.push_str("wig");
+        app.play();
+        assert_eq!(app.message, "Words need to be at least 4 letters long");
+        assert_eq!(game.score(), 14);
+    }
+
+    #[test]
+    fn test_hive_lines() {
+        let mut game = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\n");
+        let app = app(&mut game);
+        let text = |line: &Line| line.spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+        let lines: Vec<String> = hive_lines(&app).iter().map(text).collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[3].split_whitespace().nth(1), Some("I"));
+        let mut ring: Vec<&str> = lines.iter().flat_map(|l| l.split_whitespace()).collect();
+        ring.sort_unstable();
+        assert_eq!(ring, ["C", "G", "I", "L", "O", "R", "W"]);
+    }
+}
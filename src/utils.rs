@@ -1,4 +1,6 @@
-use web_sys;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use wasm_bindgen::JsValue;
 
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -11,9 +13,207 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
+// A macro to provide `println!(..)`-style syntax for `console.log` logging, for debugging.
+#[cfg(target_arch = "wasm32")]
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
     }
 }
+
+// Outside the browser there's no console, so log to standard error instead.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unused_macros)]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        eprintln!( $( $t )* );
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch, like
+/// JavaScript's `Date.now()`.
+#[cfg(target_arch = "wasm32")]
+pub fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Returns the current time in milliseconds since the Unix epoch, from the
+/// system clock, or 0 if the clock is set before the epoch.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_millis() as f64)
+}
+
+/// Collects strings into a JavaScript array of strings.
+pub fn js_array<I, S>(strings: I) -> JsValue
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+{
+    let array = js_sys::Array::new();
+    for s in strings {
+        array.push(&JsValue::from(s.as_ref()));
+    }
+    array.into()
+}
+
+/// Splits a word into its letters. Letters are extended grapheme clusters, so
+/// a letter with combining accents, or one written with several code points in
+/// a non-Latin script, counts once.
+pub fn letters(word: &str) -> Vec<&str> {
+    word.graphemes(true).collect()
+}
+
+/// Returns the number of letters in a word, counting grapheme clusters. See
+/// `letters()`.
+pub fn letter_count(word: &str) -> usize {
+    word.graphemes(true).count()
+}
+
+/// The languages that write dotted and dotless i as separate letters, so that
+/// `I` lowercases to `ı` and `İ` to `i`.
+const DOTLESS_I_LANGUAGES: &[&str] = &["tr", "az"];
+
+/// Lowercases text for comparison using the case rules of the given locale,
+/// such as `en` or `tr-CY`. This covers all of Unicode, not just ASCII, and
+/// handles the Turkish and Azerbaijani i's, which the default rules get wrong.
+///
+/// The text is put in Unicode normalization form C first, so that a letter
+/// typed as a base letter and a combining accent matches the same letter
+/// written as one code point: "cafe\u{301}" folds to the same "café" as
+/// "caf\u{e9}" does.
+pub fn fold_case(text: &str, locale: &str) -> String {
+    let text = &text.nfc().collect::<String>();
+    let language = locale.split(['-', '_']).next().unwrap_or(locale).to_lowercase();
+    if !DOTLESS_I_LANGUAGES.contains(&language.as_str()) {
+        return text.to_lowercase();
+    }
+
+    let mut folded = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // A decomposed capital dotted I is an I followed by a combining
+            // dot above.
+            'I' if chars.peek() == Some(&'\u{307}') => {
+                chars.next();
+                folded.push('i');
+            }
+            'I' => folded.push('ı'),
+            'İ' => folded.push('i'),
+            c => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded
+}
+
+/// Returns `true` if the grapheme cluster is a letter: it starts with an
+/// alphabetic character, and anything after that is also alphabetic (like many
+/// vowel signs) or a combining mark.
+pub fn is_letter(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    chars.next().is_some_and(char::is_alphabetic) &&
+        chars.all(|c| c.is_alphabetic() || is_combining_mark(c))
+}
+
+/// Returns `true` if the character is a combining mark, such as the accent in
+/// a decomposed "é". This only covers the common combining diacritics blocks.
+pub fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' |
+             '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Returns `true` if the text is a `YYYY-MM-DD` date with a month from 1 to
+/// 12 and a day from 1 to 31.
+pub fn is_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    match parts.as_slice() {
+        [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+            let number = |s: &str| -> Option<u32> {
+                if s.bytes().all(|b| b.is_ascii_digit()) { s.parse().ok() } else { None }
+            };
+            matches!((number(year), number(month), number(day)),
+                     (Some(_), Some(1..=12), Some(1..=31)))
+        }
+        _ => false,
+    }
+}
+
+/// Hashes bytes with 64-bit FNV-1a. Unlike the standard library's hashers,
+/// this is stable across platforms and versions, so hashes can be saved or
+/// used as seeds that every client agrees on.
+pub fn fnv1a<I: IntoIterator<Item = u8>>(bytes: I) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.into_iter()
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// Returns a bitmask of the letters in `word`, with bit 0 for 'a' through bit 25 for 'z', or `None`
+/// if the word has anything other than lowercase ASCII letters.
+pub fn letter_mask(word: &str) -> Option<u32> {
+    word.chars().try_fold(0, |mask, c| {
+        if c.is_ascii_lowercase() {
+            Some(mask | letter_bit(c))
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the bit for a single lowercase ASCII letter in a mask from `letter_mask()`, or 0 for
+/// anything else.
+pub fn letter_bit(c: char) -> u32 {
+    if c.is_ascii_lowercase() {
+        1 << (c as u32 - 'a' as u32)
+    } else {
+        0
+    }
+}
+
+/// A small seedable pseudorandom number generator (SplitMix64). This is not suitable for anything
+/// security-related, but it's deterministic across platforms, which is what puzzle generation
+/// needs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new generator from the given seed.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Returns the next pseudorandom 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudorandom index in `0..n`, or 0 if `n` is 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        match n {
+            0 => 0,
+            n => (self.next_u64() % n as u64) as usize,
+        }
+    }
+
+    /// Returns a pseudorandom number in `0.0..1.0`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Shuffles the items in place with a Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! This module provides `verify()`, which checks a submitted result against the play log it came
+//! with. The log is replayed against a fresh copy of the puzzle, and the claimed words, score, and
+//! rank have to be exactly what the replay gives, so a server can reject results that no sequence
+//! of plays could have reached.
+//!
+//! A result can also claim how long it took, for leaderboards that race the clock. Then every play
+//! in the log has to have its time (see `OpLog::play_at()`), the times can't go backwards, and the
+//! claimed time has to be the one the replay's clock ends on. `timing()` gives that time and the
+//! times each rank was reached at, for showing alongside a result. A log's times are only as good
+//! as whoever recorded them, so a server that wants times it can trust should record plays
+//! itself, as the HTTP API does, rather than take a client's log.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use crate::oplog::{Op, OpLog};
+use crate::rank::Rank;
+use crate::spellingbee::SpellingBeeGame;
+
+/// A result submitted for checking.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Claim {
+    /// The words the player says they found.
+    pub words: BTreeSet<String>,
+
+    /// The score the player says they reached.
+    pub score: usize,
+
+    /// The rank the player says they reached, if they claim one.
+    pub rank: Option<Rank>,
+
+    /// The milliseconds from the start of the game to the last word found that the player says
+    /// they took, if they claim a time.
+    pub elapsed: Option<u64>,
+}
+
+/// How long a log took, as `timing()` gives it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Timing {
+    /// The milliseconds from the first play to the last word found.
+    pub elapsed: u64,
+
+    /// Each rank above the lowest that was reached, with the milliseconds from the first play it
+    /// was reached at (see `SpellingBeeGame::rank_splits()`).
+    pub splits: Vec<(Rank, u64)>,
+}
+
+/// The reasons a claim can be rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Rejection {
+    /// The log couldn't be decoded.
+    BadLog,
+
+    /// Some claimed words aren't answers to the puzzle at all.
+    NotAnswers(Vec<String>),
+
+    /// The claimed words aren't the ones the log finds: `missing` were found by the log but not
+    /// claimed, and `extra` were claimed but not found by the log.
+    WrongWords { missing: Vec<String>, extra: Vec<String> },
+
+    /// The claimed score isn't the one the log reaches.
+    WrongScore { claimed: usize, actual: usize },
+
+    /// The claimed rank isn't the one the log reaches.
+    WrongRank { claimed: Rank, actual: Rank },
+
+    /// A time was claimed, but the log has plays without times, or no plays at all.
+    Untimed,
+
+    /// The play at the given index in the log is timed before the one before it.
+    TimeWentBackwards { op: usize },
+
+    /// The claimed time isn't the one the log takes.
+    WrongTime { claimed: u64, actual: u64 },
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejection::BadLog => write!(f, "the play log couldn't be decoded"),
+            Rejection::NotAnswers(words) => {
+                write!(f, "some claimed words aren't answers: {}", words.join(", "))
+            }
+            Rejection::WrongWords { missing, extra } => {
+                write!(f, "the claimed words don't match the log ({} missing, {} extra)",
+                       missing.len(), extra.len())
+            }
+            Rejection::WrongScore { claimed, actual } => {
+                write!(f, "claimed a score of {}, but the log reaches {}", claimed, actual)
+            }
+            Rejection::WrongRank { claimed, actual } => {
+                write!(f, "claimed {}, but the log reaches {}", claimed.name(), actual.name())
+            }
+            Rejection::Untimed => write!(f, "claimed a time, but the log's plays aren't timed"),
+            Rejection::TimeWentBackwards { op } => {
+                write!(f, "op {} of the log is timed before the play before it", op)
+            }
+            Rejection::WrongTime { claimed, actual } => {
+                write!(f, "claimed a time of {} ms, but the log takes {} ms", claimed, actual)
+            }
+        }
+    }
+}
+
+impl Error for Rejection {}
+
+/// Checks a claim against the log it came with by replaying the log against `puzzle`, which
+/// should be a fresh game. Returns the replayed game if the claim holds up.
+pub fn verify(puzzle: &SpellingBeeGame, log: &OpLog,
+              claim: &Claim) -> Result<SpellingBeeGame, Rejection>
+{
+    let not_answers: Vec<String> = claim.words.iter()
+        .filter(|w| !puzzle.is_answer(w))
+        .cloned()
+        .collect();
+    if !not_answers.is_empty() {
+        return Err(Rejection::NotAnswers(not_answers));
+    }
+
+    let game = log.replay(puzzle).game;
+    let found = game.played_words();
+    if !found.iter().eq(claim.words.iter().map(String::as_str)) {
+        return Err(Rejection::WrongWords {
+            missing: found.iter().filter(|w| !claim.words.contains(*w)).map(String::from).collect(),
+            extra: claim.words.iter().filter(|w| !found.contains(w)).cloned().collect(),
+        });
+    }
+
+    if claim.score != game.score() {
+        return Err(Rejection::WrongScore { claimed: claim.score, actual: game.score() });
+    }
+
+    if let Some(rank) = claim.rank.filter(|&rank| rank != game.rank()) {
+        return Err(Rejection::WrongRank { claimed: rank, actual: game.rank() });
+    }
+
+    match claim.elapsed {
+        Some(claimed) => match timing(puzzle, log)?.elapsed {
+            actual if actual != claimed => Err(Rejection::WrongTime { claimed, actual }),
+            _ => Ok(game),
+        },
+        None => Ok(game),
+    }
+}
+
+/// Returns how long a log took by replaying it against `puzzle`, which should be a fresh game
+/// whose clock hasn't started. Fails if any play in the log doesn't have a time, if there aren't
+/// any plays, or if the times go backwards.
+pub fn timing(puzzle: &SpellingBeeGame, log: &OpLog) -> Result<Timing, Rejection> {
+    let mut last = None;
+    for (i, op) in log.ops().iter().enumerate() {
+        match *op {
+            Op::Play(_) => return Err(Rejection::Untimed),
+            Op::PlayAt(_, at) if last.is_some_and(|last| at < last) => {
+                return Err(Rejection::TimeWentBackwards { op: i });
+            }
+            Op::PlayAt(_, at) => last = Some(at),
+            Op::GiveUp => break,
+            Op::Undo | Op::Hint(_) => {}
+        }
+    }
+
+    let game = log.replay(puzzle).game;
+    match game.elapsed_time() {
+        Some(elapsed) if last.is_some() => {
+            Ok(Timing { elapsed: elapsed as u64, splits: game.rank_splits() })
+        }
+        _ => Err(Rejection::Untimed),
+    }
+}
+
+/// Checks a claim like `verify()`, with the log encoded as by `OpLog::encode()`.
+pub fn verify_encoded(puzzle: &SpellingBeeGame, log: &str,
+                      claim: &Claim) -> Result<SpellingBeeGame, Rejection>
+{
+    let log = OpLog::decode(log).ok_or(Rejection::BadLog)?;
+    verify(puzzle, &log, claim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let puzzle = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let log = "play\twill\nplay\tcoil\nundo\nplay\tcowgirl\n";
+        let claim = |words: &[&str], score, rank| Claim {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            score,
+            rank,
+            elapsed: None,
+        };
+
+        assert!(verify_encoded(&puzzle, log, &claim(&["cowgirl", "will"], 15, None)).is_ok());
+        assert_eq!(verify_encoded(&puzzle, log, &claim(&["cowgirl", "will"], 16, None)),
+                   Err(Rejection::WrongScore { claimed: 16, actual: 15 }));
+        assert_eq!(verify_encoded(&puzzle, log, &claim(&["will"], 1, None)),
+                   Err(Rejection::WrongWords { missing: vec!["cowgirl".to_string()],
+                                               extra: vec![] }));
+        assert_eq!(verify_encoded(&puzzle, log, &claim(&["roll"], 1, None)),
+                   Err(Rejection::NotAnswers(vec!["roll".to_string()])));
+        assert_eq!(verify_encoded(&puzzle, log,
+                                  &claim(&["cowgirl", "will"], 15, Some(Rank::QueenBee))),
+                   Err(Rejection::WrongRank { claimed: Rank::QueenBee, actual: Rank::Genius }));
+        assert_eq!(verify_encoded(&puzzle, "jump\n", &claim(&[], 0, None)),
+                   Err(Rejection::BadLog));
+    }
+
+    #[test]
+    fn test_timing() {
+        let puzzle = SpellingBeeGame::from_answers("cglorw", "i", "will\ncowgirl\ncoil\n");
+        let log = OpLog::decode("play\tcoil\t1000\nplay\tcowl\t3000\n\
+                                 play\tcowgirl\t9000\nplay\twill\t12000\n").unwrap();
+        let timing = timing(&puzzle, &log).unwrap();
+        assert_eq!(timing.elapsed, 11_000);
+        assert_eq!(timing.splits, vec![
+            (Rank::MovingUp, 0), (Rank::Good, 0), (Rank::Solid, 8_000), (Rank::Nice, 8_000),
+            (Rank::Great, 8_000), (Rank::Amazing, 8_000), (Rank::Genius, 8_000),
+            (Rank::QueenBee, 11_000),
+        ]);
+
+        let claim = |elapsed| Claim {
+            words: ["coil", "cowgirl", "will"].iter().map(|w| w.to_string()).collect(),
+            score: 16,
+            rank: None,
+            elapsed: Some(elapsed),
+        };
+        assert!(verify(&puzzle, &log, &claim(11_000)).is_ok());
+        assert_eq!(verify(&puzzle, &log, &claim(5_000)),
+                   Err(Rejection::WrongTime { claimed: 5_000, actual: 11_000 }));
+
+        let untimed = OpLog::decode("play\tcoil\nplay\tcowgirl\t9000\nplay\twill\t12000\n");
+        assert_eq!(verify(&puzzle, &untimed.unwrap(), &claim(11_000)), Err(Rejection::Untimed));
+        let backwards = OpLog::decode("play\tcoil\t1000\nplay\tcowgirl\t9000\n\
+                                       play\twill\t2000\n").unwrap();
+        assert_eq!(verify(&puzzle, &backwards, &claim(1_000)),
+                   Err(Rejection::TimeWentBackwards { op: 2 }));
+    }
+}
@@ -0,0 +1,104 @@
+//! This module provides `WordData`, the optional per-word data a game can use beyond its answer
+//! list: definitions and frequency counts. Neither is needed to start a game, so frontends can
+//! load them after construction, once the core dictionary is up and running.
+
+use std::collections::BTreeMap;
+use std::mem::size_of;
+use crate::diagnostics::string_bytes;
+use crate::utils::fold_case;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Definitions and frequency counts for some words, all lowercased with the game's locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WordData {
+    /// The definition of each word.
+    definitions: BTreeMap<String, String>,
+
+    /// How often each word occurs in some reference corpus.
+    frequencies: BTreeMap<String, u64>,
+}
+
+impl WordData {
+    /// Creates an empty set of word data.
+    pub fn new() -> WordData {
+        WordData::default()
+    }
+
+    /// Adds or replaces definitions from lines of a word, a tab, and its definition. Lines that
+    /// don't fit that format are skipped. Returns the number of definitions loaded.
+    pub fn load_definitions(&mut self, definitions: &str, locale: &str) -> usize {
+        let entries: Vec<(String, String)> = definitions.lines().filter_map(|line| {
+            let (word, definition) = line.split_once('\t')?;
+            let word = word.trim();
+            if word.is_empty() || definition.trim().is_empty() {
+                return None;
+            }
+            Some((fold_case(word, locale), definition.trim().to_string()))
+        }).collect();
+
+        let count = entries.len();
+        self.definitions.extend(entries);
+        count
+    }
+
+    /// Adds or replaces frequencies from lines of a word, a tab, and its count. Lines that don't
+    /// fit that format are skipped. Returns the number of frequencies loaded.
+    pub fn load_frequencies(&mut self, frequencies: &str, locale: &str) -> usize {
+        let entries: Vec<(String, u64)> = frequencies.lines().filter_map(|line| {
+            let (word, count) = line.split_once('\t')?;
+            let word = word.trim();
+            if word.is_empty() {
+                return None;
+            }
+            Some((fold_case(word, locale), count.trim().parse().ok()?))
+        }).collect();
+
+        let count = entries.len();
+        self.frequencies.extend(entries);
+        count
+    }
+
+    /// Returns the definition of a word, if one has been loaded.
+    pub fn definition(&self, word: &str) -> Option<&str> {
+        self.definitions.get(word).map(String::as_str)
+    }
+
+    /// Returns the frequency count of a word, if one has been loaded.
+    pub fn frequency(&self, word: &str) -> Option<u64> {
+        self.frequencies.get(word).copied()
+    }
+
+    /// Returns the estimated bytes taken up by the definitions and frequencies (see
+    /// `diagnostics::string_bytes()`).
+    pub fn heap_bytes(&self) -> usize {
+        string_bytes(self.definitions.keys().chain(self.definitions.values())) +
+            string_bytes(self.frequencies.keys()) + self.frequencies.len() * size_of::<u64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_data() {
+        let mut data = WordData::new();
+        assert_eq!(data.heap_bytes(), 0);
+        let definitions = "Will\tTo intend\ncoil\t \nno tab\n\tA blank word\n";
+        assert_eq!(data.load_definitions(definitions, "en"), 1);
+        assert_eq!(data.definition("will"), Some("To intend"));
+        assert_eq!(data.definition("coil"), None);
+
+        assert_eq!(data.load_frequencies("will\t900\ncoil\tmany\nCOIL\t 40\n", "en"), 2);
+        assert_eq!((data.frequency("will"), data.frequency("coil")), (Some(900), Some(40)));
+        assert_eq!(data.load_frequencies("will\t12\n", "en"), 1);
+        assert_eq!(data.frequency("will"), Some(12));
+        assert!(data.heap_bytes() > 0);
+
+        let mut turkish = WordData::new();
+        turkish.load_frequencies("İYİ\t5\n", "tr");
+        assert_eq!(turkish.frequency("iyi"), Some(5));
+    }
+}
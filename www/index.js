@@ -3,13 +3,14 @@ let $ = require("jquery");
 let progressBar = require('progressbar.js')
 
 import mw_txt from './2of12inf.txt';
-import swears_txt from './swears.txt';
 
 const VALID = 0;
 const ALREADY_PLAYED = 1;
 const INVALID_WORD = 2;
 const INVALID_LENGTH = 3;
 const INVALID_LETTERS = 4;
+const INVALID_PATH = 5;
+const INVALID_CHARACTER = 6;
 
 // from
 // https://dev.to/ananyaneogi/create-a-dark-light-mode-switch-with-css-variables-34l8
@@ -35,18 +36,25 @@ if (currentTheme) {
 
 async function main() {
     const main_words = mw_txt;
-    const swears = swears_txt;
 
     const url_params = new URLSearchParams(window.location.search);
     const center_letter = url_params.get('center').toLowerCase();
     const other_letters = url_params.get('others').toLowerCase();
 
     const game = SpellingBeeGame.new(other_letters, center_letter, main_words,
-                                     swears);
+                                     "");
 
-    const max_score = game.max_score();
+    let max_score = game.max_score();
     $("#score-bar").attr("aria-valuemax", max_score);
 
+    // The swears list isn't needed to start playing, so it's loaded after the
+    // game is up.
+    import('./swears.txt').then(({ default: swears }) => {
+        game.load_swears(swears);
+        max_score = game.max_score();
+        $("#score-bar").attr("aria-valuemax", max_score);
+    });
+
     let curr_word = "";
     let played_words = [];
     $("#curr-word").empty();
@@ -123,6 +131,8 @@ async function main() {
             makeAlert("Words need to be at least 4 letters long", "error");
         } else if (result === INVALID_LETTERS) {
             makeAlert("Words must contain the center letter", "error");
+        } else if (result === INVALID_CHARACTER) {
+            makeAlert("'" + game.invalid_character(curr_word) + "' isn't a letter", "error");
         } else {
             makeAlert("A strange error occurred", "error");
             curr_word = "";